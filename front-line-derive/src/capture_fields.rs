@@ -1,72 +1,489 @@
+use crate::path::Path;
+use crate::router_context::RouterContext;
 use proc_macro2::TokenStream;
-use quote::{format_ident, quote};
-use syn::{Fields, Ident, Lifetime, Type, Variant};
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{Attribute, Fields, GenericArgument, Ident, Lifetime, PathArguments, Type, Variant};
 
 pub(crate) struct CaptureFields<'a> {
-    fields: Vec<(&'a Ident, &'a Type)>,
+    fields: Vec<CaptureField<'a>>,
+    positional: bool,
+    /// The field marked `#[method]`, which is bound to the parsed method rather than captured.
+    method: Option<MethodField<'a>>,
+}
+
+/// A `#[method]` field, with its position among every field of the variant.
+struct MethodField<'a> {
+    ident: Option<&'a Ident>,
+    position: usize,
+}
+
+struct CaptureField<'a> {
+    ident: Option<&'a Ident>,
+    variable: Option<Ident>,
+    ty: &'a Type,
+    decode: bool,
+    parse_with: Option<syn::Path>,
+    /// Whether the field is captured by a `{**name}` variable, and parsed with `FromRouteRest`.
+    rest: bool,
+    /// Whether an empty capture fails to match, per `#[non_empty]`.
+    non_empty: bool,
 }
 
 impl<'a> CaptureFields<'a> {
-    pub(crate) fn new(variant: &'a Variant) -> Self {
-        let fields: Vec<_> = match &variant.fields {
-            Fields::Named(fields) => fields
-                .named
-                .iter()
-                .filter_map(|f| f.ident.as_ref().map(|i| (i, &f.ty)))
-                .collect(),
-            _ => vec![],
+    pub(crate) fn new(variant: &'a Variant) -> syn::Result<Self> {
+        let decode_all = has_decode_attr(&variant.attrs);
+        let non_empty_all = has_non_empty_attr(&variant.attrs);
+        let (fields, positional): (Vec<_>, _) = match &variant.fields {
+            Fields::Named(fields) => (fields.named.iter().collect(), false),
+            Fields::Unnamed(fields) => (fields.unnamed.iter().collect(), true),
+            Fields::Unit => (vec![], false),
         };
-        Self { fields }
+        let mut method: Option<MethodField> = None;
+        for (position, f) in fields.iter().enumerate() {
+            let Some(attr) = f.attrs.iter().find(|attr| attr.path().is_ident("method")) else {
+                continue;
+            };
+            if method.is_some() {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    format!("{} can only have one #[method] field", variant.ident),
+                ));
+            }
+            let conflicting = f.attrs.iter().find(|attr| {
+                ["from", "decode", "parse_with", "non_empty"]
+                    .iter()
+                    .any(|name| attr.path().is_ident(name))
+            });
+            if let Some(conflicting) = conflicting {
+                return Err(syn::Error::new_spanned(
+                    conflicting,
+                    "a #[method] field isn't captured, so it can't be renamed, decoded or parsed",
+                ));
+            }
+            method = Some(MethodField {
+                ident: f.ident.as_ref(),
+                position,
+            });
+        }
+        let method_position = method.as_ref().map(|method| method.position);
+        let fields: Vec<CaptureField> = fields
+            .into_iter()
+            .enumerate()
+            .filter(|(position, _)| Some(*position) != method_position)
+            .map(|(_, f)| {
+                let from = from_attr(&f.attrs)?;
+                if let (Some(_), None) = (&from, &f.ident) {
+                    return Err(syn::Error::new_spanned(
+                        f,
+                        "from can only be used on named fields",
+                    ));
+                }
+                Ok(CaptureField {
+                    ident: f.ident.as_ref(),
+                    variable: from.or_else(|| f.ident.clone()),
+                    ty: &f.ty,
+                    decode: decode_all || has_decode_attr(&f.attrs),
+                    parse_with: parse_with_attr(&f.attrs)?,
+                    rest: false,
+                    non_empty: non_empty_all || has_non_empty_attr(&f.attrs),
+                })
+            })
+            .collect::<syn::Result<_>>()?;
+        for (offset, field) in fields.iter().enumerate() {
+            let Some(variable) = &field.variable else {
+                continue;
+            };
+            if fields[..offset]
+                .iter()
+                .any(|other| other.variable.as_ref() == Some(variable))
+            {
+                return Err(syn::Error::new_spanned(
+                    &variant.fields,
+                    format!(
+                        "more than one field of {} captures {{{variable}}}",
+                        variant.ident
+                    ),
+                ));
+            }
+        }
+        Ok(Self {
+            fields,
+            positional,
+            method,
+        })
     }
 
+    /// Whether the variant has no captured fields, which doesn't count a `#[method]` field.
     pub(crate) fn is_empty(&self) -> bool {
         self.fields.is_empty()
     }
 
+    /// The number of captured fields, which doesn't count a `#[method]` field.
+    pub(crate) fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Whether the variant has a `#[method]` field.
+    pub(crate) fn has_method(&self) -> bool {
+        self.method.is_some()
+    }
+
+    /// The pattern that destructures the variant into `bindings`, one per captured field, while
+    /// skipping a `#[method]` field.
+    pub(crate) fn pattern(&self, variant_path: &TokenStream, bindings: &[&Ident]) -> TokenStream {
+        match (self.positional, &self.method) {
+            (_, None) if self.fields.is_empty() => quote! { #variant_path },
+            (true, method) => {
+                let mut parts: Vec<_> = bindings.iter().map(|b| quote! { #b }).collect();
+                if let Some(method) = method {
+                    parts.insert(method.position, quote! { _ });
+                }
+                quote! { #variant_path(#( #parts ),*) }
+            }
+            (false, None) => quote! { #variant_path { #( #bindings ),* } },
+            (false, Some(_)) => quote! { #variant_path { #( #bindings, )* .. } },
+        }
+    }
+
+    /// Constructs the variant from `values`, one per captured field, binding a `#[method]` field
+    /// to `method`.
+    pub(crate) fn constructor(
+        &self,
+        variant_path: &TokenStream,
+        values: &[Ident],
+        method: &Ident,
+    ) -> TokenStream {
+        match (self.positional, &self.method) {
+            (_, None) if self.fields.is_empty() => quote! { #variant_path },
+            (true, method_field) => {
+                let mut parts: Vec<_> = values.iter().map(|v| quote! { #v }).collect();
+                if let Some(method_field) = method_field {
+                    parts.insert(method_field.position, quote! { #method });
+                }
+                quote! { #variant_path(#( #parts ),*) }
+            }
+            (false, method_field) => {
+                let idents = self.fields.iter().filter_map(|f| f.ident);
+                let method_field = method_field
+                    .as_ref()
+                    .and_then(|method_field| method_field.ident)
+                    .map(|ident| quote! { #ident: #method, });
+                quote! {
+                    #variant_path {
+                        #(
+                            #idents: #values,
+                        )*
+                        #method_field
+                    }
+                }
+            }
+        }
+    }
+
     pub(crate) fn matches_all_idents(&self, idents: &[Ident]) -> bool {
-        self.fields.iter().all(|(f, _)| idents.contains(f))
+        if self.positional {
+            return self.fields.len() == idents.len();
+        }
+        self.fields.iter().all(|f| {
+            f.variable
+                .as_ref()
+                .is_some_and(|ident| idents.contains(ident))
+        })
+    }
+
+    /// Returns the field bound to the path variable `variable`, which differs from the variable
+    /// when the field has a `#[from("name")]` attribute.
+    pub(crate) fn field_ident(&self, variable: &Ident) -> Option<&'a Ident> {
+        self.fields
+            .iter()
+            .find(|f| f.variable.as_ref() == Some(variable))
+            .and_then(|f| f.ident)
+    }
+
+    /// The types of every field.
+    pub(crate) fn types(&self) -> impl Iterator<Item = &'a Type> + '_ {
+        self.fields.iter().map(|f| f.ty)
+    }
+
+    /// The types of the fields parsed with `FromRoute`, i.e. those without `#[parse_with]` that
+    /// aren't captured by a `{**name}` variable. The type an `Option` wraps is included too, since
+    /// a present `{name?}` variable is parsed as that type.
+    pub(crate) fn parsed_types(&self) -> impl Iterator<Item = &'a Type> + '_ {
+        self.fields
+            .iter()
+            .filter(|f| f.parse_with.is_none() && !f.rest)
+            .flat_map(|f| std::iter::once(f.ty).chain(option_inner(f.ty)))
+    }
+
+    /// The types of the fields parsed with `FromRouteRest`, i.e. those without `#[parse_with]`
+    /// that are captured by a `{**name}` variable.
+    pub(crate) fn rest_types(&self) -> impl Iterator<Item = &'a Type> + '_ {
+        self.fields
+            .iter()
+            .filter(|f| f.parse_with.is_none() && f.rest)
+            .map(|f| f.ty)
+    }
+
+    /// Marks the field bound to the path variable at `position` as captured by a `{**name}`
+    /// variable.
+    pub(crate) fn mark_rest(&mut self, variable: &Ident, position: usize) {
+        let field = match self.positional {
+            true => self.fields.get_mut(position),
+            false => self
+                .fields
+                .iter_mut()
+                .find(|f| f.variable.as_ref() == Some(variable)),
+        };
+        if let Some(field) = field {
+            field.rest = true;
+        }
+    }
+
+    /// Returns whether the field bound to the path variable at `position` is captured by a
+    /// `{**name}` variable.
+    pub(crate) fn is_rest(&self, variable: &Ident, position: usize) -> bool {
+        if self.positional {
+            return self.fields.get(position).is_some_and(|f| f.rest);
+        }
+        self.fields
+            .iter()
+            .any(|f| f.variable.as_ref() == Some(variable) && f.rest)
+    }
+
+    /// Returns the type of the field bound to the path variable at `position`.
+    pub(crate) fn field_type(&self, variable: &Ident, position: usize) -> Option<&'a Type> {
+        if self.positional {
+            return self.fields.get(position).map(|f| f.ty);
+        }
+        self.fields
+            .iter()
+            .find(|f| f.variable.as_ref() == Some(variable))
+            .map(|f| f.ty)
+    }
+
+    /// Whether the query parameter `parameter` of `path` is captured by an `Option` field, which
+    /// is bound to `None` when the request leaves the parameter out.
+    pub(crate) fn is_optional_query(&self, path: &Path, parameter: &str) -> bool {
+        let variables = path.variables();
+        let position = variables.iter().position(|variable| variable == parameter);
+        path.query.iter().any(|query| query == parameter)
+            && position
+                .and_then(|position| self.field_type(&variables[position], position))
+                .and_then(option_inner)
+                .is_some()
     }
 
     pub(crate) fn make_token_stream(
         &self,
-        parent: &Ident,
+        context: &RouterContext,
         variant: &Ident,
+        path: &Path,
         variant_offset: usize,
         path_offset: usize,
         path_block: &Lifetime,
     ) -> TokenStream {
+        let variant_path = context.variant_path(variant);
+        let pattern_index = context.pattern_index(&path.template);
+        let variables = path.variables();
         let mut conversions = Vec::new();
         let base_offset = format_ident!("_{variant_offset}_{path_offset}");
-        for (ident, ty) in self.fields.iter() {
+        let bindings: Vec<_> = self
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(position, f)| f.variable.as_ref().unwrap_or(&variables[position]))
+            .collect();
+        for (field, ident) in self.fields.iter().zip(bindings.iter()) {
+            let CaptureField {
+                ty,
+                decode,
+                parse_with,
+                rest,
+                non_empty,
+                ..
+            } = field;
             let capture = format_ident!("capture{base_offset}_{ident}");
             let parsed = format_ident!("parsed{base_offset}_{ident}");
             let converted = format_ident!("converted{base_offset}_{ident}");
+            let span = ty.span();
+            let parse_fn = match (parse_with, rest, decode) {
+                (Some(parse_with), _, _) => quote! { #parse_with },
+                (None, true, _) => {
+                    quote_spanned! { span=> front_line::FromRouteRest::parse_path_rest }
+                }
+                (None, false, true) => {
+                    quote_spanned! { span=> front_line::FromRoute::parse_path_variable }
+                }
+                (None, false, false) => {
+                    quote_spanned! { span=> front_line::FromRoute::parse_path_bytes }
+                }
+            };
+            // captures are raw bytes, which only `FromRoute::parse_path_bytes` takes as is, so
+            // every other parser gets the capture validated as UTF-8 first
+            let utf8_check = match parse_with.is_some() || *rest || *decode {
+                true => quote! {
+                    let #capture = match core::str::from_utf8(#capture) {
+                        Ok(capture) => capture,
+                        Err(_) => break #path_block,
+                    };
+                },
+                false => quote! {},
+            };
+            let (is_optional, is_omitted) = path.optionality(ident);
+            if is_omitted {
+                conversions.push(quote! {
+                    let #converted: #ty = None;
+                });
+                continue;
+            }
+            // the call is spanned to the field's type, so a type that doesn't implement the trait
+            // is reported on the field rather than on the derive
+            let input = match *decode {
+                true => quote! { decoded },
+                false => quote! { #capture },
+            };
+            let call = quote_spanned! { span=> #parse_fn(&#input) };
+            let parse = if *decode {
+                quote! {
+                    match front_line::percent_decode(#capture) {
+                        Some(decoded) => #call,
+                        None => None,
+                    }
+                }
+            } else {
+                call
+            };
+            // a present optional variable must be non-empty and parse as the type its `Option`
+            // wraps, otherwise the shorter path without it is tried
+            let parse = match is_optional {
+                true => quote! { (#parse).map(Some) },
+                false => parse,
+            };
+            let empty_check = match is_optional || *non_empty || context.non_empty {
+                true => quote! {
+                    if #capture.is_empty() {
+                        break #path_block;
+                    }
+                },
+                false => quote! {},
+            };
             let conversion = quote! {
-                let #parsed: Option<#ty> = front_line::FromRoute::parse_path_variable(&#capture);
+                #empty_check
+                #utf8_check
+                let #parsed: Option<#ty> = #parse;
                 if #parsed.is_none() {
                     break #path_block;
                 }
                 let #converted = #parsed.unwrap();
             };
-            conversions.push(conversion);
-        }
-        let mut initializers = Vec::new();
-        for (ident, _) in self.fields.iter() {
-            let converted = format_ident!("converted{base_offset}_{ident}");
-            let initializer = quote! {
-                #ident: #converted,
+            // an optional query parameter that the request left out is bound to `None`
+            let conversion = match self.is_optional_query(path, &ident.to_string()) {
+                true => quote! {
+                    let #converted: #ty = match #capture {
+                        Some(#capture) => {
+                            #conversion
+                            #converted
+                        }
+                        None => None,
+                    };
+                },
+                false => conversion,
             };
-            initializers.push(initializer);
+            conversions.push(conversion);
         }
+        let converted: Vec<_> = bindings
+            .iter()
+            .map(|ident| format_ident!("converted{base_offset}_{ident}"))
+            .collect();
+        let constructor = self.constructor(&variant_path, &converted, context.method);
+        let emit = context.emit(constructor, quote! { Some(#pattern_index) });
         quote! {
             #(
                 #conversions
             )*
-            return Some(#parent::#variant {
-                #(
-                    #initializers
-                )*
-            });
+            #emit
         }
     }
 }
+
+fn has_decode_attr(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident("decode"))
+}
+
+fn has_non_empty_attr(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident("non_empty"))
+}
+
+/// Returns the function named by a `#[parse_with(path::to::fn)]` attribute, which parses the
+/// field in place of `FromRoute::parse_path_variable`.
+fn parse_with_attr(attrs: &[Attribute]) -> syn::Result<Option<syn::Path>> {
+    let mut attrs = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("parse_with"));
+    let parse_with = match attrs.next() {
+        Some(attr) => attr.parse_args::<syn::Path>().map_err(|_| {
+            syn::Error::new_spanned(attr, "parse_with expects a path to a function")
+        })?,
+        None => return Ok(None),
+    };
+    if let Some(duplicate) = attrs.next() {
+        return Err(syn::Error::new_spanned(
+            duplicate,
+            "a field can only have one parse_with attribute",
+        ));
+    }
+    Ok(Some(parse_with))
+}
+
+/// Returns the path variable named by a `#[from("name")]` attribute, which binds the field to a
+/// variable with a different name.
+fn from_attr(attrs: &[Attribute]) -> syn::Result<Option<Ident>> {
+    let mut attrs = attrs.iter().filter(|attr| attr.path().is_ident("from"));
+    let Some(attr) = attrs.next() else {
+        return Ok(None);
+    };
+    let invalid =
+        || syn::Error::new_spanned(attr, "from expects a variable name, like #[from(\"id\")]");
+    let name = attr.parse_args::<syn::LitStr>().map_err(|_| invalid())?;
+    let value = name.value();
+    if !is_identifier(&value) {
+        return Err(invalid());
+    }
+    if let Some(duplicate) = attrs.next() {
+        return Err(syn::Error::new_spanned(
+            duplicate,
+            "a field can only have one from attribute",
+        ));
+    }
+    Ok(Some(Ident::new(&value, name.span())))
+}
+
+/// Whether `value` can name a field, and so a variable captured into one.
+pub(crate) fn is_identifier(value: &str) -> bool {
+    value
+        .chars()
+        .next()
+        .is_some_and(|first| first.is_alphabetic() || first == '_')
+        && value.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Returns the type wrapped by an `Option` type, like `u32` for `Option<u32>`.
+pub(crate) fn option_inner(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(type_path) => type_path.path.segments.last()?,
+        Type::Group(group) => return option_inner(&group.elem),
+        Type::Paren(paren) => return option_inner(&paren.elem),
+        _ => return None,
+    };
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) => match arguments.args.first()? {
+            GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,57 @@
+use crate::has_flag;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Ident};
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub(crate) enum CaseSensitivity {
+    Sensitive,
+    Insensitive,
+}
+
+impl CaseSensitivity {
+    pub(crate) fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        if has_flag(input, "case_insensitive")? {
+            Ok(CaseSensitivity::Insensitive)
+        } else {
+            Ok(CaseSensitivity::Sensitive)
+        }
+    }
+
+    /// Whether two literals match the same paths.
+    pub(crate) fn same(self, a: &str, b: &str) -> bool {
+        match self {
+            CaseSensitivity::Sensitive => a == b,
+            CaseSensitivity::Insensitive => a.eq_ignore_ascii_case(b),
+        }
+    }
+
+    /// Generates a boolean expression that is true when the `slice` of path bytes doesn't start
+    /// with `literal`.
+    pub(crate) fn mismatch(self, slice: &Ident, literal: &Ident, len: &Ident) -> TokenStream {
+        match self {
+            CaseSensitivity::Sensitive => quote! {
+                #slice.len() < #len || &#slice[..#len] != #literal.as_bytes()
+            },
+            CaseSensitivity::Insensitive => quote! {
+                #slice.len() < #len
+                    || !#slice[..#len].eq_ignore_ascii_case(#literal.as_bytes())
+            },
+        }
+    }
+
+    /// Generates an `Option<usize>` expression holding the offset of the first occurrence of
+    /// `literal` in the `slice` of path bytes.
+    pub(crate) fn find(self, slice: &TokenStream, literal: &Ident) -> TokenStream {
+        match self {
+            CaseSensitivity::Sensitive => quote! {
+                front_line::memchr::memmem::find(#slice, #literal.as_bytes())
+            },
+            CaseSensitivity::Insensitive => quote! {
+                (#slice)
+                    .windows(#literal.len())
+                    .position(|window| window.eq_ignore_ascii_case(#literal.as_bytes()))
+            },
+        }
+    }
+}
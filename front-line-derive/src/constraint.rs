@@ -0,0 +1,41 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Ident, Lifetime};
+
+/// A restriction on the bytes a path variable may capture, declared as `{name:pattern}`.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub(crate) enum Constraint {
+    Digits,
+    Alphanumeric,
+}
+
+impl Constraint {
+    pub(crate) fn parse(pattern: &str) -> Option<Self> {
+        match pattern {
+            r"\d+" | "[0-9]+" => Some(Constraint::Digits),
+            "[a-zA-Z0-9]+" | "[[:alnum:]]+" => Some(Constraint::Alphanumeric),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Constraint::Digits => r"\d+",
+            Constraint::Alphanumeric => "[a-zA-Z0-9]+",
+        }
+    }
+
+    /// Generates a byte scan that breaks out of `path_block` when `capture` doesn't satisfy the
+    /// constraint.
+    pub(crate) fn into_token_stream(self, capture: &Ident, path_block: &Lifetime) -> TokenStream {
+        let is_allowed = match self {
+            Constraint::Digits => quote! { u8::is_ascii_digit },
+            Constraint::Alphanumeric => quote! { u8::is_ascii_alphanumeric },
+        };
+        quote! {
+            if #capture.is_empty() || !#capture.iter().all(#is_allowed) {
+                break #path_block;
+            }
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use crate::method_tag::MethodTag;
+use crate::router_context::RouterContext;
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{Attribute, DataEnum, Fields, Ident, Type, Variant};
+
+/// The `#[fallback]` variant of a router, which resolves when no other route matches.
+#[derive(Clone)]
+pub(crate) struct Fallback<'a> {
+    ident: &'a Ident,
+    field: Option<FallbackField<'a>>,
+    methods: Vec<MethodTag>,
+}
+
+/// The single field of a fallback variant, which captures the full path.
+#[derive(Clone)]
+struct FallbackField<'a> {
+    ident: Option<&'a Ident>,
+    ty: &'a Type,
+}
+
+impl<'a> Fallback<'a> {
+    pub(crate) fn parse(data: &'a DataEnum) -> syn::Result<Option<Self>> {
+        let mut fallback: Option<Self> = None;
+        for variant in data.variants.iter().filter(|variant| is_fallback(variant)) {
+            if let Some(existing) = &fallback {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    format!(
+                        "{} is #[fallback], but {} is already the fallback",
+                        variant.ident, existing.ident
+                    ),
+                ));
+            }
+            fallback = Some(Self {
+                ident: &variant.ident,
+                field: parse_field(variant)?,
+                methods: parse_methods(variant)?,
+            });
+        }
+        Ok(fallback)
+    }
+
+    /// Generates the matcher that emits the fallback variant, which runs after every other
+    /// matcher has failed. With `head_implies_get`, a fallback for `GET` also answers `HEAD`.
+    pub(crate) fn into_token_stream(
+        mut self,
+        context: &RouterContext,
+        remaining_path: &Ident,
+        head_implies_get: bool,
+    ) -> TokenStream {
+        self.add_implied_head(head_implies_get);
+        let parent = context.parent;
+        let method = context.method;
+        let variant = self.ident;
+        let construct = match self.field {
+            None => context.emit(quote! { #parent::#variant }, quote! { None }),
+            Some(FallbackField { ident, ty }) => {
+                let constructor = match ident {
+                    Some(ident) => quote! { #parent::#variant { #ident: path } },
+                    None => quote! { #parent::#variant(path) },
+                };
+                let emit = context.emit(constructor, quote! { None });
+                let parse = quote_spanned! { ty.span()=>
+                    front_line::FromRoute::parse_path_bytes(#remaining_path)
+                };
+                quote! {
+                    let parsed: Option<#ty> = #parse;
+                    if let Some(path) = parsed {
+                        #emit
+                    }
+                }
+            }
+        };
+        if self.methods.is_empty() {
+            return construct;
+        }
+        let methods = self.methods.iter().map(MethodTag::to_ident);
+        quote! {
+            if matches!(#method, #( front_line::Method::#methods )|*) {
+                #construct
+            }
+        }
+    }
+
+    /// Generates the statement that inserts every method the fallback answers into `allowed`,
+    /// for `handle_parsed_methods`, when its field parses from the path.
+    pub(crate) fn into_methods_token_stream(
+        mut self,
+        remaining_path: &Ident,
+        allowed: &Ident,
+        head_implies_get: bool,
+    ) -> TokenStream {
+        self.add_implied_head(head_implies_get);
+        let methods = match self.methods.is_empty() {
+            true => quote! { front_line::MethodSet::ALL },
+            false => {
+                let methods = self.methods.iter().map(MethodTag::to_ident);
+                quote! {
+                    front_line::MethodSet::from_methods(&[#( front_line::Method::#methods ),*])
+                }
+            }
+        };
+        let insert = quote! { #allowed = #allowed.union(#methods); };
+        match self.field {
+            None => insert,
+            Some(FallbackField { ty, .. }) => {
+                let parse = quote_spanned! { ty.span()=>
+                    front_line::FromRoute::parse_path_bytes(#remaining_path)
+                };
+                quote! {
+                    let parsed: Option<#ty> = #parse;
+                    if parsed.is_some() {
+                        #insert
+                    }
+                }
+            }
+        }
+    }
+
+    /// With `head_implies_get`, a fallback for `GET` also answers `HEAD`.
+    fn add_implied_head(&mut self, head_implies_get: bool) {
+        if head_implies_get
+            && self.methods.contains(&MethodTag::Get)
+            && !self.methods.contains(&MethodTag::Head)
+        {
+            self.methods.push(MethodTag::Head);
+        }
+    }
+
+    /// Whether the fallback answers every request, so nothing runs after its matcher.
+    pub(crate) fn always_matches(&self) -> bool {
+        self.field.is_none() && self.methods.is_empty()
+    }
+
+    /// The methods the fallback is declared for, or none when it answers every method.
+    pub(crate) fn methods(&self) -> &[MethodTag] {
+        &self.methods
+    }
+
+    pub(crate) fn ident(&self) -> &'a Ident {
+        self.ident
+    }
+
+    pub(crate) fn into_to_path_arm(self, parent: &Ident) -> syn::Result<TokenStream> {
+        let variant = self.ident;
+        match self.field {
+            Some(FallbackField {
+                ident: Some(ident), ..
+            }) => Ok(quote! {
+                #parent::#variant { #ident } => #ident.to_string(),
+            }),
+            Some(FallbackField { ident: None, .. }) => Ok(quote! {
+                #parent::#variant(path) => path.to_string(),
+            }),
+            None => Err(syn::Error::new_spanned(
+                variant,
+                format!("{variant} must capture the path to derive ToPath"),
+            )),
+        }
+    }
+}
+
+pub(crate) fn is_fallback(variant: &Variant) -> bool {
+    variant
+        .attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("fallback"))
+}
+
+fn parse_field(variant: &Variant) -> syn::Result<Option<FallbackField<'_>>> {
+    let field = match &variant.fields {
+        Fields::Unit => return Ok(None),
+        Fields::Named(fields) if fields.named.len() == 1 => &fields.named[0],
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => &fields.unnamed[0],
+        fields => {
+            return Err(syn::Error::new_spanned(
+                fields,
+                format!(
+                    "{} is #[fallback], so it must be a unit variant or have a single field for the path",
+                    variant.ident
+                ),
+            ))
+        }
+    };
+    Ok(Some(FallbackField {
+        ident: field.ident.as_ref(),
+        ty: &field.ty,
+    }))
+}
+
+fn parse_methods(variant: &Variant) -> syn::Result<Vec<MethodTag>> {
+    let mut methods = Vec::new();
+    for attr in variant.attrs.iter() {
+        let ident = match attr.path().get_ident() {
+            Some(ident) => ident,
+            None => continue,
+        };
+        let is_routing = ["flatten", "mount", "methods", "any", "prefix", "group"]
+            .iter()
+            .any(|name| ident == name);
+        if is_routing {
+            return Err(invalid_attr(variant, attr));
+        }
+        if let Ok(method) = MethodTag::try_from(ident) {
+            if attr.meta.require_path_only().is_err() {
+                return Err(invalid_attr(variant, attr));
+            }
+            if !methods.contains(&method) {
+                methods.push(method);
+            }
+        }
+    }
+    Ok(methods)
+}
+
+fn invalid_attr(variant: &Variant, attr: &Attribute) -> syn::Error {
+    syn::Error::new_spanned(
+        attr,
+        format!(
+            "{} is #[fallback], so it can only have bare method attributes like #[get]",
+            variant.ident
+        ),
+    )
+}
@@ -0,0 +1,73 @@
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{Generics, Ident, Lifetime, LifetimeParam, Type, WherePredicate};
+
+/// The generics of a derived impl: the enum's own generics, plus the bounds the generated code
+/// needs on any field type that mentions one of the enum's type parameters.
+///
+/// Fields whose types are concrete need no bounds, since the generated code wouldn't compile
+/// without them anyway, so only generic field types get a predicate. This lets a route enum like
+/// `enum Routes<T> { Get { id: T } }` derive without spelling out bounds on `'de`, which only
+/// exists in the generated impl.
+pub(crate) struct ImplGenerics {
+    generics: Generics,
+}
+
+impl ImplGenerics {
+    pub(crate) fn new(generics: &Generics) -> Self {
+        Self {
+            generics: generics.clone(),
+        }
+    }
+
+    /// Adds the `'de` lifetime of `Router<'de>` impls, which outlives every lifetime of the enum.
+    pub(crate) fn with_decoding_lifetime(mut self) -> Self {
+        let mut decoding = LifetimeParam::new(Lifetime::new("'de", Span::call_site()));
+        decoding.bounds.extend(
+            self.generics
+                .lifetimes()
+                .map(|param| param.lifetime.clone()),
+        );
+        self.generics.params.insert(0, decoding.into());
+        self
+    }
+
+    /// Bounds each of `types` that mentions a type parameter by `bound`, e.g.
+    /// `front_line::FromRoute<'de>`.
+    pub(crate) fn with_bounds<'t>(
+        mut self,
+        types: impl IntoIterator<Item = &'t Type>,
+        bound: TokenStream,
+    ) -> Self {
+        let mut bounded: Vec<String> = Vec::new();
+        for ty in types {
+            let key = ty.to_token_stream().to_string();
+            if !mentions_type_param(ty, &self.generics) || bounded.contains(&key) {
+                continue;
+            }
+            let predicate: WherePredicate = syn::parse_quote! { #ty: #bound };
+            self.generics.make_where_clause().predicates.push(predicate);
+            bounded.push(key);
+        }
+        self
+    }
+
+    pub(crate) fn into_inner(self) -> Generics {
+        self.generics
+    }
+}
+
+/// Whether `ty` mentions any of the type parameters of `generics`.
+pub(crate) fn mentions_type_param(ty: &Type, generics: &Generics) -> bool {
+    let type_params: Vec<&Ident> = generics.type_params().map(|param| &param.ident).collect();
+    !type_params.is_empty() && mentions(ty.to_token_stream(), &type_params)
+}
+
+/// Whether `tokens` contain any of `idents`, at any depth.
+fn mentions(tokens: TokenStream, idents: &[&Ident]) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => idents.contains(&&ident),
+        TokenTree::Group(group) => mentions(group.stream(), idents),
+        _ => false,
+    })
+}
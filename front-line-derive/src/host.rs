@@ -0,0 +1,72 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{DeriveInput, Ident, LitStr, Token};
+
+/// The hosts a router was declared for with `#[host(..)]`, which it only matches requests for.
+pub(crate) struct Hosts {
+    values: Vec<String>,
+}
+
+impl Hosts {
+    pub(crate) fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        let attr = match input.attrs.iter().find(|attr| attr.path().is_ident("host")) {
+            Some(attr) => attr,
+            None => return Ok(Self { values: vec![] }),
+        };
+        let literals = attr
+            .parse_args_with(Punctuated::<LitStr, Token![,]>::parse_terminated)
+            .map_err(|_| {
+                syn::Error::new_spanned(attr, "host values must be a list of simple &str")
+            })?;
+        if literals.is_empty() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "host must list at least one value",
+            ));
+        }
+        let mut values = Vec::with_capacity(literals.len());
+        for literal in literals.iter() {
+            let host = literal.value();
+            let is_name = !host.is_empty()
+                && !host.ends_with('.')
+                && !host
+                    .bytes()
+                    .any(|b| b.is_ascii_whitespace() || matches!(b, b'/' | b'@' | b'?' | b'#'));
+            let has_port = match host.strip_prefix('[') {
+                Some(literal) => !literal.ends_with(']'),
+                None => host.contains(':'),
+            };
+            if !is_name || has_port {
+                return Err(syn::Error::new(
+                    literal.span(),
+                    "host value must be a host name without a scheme, port or path",
+                ));
+            }
+            values.push(host);
+        }
+        Ok(Self { values })
+    }
+
+    /// Generates the `Router::HOSTS` table.
+    pub(crate) fn hosts_const(&self) -> TokenStream {
+        let values = &self.values;
+        quote! {
+            const HOSTS: &'static [&'static str] = &[#( #values ),*];
+        }
+    }
+
+    /// Generates a statement that returns `no_match` from the router unless `host` is one of the
+    /// declared hosts, or nothing when the router matches any host.
+    pub(crate) fn guard(&self, host: &Ident, no_match: TokenStream) -> TokenStream {
+        if self.values.is_empty() {
+            return quote! {};
+        }
+        quote! {
+            match #host {
+                Some(#host) if front_line::host::matches(#host, Self::HOSTS) => {}
+                _ => return #no_match,
+            }
+        }
+    }
+}
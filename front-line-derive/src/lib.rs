@@ -2,78 +2,706 @@
 //! See: [front-line](https://docs.rs/front-line/latest/front_line/)
 
 mod capture_fields;
+mod case_sensitivity;
+mod constraint;
+mod fallback;
+mod generics;
+mod host;
 mod method_tag;
+#[cfg(feature = "openapi")]
+mod openapi;
 mod path;
+mod patterns;
 mod prefix;
+mod route_data;
+mod route_table;
+mod route_tree;
+mod router_context;
+mod trailing_slash;
 mod variant_type;
 
+use crate::case_sensitivity::CaseSensitivity;
+use crate::fallback::Fallback;
+use crate::generics::{mentions_type_param, ImplGenerics};
+use crate::host::Hosts;
+use crate::method_tag::MethodTag;
+use crate::patterns::{
+    make_methods_const, make_patterns_const, make_prefix_checks, make_routes_const,
+};
 use crate::prefix::Prefix;
+use crate::route_data::RouteData;
+use crate::route_table::make_route_table;
+use crate::route_tree::RouteTree;
+use crate::router_context::{Matching, RouterContext};
+use crate::trailing_slash::TrailingSlash;
 use crate::variant_type::VariantType;
 use proc_macro::TokenStream;
 use quote::{format_ident, quote};
-use syn::punctuated::Punctuated;
-use syn::{Data, DeriveInput, GenericParam, Token};
+use std::collections::BTreeMap;
+use syn::{DeriveInput, Ident};
 
-fn extend_decoding_params(
-    params: &Punctuated<GenericParam, Token![,]>,
-) -> proc_macro2::TokenStream {
-    let lifetimes: Vec<_> = params
-        .iter()
-        .filter_map(|p| match p {
-            GenericParam::Lifetime(l) => Some(&l.lifetime),
-            GenericParam::Type(_) => None,
-            GenericParam::Const(_) => None,
-        })
-        .collect();
-    if lifetimes.is_empty() {
-        quote! { 'de, #params }
-    } else {
-        quote! { 'de: #(#lifetimes)+*, #params }
+/// Whether the enum is marked with the flag attribute `#[name]`, which takes no arguments.
+pub(crate) fn has_flag(input: &DeriveInput, name: &str) -> syn::Result<bool> {
+    let mut found = false;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident(name)) {
+        attr.meta.require_path_only()?;
+        found = true;
     }
+    Ok(found)
+}
+
+/// Whether `HEAD` requests fall back to `GET` routes when no route matches them, per
+/// `#[head_implies_get]`.
+fn head_implies_get(input: &DeriveInput) -> syn::Result<bool> {
+    has_flag(input, "head_implies_get")
+}
+
+/// Whether each `/` of a route's literals matches a run of consecutive slashes, per
+/// `#[merge_slashes]`.
+fn merge_slashes(input: &DeriveInput) -> syn::Result<bool> {
+    has_flag(input, "merge_slashes")
+}
+
+/// Whether every capture of every route must be non-empty, per an enum-level `#[non_empty]`.
+fn non_empty_captures(input: &DeriveInput) -> syn::Result<bool> {
+    has_flag(input, "non_empty")
 }
 
 #[proc_macro_derive(
     FrontLine,
     attributes(
-        get, post, put, delete, head, options, connect, trace, patch, flatten, prefix
+        get,
+        post,
+        put,
+        delete,
+        head,
+        options,
+        connect,
+        trace,
+        patch,
+        methods,
+        any,
+        flatten,
+        mount,
+        prefix,
+        group,
+        host,
+        trailing_slash,
+        case_insensitive,
+        decode,
+        parse_with,
+        from,
+        fallback,
+        head_implies_get,
+        method,
+        non_empty,
+        merge_slashes
     )
 )]
 pub fn front_line_derive(input: TokenStream) -> TokenStream {
     let derive_input = syn::parse_macro_input!(input as DeriveInput);
-    match &derive_input.data {
-        Data::Enum(data) => {
-            let name = &derive_input.ident;
-            let params = &derive_input.generics.params;
-            let extended_params = extend_decoding_params(params);
-            let prefix = Prefix::parse(&derive_input);
-            let variants = VariantType::parse(data);
-            let method = format_ident!("method");
-            let remaining_path = format_ident!("remaining_path");
-            let after_prefix = format_ident!("after_prefix");
-            let prefix_matcher = prefix.into_token_stream(&remaining_path, &after_prefix);
-            let variant_matchers: Vec<_> = variants
-                .into_iter()
-                .enumerate()
-                .map(|(variant_offset, variant)| {
-                    variant.into_token_stream(name, variant_offset, &method, &after_prefix)
-                })
-                .collect();
-            let router = quote! {
-                impl<#extended_params> front_line::Router<'de> for #name<#params> {
-                      fn handle_parsed(
-                        #method: front_line::Method,
-                        #remaining_path: &'de str
-                    ) -> Option<Self> {
-                        #prefix_matcher
-                        #(
-                            #variant_matchers
-                        )*
-                        None
+    expand_front_line(&derive_input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_front_line(derive_input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let RouteData { data, is_struct } = RouteData::parse(derive_input, "FrontLine")?;
+    let data = &data;
+    let name = &derive_input.ident;
+    let prefix = Prefix::parse(derive_input)?;
+    let hosts = Hosts::parse(derive_input)?;
+    let trailing_slash = TrailingSlash::parse(derive_input)?;
+    let case_sensitivity = CaseSensitivity::parse(derive_input)?;
+    let fallback = Fallback::parse(data)?;
+    let variants = VariantType::parse(data, &prefix)?;
+    if let Some(conflict) = VariantType::find_conflict(&variants, trailing_slash) {
+        return Err(conflict);
+    }
+    let generic_flattened = variants.iter().find_map(|variant| {
+        variant
+            .flattened_type()
+            .filter(|ty| mentions_type_param(ty, &derive_input.generics))
+    });
+    if let Some(ty) = generic_flattened {
+        return Err(syn::Error::new_spanned(
+            ty,
+            "flattened types can't depend on a type parameter, since their patterns are \
+             computed at compile time",
+        ));
+    }
+    let decoding_generics = ImplGenerics::new(&derive_input.generics)
+        .with_decoding_lifetime()
+        .with_bounds(
+            variants
+                .iter()
+                .filter_map(|variant| variant.fields())
+                .flat_map(|fields| fields.parsed_types()),
+            quote! { front_line::FromRoute<'de> },
+        )
+        .with_bounds(
+            variants
+                .iter()
+                .filter_map(|variant| variant.fields())
+                .flat_map(|fields| fields.rest_types()),
+            quote! { front_line::FromRouteRest<'de> },
+        )
+        .into_inner();
+    let (impl_generics, _, where_clause) = decoding_generics.split_for_impl();
+    let (_, ty_generics, _) = derive_input.generics.split_for_impl();
+    let method = format_ident!("method");
+    let remaining_path = format_ident!("remaining_path");
+    let after_prefix = format_ident!("after_prefix");
+    let query = format_ident!("query");
+    let deepest = format_ident!("deepest");
+    let host = format_ident!("host");
+    let mut patterns: Vec<String> = Vec::new();
+    for template in variants.iter().flat_map(|variant| variant.templates()) {
+        if !patterns.iter().any(|pattern| pattern == template) {
+            patterns.push(template.to_string());
+        }
+    }
+    let flattened: Vec<_> = variants
+        .iter()
+        .filter_map(|variant| variant.flattened())
+        .collect();
+    let patterns_const = make_patterns_const(&prefix.matching_order(), &patterns, &flattened);
+    let mut routes = Vec::new();
+    for route in variants.iter().flat_map(|variant| variant.routes()) {
+        if !routes.contains(&route) {
+            routes.push(route);
+        }
+    }
+    let routes_const = make_routes_const(&prefix.matching_order(), &routes, &flattened);
+    let declared_prefix = prefix.declared().to_string();
+    let methods_const = make_methods_const(
+        &routes,
+        &flattened,
+        fallback.as_ref().map(Fallback::methods),
+        head_implies_get(derive_input)?,
+    );
+    #[cfg(feature = "openapi")]
+    let openapi_impl =
+        openapi::make_openapi_impl(derive_input, &prefix.matching_order(), &variants);
+    #[cfg(not(feature = "openapi"))]
+    let openapi_impl = quote! {};
+    let context = RouterContext {
+        parent: name,
+        is_struct,
+        method: &method,
+        after_prefix: &after_prefix,
+        query: &query,
+        deepest: &deepest,
+        host: &host,
+        trailing_slash,
+        case_sensitivity,
+        non_empty: non_empty_captures(derive_input)?,
+        merge_slashes: merge_slashes(derive_input)?,
+        patterns: &patterns,
+        matching: Matching::First,
+        found: None,
+    };
+    let head_implies_get = head_implies_get(derive_input)?;
+    let body = make_matcher_body(
+        &context,
+        &variants,
+        prefix.clone(),
+        fallback.clone(),
+        head_implies_get,
+        &remaining_path,
+    );
+    let hosts_const = hosts.hosts_const();
+    let host_guard = hosts.guard(&host, context.no_match());
+    let handle_parsed_methods = {
+        let allowed = format_ident!("allowed");
+        let matched = format_ident!("matched");
+        let context = RouterContext {
+            matching: Matching::Methods(&matched),
+            ..context
+        };
+        let body = make_matcher_body(
+            &context,
+            &variants,
+            prefix.clone(),
+            fallback.clone(),
+            head_implies_get,
+            &remaining_path,
+        );
+        let host_guard = hosts.guard(&host, context.no_match());
+        quote! {
+            #[allow(unused_variables)]
+            fn handle_parsed_methods(
+                #host: Option<&'de str>,
+                #remaining_path: &'de [u8],
+                #query: &'de str,
+                #allowed: &mut front_line::MethodSet
+            ) {
+                #host_guard
+                let mut #deepest = #remaining_path;
+                let #deepest = &mut #deepest;
+                let mut #matched = front_line::MethodSet::EMPTY;
+                #body
+                *#allowed = #allowed.union(#matched);
+            }
+        }
+    };
+    #[cfg(feature = "resolve-all")]
+    let handle_parsed_all = {
+        let collected = format_ident!("collected");
+        let context = RouterContext {
+            matching: Matching::All(&collected),
+            ..context
+        };
+        let body = make_matcher_body(
+            &context,
+            &variants,
+            prefix,
+            fallback,
+            head_implies_get,
+            &remaining_path,
+        );
+        let host_guard = hosts.guard(&host, context.no_match());
+        quote! {
+            #[allow(unused_variables)]
+            fn handle_parsed_all(
+                #method: front_line::Method,
+                #host: Option<&'de str>,
+                #remaining_path: &'de [u8],
+                #query: &'de str,
+                #collected: &mut Vec<(Self, Option<usize>)>
+            ) {
+                #host_guard
+                let mut #deepest = #remaining_path;
+                let #deepest = &mut #deepest;
+                #body
+            }
+        }
+    };
+    #[cfg(not(feature = "resolve-all"))]
+    let handle_parsed_all = quote! {};
+    let flattened_variants: Vec<_> = variants
+        .iter()
+        .filter_map(|variant| variant.flattened_variant())
+        .collect();
+    let prefix_checks = make_prefix_checks(name, &flattened_variants);
+    let router = quote! {
+        #openapi_impl
+
+        #prefix_checks
+
+        impl #impl_generics front_line::Router<'de> for #name #ty_generics #where_clause {
+            const PREFIX: &'static str = #declared_prefix;
+
+            #hosts_const
+
+            #methods_const
+
+            #patterns_const
+
+            #routes_const
+
+            fn handle_parsed(
+                #method: front_line::Method,
+                #remaining_path: &'de str
+            ) -> Option<Self> {
+                Self::handle_parsed_with_query(#method, #remaining_path, "")
+            }
+
+            fn handle_parsed_with_query(
+                #method: front_line::Method,
+                #remaining_path: &'de str,
+                #query: &'de str
+            ) -> Option<Self> {
+                Self::handle_parsed_with_pattern(#method, #remaining_path, #query)
+                    .map(|(route, _)| route)
+            }
+
+            fn handle_parsed_with_pattern(
+                #method: front_line::Method,
+                #remaining_path: &'de str,
+                #query: &'de str
+            ) -> Option<(Self, Option<usize>)> {
+                Self::handle_parsed_bytes(#method, #remaining_path.as_bytes(), #query)
+            }
+
+            fn handle_parsed_bytes(
+                #method: front_line::Method,
+                #remaining_path: &'de [u8],
+                #query: &'de str
+            ) -> Option<(Self, Option<usize>)> {
+                let mut #deepest = #remaining_path;
+                Self::handle_parsed_tracking(#method, #remaining_path, #query, &mut #deepest)
+            }
+
+            #[inline]
+            fn handle_parsed_tracking(
+                #method: front_line::Method,
+                #remaining_path: &'de [u8],
+                #query: &'de str,
+                #deepest: &mut &'de [u8]
+            ) -> Option<(Self, Option<usize>)> {
+                Self::handle_parsed_with_host(#method, None, #remaining_path, #query, #deepest)
+            }
+
+            #[allow(unused_variables)]
+            #[inline]
+            fn handle_parsed_with_host(
+                #method: front_line::Method,
+                #host: Option<&'de str>,
+                #remaining_path: &'de [u8],
+                #query: &'de str,
+                #deepest: &mut &'de [u8]
+            ) -> Option<(Self, Option<usize>)> {
+                #host_guard
+                #body
+            }
+
+            #handle_parsed_methods
+
+            #handle_parsed_all
+        }
+    };
+    Ok(router)
+}
+
+#[proc_macro_derive(
+    ToPath,
+    attributes(
+        get, post, put, delete, head, options, connect, trace, patch, methods, any, flatten, mount,
+        prefix, group, decode, parse_with, from, fallback, method, non_empty
+    )
+)]
+pub fn to_path_derive(input: TokenStream) -> TokenStream {
+    let derive_input = syn::parse_macro_input!(input as DeriveInput);
+    expand_to_path(&derive_input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_to_path(derive_input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let RouteData { data, is_struct } = RouteData::parse(derive_input, "ToPath")?;
+    let data = &data;
+    let name = &derive_input.ident;
+    let prefix = Prefix::parse(derive_input)?;
+    let fallback = Fallback::parse(data)?;
+    let variants = VariantType::parse(data, &prefix)?;
+    let generics = ImplGenerics::new(&derive_input.generics)
+        .with_bounds(
+            variants
+                .iter()
+                .filter_map(|variant| variant.fields())
+                .flat_map(|fields| fields.types()),
+            quote! { ::std::fmt::Display },
+        )
+        .with_bounds(
+            variants
+                .iter()
+                .filter_map(|variant| variant.flattened_type()),
+            quote! { front_line::ToPath },
+        )
+        .into_inner();
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = derive_input.generics.split_for_impl();
+    let unrouted = data.variants.iter().find(|variant| {
+        let is_fallback = fallback
+            .as_ref()
+            .is_some_and(|fallback| fallback.ident() == &variant.ident);
+        !is_fallback
+            && !variants
+                .iter()
+                .any(|routed| routed.ident() == &variant.ident)
+    });
+    if let Some(unrouted) = unrouted {
+        return Err(syn::Error::new_spanned(
+            unrouted,
+            format!("{} must have a route to derive ToPath", unrouted.ident),
+        ));
+    }
+    let mut arms: Vec<_> = variants
+        .into_iter()
+        .map(|variant| variant.into_to_path_arm(name, prefix.as_str(), is_struct))
+        .collect();
+    if let Some(fallback) = fallback {
+        arms.push(fallback.into_to_path_arm(name)?);
+    }
+    let to_path = quote! {
+        impl #impl_generics front_line::ToPath for #name #ty_generics #where_clause {
+            fn to_path(&self) -> String {
+                match self {
+                    #(
+                        #arms
+                    )*
+                }
+            }
+        }
+    };
+    Ok(to_path)
+}
+
+#[proc_macro_derive(
+    RouteTable,
+    attributes(
+        get, post, put, delete, head, options, connect, trace, patch, methods, any, prefix, group,
+        decode, parse_with, from, fallback, method, non_empty
+    )
+)]
+pub fn route_table_derive(input: TokenStream) -> TokenStream {
+    let derive_input = syn::parse_macro_input!(input as DeriveInput);
+    expand_route_table(&derive_input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_route_table(derive_input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let RouteData { data, .. } = RouteData::parse(derive_input, "RouteTable")?;
+    let name = &derive_input.ident;
+    let prefix = Prefix::parse(derive_input)?;
+    let variants = VariantType::parse(&data, &prefix)?;
+    let (rows, names) = make_route_table(&prefix.matching_order(), &variants)?;
+    let (impl_generics, ty_generics, where_clause) = derive_input.generics.split_for_impl();
+    let route_table = quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Every route as a `(Method, &[Segment])` row, one per path of each variant in
+            /// declaration order for each prefix in turn, to match with
+            /// `front_line::route_table::match_table`.
+            pub const ROUTE_TABLE: &'static [front_line::route_table::TableRoute] = &[#( #rows ),*];
+
+            /// The name of the variant of each row of `ROUTE_TABLE`.
+            pub const ROUTE_TABLE_VARIANTS: &'static [&'static str] = &[#( #names ),*];
+        }
+    };
+    Ok(route_table)
+}
+
+/// Generates the body of `handle_parsed_with_host`, which returns the first route that matches,
+/// or of `handle_parsed_all` when the context collects every route that matches instead.
+fn make_matcher_body(
+    context: &RouterContext,
+    variants: &[VariantType],
+    prefix: Prefix,
+    fallback: Option<Fallback>,
+    head_implies_get: bool,
+    remaining_path: &Ident,
+) -> proc_macro2::TokenStream {
+    let method = context.method;
+    let after_prefix = context.after_prefix;
+    // the matchers within the prefix break out of its block instead of returning when it has to
+    // offset the index of the route they find
+    let found = prefix.found_label(context);
+    let prefixed = RouterContext {
+        found: found.as_ref(),
+        ..*context
+    };
+    let mut routes_by_method = BTreeMap::new();
+    let mut asterisk_matcher = quote! {};
+    let mut bare_prefix_matchers = Vec::new();
+    for (variant_offset, variant) in variants.iter().enumerate() {
+        for route in variant.leaf_routes(variant_offset) {
+            // a path that is exactly a stripped prefix, like `/api`, matches root routes as if it
+            // were `/api/`, unless trailing slashes are strict
+            let is_root = route.path.template == "/";
+            if is_root && prefix.is_stripped() && context.trailing_slash == TrailingSlash::Lenient {
+                let variant_path = context.variant_path(route.variant);
+                let variant_path = route.fields.constructor(&variant_path, &[], method);
+                let pattern_index = context.pattern_index(&route.path.template);
+                let emit = prefixed.emit(variant_path, quote! { Some(#pattern_index) });
+                bare_prefix_matchers.push(method_guard(
+                    &prefixed,
+                    route.method,
+                    quote! { #after_prefix.is_empty() },
+                    emit,
+                ));
+            }
+            if route.path.is_asterisk() {
+                let variant_path = context.variant_path(route.variant);
+                let variant_path = route.fields.constructor(&variant_path, &[], method);
+                let pattern_index = context.pattern_index(&route.path.template);
+                let emit = context.emit(variant_path, quote! { Some(#pattern_index) });
+                asterisk_matcher = method_guard(
+                    context,
+                    route.method,
+                    quote! { #remaining_path == b"*" },
+                    emit,
+                );
+                continue;
+            }
+            routes_by_method
+                .entry(route.method)
+                .or_insert_with(Vec::new)
+                .push(route);
+        }
+    }
+    let mut node_count = 0;
+    let leaf_matcher = match context.matching {
+        // the routes of every method are tried in a single tree, since they all have to be
+        Matching::Methods(_) => {
+            let mut routes: Vec<_> = routes_by_method.into_values().flatten().collect();
+            routes.sort_by_key(|route| route.path.specificity());
+            RouteTree::new(routes, context.case_sensitivity).into_token_stream(
+                &prefixed,
+                after_prefix,
+                0,
+                &mut node_count,
+            )
+        }
+        Matching::First | Matching::All(_) => {
+            let method_arms: Vec<_> =
+                routes_by_method
+                    .into_iter()
+                    .map(|(method, mut routes)| {
+                        // the sort is stable, so equally specific routes keep their declaration order
+                        routes.sort_by_key(|route| route.path.specificity());
+                        let method_ident = method.to_ident();
+                        let tree = RouteTree::new(routes, context.case_sensitivity)
+                            .into_token_stream(&prefixed, after_prefix, 0, &mut node_count);
+                        quote! {
+                            front_line::Method::#method_ident => {
+                                #tree
+                            }
+                        }
+                    })
+                    .collect();
+            if method_arms.is_empty() {
+                quote! {}
+            } else {
+                quote! {
+                    match #method {
+                        #( #method_arms )*
+                        _ => {}
                     }
                 }
+            }
+        }
+    };
+    let leaf_count = context.patterns.len();
+    let mut pattern_offset = quote! { #leaf_count };
+    let mut flattened_matchers = Vec::new();
+    for (variant_offset, variant) in variants.iter().enumerate() {
+        flattened_matchers.extend(variant.flattened_matcher(
+            &prefixed,
+            variant_offset,
+            &pattern_offset,
+        ));
+        if let Some(ty) = variant.flattened_type() {
+            pattern_offset = quote! {
+                #pattern_offset + <#ty as front_line::Router<'de>>::PATTERNS.len()
+            };
+        }
+    }
+    let matchers = quote! {
+        #( #bare_prefix_matchers )*
+        #leaf_matcher
+        #(
+            #flattened_matchers
+        )*
+    };
+    let prefix_matcher =
+        prefix.into_token_stream(context, remaining_path, matchers, &pattern_offset);
+    let mut body = quote! {
+        #asterisk_matcher
+        #prefix_matcher
+    };
+    match (head_implies_get, context.matching) {
+        (false, _) => {}
+        (true, Matching::First) => {
+            body = quote! {
+                let mut matches = |#method: front_line::Method| -> Option<(Self, Option<usize>)> {
+                    #body
+                };
+                match matches(#method) {
+                    None if #method == front_line::Method::Head => matches(front_line::Method::Get),
+                    matched => matched,
+                }
+            };
+        }
+        (true, Matching::All(collected)) => {
+            body = quote! {
+                let mut collect = |#method: front_line::Method,
+                                   #collected: &mut Vec<(Self, Option<usize>)>| {
+                    #body
+                };
+                collect(#method, #collected);
+                if #collected.is_empty() && #method == front_line::Method::Head {
+                    collect(front_line::Method::Get, #collected);
+                }
+            };
+        }
+        (true, Matching::Methods(allowed)) => {
+            body = quote! {
+                #body
+                if #allowed.contains(front_line::Method::Get) {
+                    #allowed.insert(front_line::Method::Head);
+                }
             };
-            router.into()
         }
-        _ => panic!("FrontLine derive macro only works on enum types"),
+    }
+    if let Some(fallback) = fallback {
+        let declined = match fallback.always_matches() {
+            true => quote! {},
+            false => context.no_match(),
+        };
+        body = match context.matching {
+            Matching::First => {
+                let fallback =
+                    fallback.into_token_stream(context, remaining_path, head_implies_get);
+                quote! {
+                    let matched: Option<(Self, Option<usize>)> = {
+                        #body
+                    };
+                    if matched.is_some() {
+                        return matched;
+                    }
+                    #fallback
+                    #declined
+                }
+            }
+            // the fallback only answers when no other route matched
+            Matching::All(collected) => {
+                let fallback =
+                    fallback.into_token_stream(context, remaining_path, head_implies_get);
+                quote! {
+                    #body
+                    if #collected.is_empty() {
+                        #fallback
+                    }
+                }
+            }
+            // whether the fallback answers a method doesn't depend on the other routes
+            Matching::Methods(allowed) => {
+                let fallback =
+                    fallback.into_methods_token_stream(remaining_path, allowed, head_implies_get);
+                quote! {
+                    #body
+                    #fallback
+                }
+            }
+        };
+    }
+    body
+}
+
+/// Generates `matcher`, run when `condition` holds for a request with `route_method`. When
+/// matching every method at once, it runs whenever `condition` holds, with the method bound for
+/// the route instead.
+fn method_guard(
+    context: &RouterContext,
+    route_method: &MethodTag,
+    condition: proc_macro2::TokenStream,
+    matcher: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let method = context.method;
+    let method_ident = route_method.to_ident();
+    match context.matching {
+        Matching::Methods(_) => quote! {
+            if #condition {
+                let #method = front_line::Method::#method_ident;
+                #matcher
+            }
+        },
+        Matching::First | Matching::All(_) => quote! {
+            if #condition && #method == front_line::Method::#method_ident {
+                #matcher
+            }
+        },
     }
 }
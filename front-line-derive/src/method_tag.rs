@@ -1,7 +1,12 @@
 use proc_macro2::Span;
 use syn::Ident;
 
-#[derive(Eq, PartialEq, PartialOrd, Ord, Debug)]
+/// The method of a declared route.
+///
+/// The order of the variants is the order the generated `match` lists its method arms in, since
+/// routes are grouped by method in a `BTreeMap`, so the output doesn't depend on the order routes
+/// are declared in. `Get`, the most common method, comes first.
+#[derive(Eq, PartialEq, PartialOrd, Ord, Clone, Debug)]
 pub(crate) enum MethodTag {
     Get,
     Post,
@@ -15,6 +20,19 @@ pub(crate) enum MethodTag {
 }
 
 impl MethodTag {
+    /// Every method, which `#[any("/path")]` declares a route for.
+    pub(crate) const ALL: [MethodTag; 9] = [
+        MethodTag::Get,
+        MethodTag::Post,
+        MethodTag::Put,
+        MethodTag::Delete,
+        MethodTag::Head,
+        MethodTag::Options,
+        MethodTag::Connect,
+        MethodTag::Trace,
+        MethodTag::Patch,
+    ];
+
     pub fn to_ident(&self) -> Ident {
         match self {
             MethodTag::Get => Ident::new("Get", Span::call_site()),
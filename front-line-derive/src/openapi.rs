@@ -0,0 +1,146 @@
+use crate::capture_fields::CaptureFields;
+use crate::method_tag::MethodTag;
+use crate::path::{Path, PathParts};
+use crate::variant_type::VariantType;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{DeriveInput, GenericArgument, PathArguments, Type};
+
+/// Generates the `front_line::OpenApi` impl, which lists every route in the same order as
+/// `Router::ROUTES`, with the parameters of each route typed from the fields they bind to.
+pub(crate) fn make_openapi_impl(
+    derive_input: &DeriveInput,
+    prefixes: &[&str],
+    variants: &[VariantType],
+) -> TokenStream {
+    let name = &derive_input.ident;
+    let (impl_generics, ty_generics, where_clause) = derive_input.generics.split_for_impl();
+    let mut leaves: Vec<(&MethodTag, &Path, &CaptureFields)> = Vec::new();
+    for variant in variants {
+        if let VariantType::LeafVariant(_, fields, routes) = variant {
+            for (path, method) in routes {
+                let seen = leaves.iter().any(|(m, p, _)| {
+                    *m == method && p.template == path.template && p.omitted == path.omitted
+                });
+                if !seen {
+                    leaves.push((method, path, fields));
+                }
+            }
+        }
+    }
+    let pushes = prefixes.iter().map(|prefix| {
+        let leaf_routes = leaves.iter().map(|(method, path, fields)| {
+            let method = method.to_ident();
+            let openapi_path = match path.is_asterisk() {
+                true => path.to_openapi(),
+                false => format!("{prefix}{}", path.to_openapi()),
+            };
+            let parameters = make_parameters(path, fields);
+            quote! {
+                routes.push(front_line::OpenApiRoute {
+                    method: front_line::Method::#method,
+                    path: String::from(#openapi_path),
+                    parameters: vec![#( #parameters ),*],
+                });
+            }
+        });
+        let flattened_routes = variants
+            .iter()
+            .filter_map(|variant| variant.flattened())
+            .map(|(ty, mount)| {
+                let prefix = format!("{prefix}{mount}");
+                quote! {
+                    for mut route in <#ty as front_line::OpenApi>::openapi_routes() {
+                        if route.path != "*" {
+                            route.path.insert_str(0, #prefix);
+                        }
+                        routes.push(route);
+                    }
+                }
+            });
+        quote! {
+            #( #leaf_routes )*
+            #( #flattened_routes )*
+        }
+    });
+    quote! {
+        impl #impl_generics front_line::OpenApi for #name #ty_generics #where_clause {
+            fn openapi_routes() -> Vec<front_line::OpenApiRoute> {
+                let mut routes = Vec::new();
+                #( #pushes )*
+                routes
+            }
+        }
+    }
+}
+
+/// Path variables come first, in path order, followed by the query parameters. Path variables
+/// are always required, while a query parameter is optional when its field is an `Option`.
+/// Omitted `{name?}` variables aren't part of the path, so they're left out.
+fn make_parameters(path: &Path, fields: &CaptureFields) -> Vec<TokenStream> {
+    let path_variables = path
+        .parts
+        .iter()
+        .filter(|part| !matches!(part, PathParts::Segment(_)))
+        .count()
+        + path.omitted.len();
+    path.variables()
+        .iter()
+        .enumerate()
+        .filter(|(_, variable)| !path.optionality(variable).1)
+        .map(|(position, variable)| {
+            let name = variable.to_string();
+            let is_query = position >= path_variables;
+            let (schema, optional) = match fields.field_type(variable, position) {
+                Some(ty) => schema_type(ty),
+                None => ("String", false),
+            };
+            let location = match is_query {
+                true => quote! { front_line::ParameterLocation::Query },
+                false => quote! { front_line::ParameterLocation::Path },
+            };
+            let schema = syn::Ident::new(schema, proc_macro2::Span::call_site());
+            let required = !(is_query && optional);
+            quote! {
+                front_line::OpenApiParameter {
+                    name: #name,
+                    location: #location,
+                    schema: front_line::SchemaType::#schema,
+                    required: #required,
+                }
+            }
+        })
+        .collect()
+}
+
+/// Maps a field's type to the name of its `SchemaType` variant, along with whether the field is
+/// an `Option`. The schema of an `Option` or a `Result` is that of the type it wraps.
+fn schema_type(ty: &Type) -> (&'static str, bool) {
+    let segment = match ty {
+        Type::Path(type_path) => match type_path.path.segments.last() {
+            Some(segment) => segment,
+            None => return ("String", false),
+        },
+        Type::Group(group) => return schema_type(&group.elem),
+        Type::Paren(paren) => return schema_type(&paren.elem),
+        _ => return ("String", false),
+    };
+    if segment.ident == "Option" || segment.ident == "Result" {
+        if let PathArguments::AngleBracketed(arguments) = &segment.arguments {
+            if let Some(GenericArgument::Type(inner)) = arguments.args.first() {
+                let (schema, optional) = schema_type(inner);
+                return (schema, optional || segment.ident == "Option");
+            }
+        }
+    }
+    let ident = segment.ident.to_token_stream().to_string();
+    let schema = match ident.as_str() {
+        "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64" | "i128"
+        | "isize" => "Integer",
+        ident if ident.starts_with("NonZero") => "Integer",
+        "f32" | "f64" => "Number",
+        "bool" => "Boolean",
+        _ => "String",
+    };
+    (schema, false)
+}
@@ -1,103 +1,858 @@
-use crate::capture_fields::CaptureFields;
-use proc_macro2::{Span, TokenStream};
+use crate::capture_fields::{is_identifier, CaptureFields};
+use crate::constraint::Constraint;
+use crate::router_context::RouterContext;
+use crate::trailing_slash::TrailingSlash;
+use proc_macro2::{Literal, Span, TokenStream};
 use quote::{format_ident, quote};
-use regex::Regex;
 use syn::{Ident, Lifetime};
 
-#[derive(PartialEq, Eq, Debug)]
+/// Where a variable sits in a path, which decides how `ToPath` percent-encodes its value.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub(crate) enum Placement {
+    /// A variable within a single segment, like `{id}`, along with the first character of the
+    /// literal that follows it within the segment, like the `.` of `{name}.json`, which would
+    /// otherwise end its capture early.
+    Segment(Option<char>),
+    /// A catch-all spanning several segments, like `{*path}`.
+    Segments,
+    /// A query parameter, like the `{q}` of `?{q}`.
+    Query,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub(crate) struct Path {
     pub parts: Vec<PathParts>,
+    pub query: Vec<String>,
+    pub template: String,
+    /// The trailing `{name?}` variables of the template, whether or not they're in `parts`.
+    pub optional: Vec<String>,
+    /// The optional variables left out of `parts`, which are bound to `None`.
+    pub omitted: Vec<String>,
+    /// Whether the catch-all ending `parts` is a `{**name}` variable, parsed with `FromRouteRest`.
+    pub rest: bool,
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub(crate) enum PathParts {
     Segment(String),
-    Variable(String),
+    Variable(String, Option<Constraint>),
+    CatchAll(String),
+}
+
+/// How specific a segment of a path is, from the most to the least specific.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug)]
+pub(crate) enum Specificity {
+    /// A segment that's all literal, like `me`.
+    Literal,
+    /// A segment mixing literals and variables, like `{name}.json`.
+    Mixed,
+    /// A segment that's a single constrained variable, like `{id:\d+}`.
+    Constrained,
+    /// A segment that's a single variable, like `{id}`.
+    Variable,
+    /// A catch-all variable, like `{*path}`, along with what's left of the path.
+    CatchAll,
 }
 
 impl Path {
-    pub(crate) fn parse(path: &str) -> Path {
-        let re = Regex::new(r"\{(?P<var>[^}]+)}|(?P<seg>/[^{]+/?)").unwrap();
+    pub(crate) fn parse(template: &str, span: Span) -> syn::Result<Path> {
+        if template == "*" {
+            return Ok(Path {
+                parts: vec![PathParts::Segment(template.to_string())],
+                query: Vec::new(),
+                template: template.to_string(),
+                optional: Vec::new(),
+                omitted: Vec::new(),
+                rest: false,
+            });
+        }
+        let (path, query) = match query_start(template) {
+            Some(start) => (
+                &template[..start],
+                parse_query(template, &template[start + 1..], span)?,
+            ),
+            None => (template, Vec::new()),
+        };
         let mut parts = Vec::new();
+        let mut optional = Vec::new();
+        let mut rest = false;
 
-        for cap in re.captures_iter(path) {
-            if let Some(m) = cap.name("var") {
-                parts.push(PathParts::Variable(m.as_str().to_string()));
-            } else if let Some(m) = cap.name("seg") {
-                parts.push(PathParts::Segment(m.as_str().to_string()));
+        let tokens = scan(path).map_err(|reason| {
+            syn::Error::new(
+                span,
+                format!("{path} has {reason}, write {{{{ or }}}} for a literal brace"),
+            )
+        })?;
+        for token in tokens {
+            match token {
+                Token::Variable(variable) => match variable.strip_prefix('*') {
+                    Some(name) if name.ends_with('?') => {
+                        return Err(syn::Error::new(
+                            span,
+                            format!("catch-all variable in {path} can't be optional"),
+                        ));
+                    }
+                    Some(name) => {
+                        let (name, is_rest) = match name.strip_prefix('*') {
+                            Some(name) => (name, true),
+                            None => (name, false),
+                        };
+                        rest |= is_rest;
+                        parts.push(PathParts::CatchAll(name.to_string()));
+                    }
+                    None => {
+                        let (part, is_optional) = parse_variable(variable, span)?;
+                        if let (PathParts::Variable(name, _), true) = (&part, is_optional) {
+                            optional.push(name.clone());
+                        }
+                        parts.push(part);
+                    }
+                },
+                Token::Literal(literal) => match parts.last_mut() {
+                    Some(PathParts::Segment(segment)) => segment.push_str(literal),
+                    _ => parts.push(PathParts::Segment(literal.to_string())),
+                },
+            }
+        }
+
+        let adjacent = parts.windows(2).any(|pair| {
+            !matches!(pair[0], PathParts::Segment(_)) && !matches!(pair[1], PathParts::Segment(_))
+        });
+        if adjacent {
+            return Err(syn::Error::new(
+                span,
+                format!("variables in {path} must be separated by a literal"),
+            ));
+        }
+
+        let catch_all = parts
+            .iter()
+            .position(|part| matches!(part, PathParts::CatchAll(_)));
+        if let Some(position) = catch_all {
+            if position != parts.len() - 1 {
+                return Err(syn::Error::new(
+                    span,
+                    format!("catch-all variable in {path} must be the final part of the path"),
+                ));
             }
         }
 
-        Path { parts }
+        if !optional.is_empty() && !has_trailing_optional_segments(&parts, &optional) {
+            return Err(syn::Error::new(
+                span,
+                format!(
+                    "optional variables in {path} must be whole segments at the end of the path"
+                ),
+            ));
+        }
+
+        let path = Path {
+            parts,
+            query,
+            template: template.to_string(),
+            optional,
+            omitted: Vec::new(),
+            rest,
+        };
+        let invalid = path
+            .path_variables()
+            .chain(path.query.iter())
+            .find(|variable| !is_identifier(variable));
+        if let Some(variable) = invalid {
+            let message = match variable.is_empty() {
+                true => format!("a variable in {template} has no name"),
+                false => format!(
+                    "variable {{{variable}}} in {template} must be named like a field, e.g. {{user_id}}"
+                ),
+            };
+            return Err(syn::Error::new(span, message));
+        }
+        let variables = path.variables();
+        for (offset, variable) in variables.iter().enumerate() {
+            if variables[..offset].contains(variable) {
+                return Err(syn::Error::new(
+                    span,
+                    format!("variable {{{variable}}} appears more than once in {template}"),
+                ));
+            }
+        }
+        Ok(path)
     }
 
+    /// Every variable of the template, in the order they're declared: the path variables,
+    /// including omitted ones, followed by the query parameters.
     pub(crate) fn variables(&self) -> Vec<Ident> {
-        self.parts
+        self.path_variables()
+            .chain(self.omitted.iter())
+            .chain(self.query.iter())
+            .map(|variable| Ident::new(variable.as_str(), Span::call_site()))
+            .collect()
+    }
+
+    /// The variables that are present in `parts` or the query, in order.
+    fn captured_variables(&self) -> Vec<Ident> {
+        self.path_variables()
+            .chain(self.query.iter())
+            .map(|variable| Ident::new(variable.as_str(), Span::call_site()))
+            .collect()
+    }
+
+    fn path_variables(&self) -> impl Iterator<Item = &String> {
+        self.parts.iter().filter_map(|part| match part {
+            PathParts::Variable(variable, _) | PathParts::CatchAll(variable) => Some(variable),
+            PathParts::Segment(_) => None,
+        })
+    }
+
+    /// The name of the `{**name}` variable ending the path, if it has one.
+    pub(crate) fn rest_variable(&self) -> Option<&str> {
+        match (self.rest, self.parts.last()) {
+            (true, Some(PathParts::CatchAll(variable))) => Some(variable),
+            _ => None,
+        }
+    }
+
+    /// Whether `variable` is one of the trailing `{name?}` variables, and whether it's omitted
+    /// from this path.
+    pub(crate) fn optionality(&self, variable: &Ident) -> (bool, bool) {
+        let is = |names: &[String]| names.iter().any(|name| variable == name);
+        (is(&self.optional), is(&self.omitted))
+    }
+
+    /// Expands the path into one path per number of trailing optional variables present, from
+    /// all of them down to none, so each can be matched like any other path. A path without
+    /// optional variables expands to itself.
+    pub(crate) fn expansions(self) -> Vec<Path> {
+        let mut expansions = vec![self.clone()];
+        let mut path = self;
+        for name in path.optional.clone().iter().rev() {
+            // drop the variable along with the `/` before it, keeping a lone `/` for the root
+            path.parts.pop();
+            let is_root = path.parts.len() == 1;
+            if let Some(PathParts::Segment(segment)) = path.parts.last_mut() {
+                if !(is_root && segment == "/") {
+                    segment.pop();
+                }
+                if segment.is_empty() {
+                    path.parts.pop();
+                }
+            }
+            path.omitted.insert(0, name.clone());
+            expansions.push(path.clone());
+        }
+        expansions
+    }
+
+    /// Whether this is the `*` path of a server-wide `OPTIONS *` request.
+    pub(crate) fn is_asterisk(&self) -> bool {
+        self.template == "*"
+    }
+
+    /// Renders the path with every variable name erased, so paths that match the same requests
+    /// normalize to the same string. The query parameters are sorted, since they match in any
+    /// order, and a trailing slash is dropped when trailing slashes are lenient.
+    pub(crate) fn normalized(&self, trailing_slash: TrailingSlash) -> String {
+        let mut normalized = String::new();
+        for part in self.parts.iter() {
+            match part {
+                PathParts::Segment(segment) => normalized.push_str(segment),
+                PathParts::Variable(_, None) => normalized.push_str("{}"),
+                PathParts::Variable(_, Some(constraint)) => {
+                    normalized.push_str(&format!("{{:{}}}", constraint.as_str()));
+                }
+                PathParts::CatchAll(_) => normalized.push_str("{*}"),
+            }
+        }
+        if trailing_slash == TrailingSlash::Lenient && normalized.len() > 1 {
+            if let Some(trimmed) = normalized.strip_suffix('/') {
+                normalized.truncate(trimmed.len());
+            }
+        }
+        let mut query: Vec<_> = self.query.iter().collect();
+        query.sort();
+        for (offset, parameter) in query.into_iter().enumerate() {
+            normalized.push(if offset == 0 { '?' } else { '&' });
+            normalized.push_str(parameter);
+        }
+        normalized
+    }
+
+    /// Renders the path as an OpenAPI path template, with constraints and catch-all markers
+    /// removed and query parameters left out, e.g. `/users/{id}`.
+    #[cfg(feature = "openapi")]
+    pub(crate) fn to_openapi(&self) -> String {
+        let mut openapi = String::new();
+        for part in self.parts.iter() {
+            match part {
+                PathParts::Segment(segment) => openapi.push_str(segment),
+                PathParts::Variable(variable, _) | PathParts::CatchAll(variable) => {
+                    openapi.push_str(&format!("{{{variable}}}"));
+                }
+            }
+        }
+        openapi
+    }
+
+    /// Renders the path as a `format!` string with a `{}` placeholder per variable, along with
+    /// the variable names and where they're placed, in placeholder order. Omitted optional
+    /// variables are left out, and so are the query parameters unless `with_query` is set.
+    pub(crate) fn to_format(&self, with_query: bool) -> (String, Vec<(Ident, Placement)>) {
+        let mut format = String::new();
+        for part in self.parts.iter() {
+            match part {
+                PathParts::Segment(segment) => {
+                    format.push_str(&segment.replace('{', "{{").replace('}', "}}"));
+                }
+                PathParts::Variable(_, _) | PathParts::CatchAll(_) => format.push_str("{}"),
+            }
+        }
+        let query = match with_query {
+            true => self.query.as_slice(),
+            false => &[],
+        };
+        for (offset, parameter) in query.iter().enumerate() {
+            format.push(if offset == 0 { '?' } else { '&' });
+            format.push_str(parameter);
+            format.push_str("={}");
+        }
+        let placements = self
+            .parts
             .iter()
-            .filter_map(|part| match part {
-                PathParts::Variable(variable) => {
-                    Some(Ident::new(variable.as_str(), Span::call_site()))
+            .enumerate()
+            .filter_map(|(offset, part)| match part {
+                PathParts::Variable(_, _) => {
+                    let delimiter = self.suffix_after(offset).and_then(|s| s.chars().next());
+                    Some(Placement::Segment(delimiter))
                 }
+                PathParts::CatchAll(_) => Some(Placement::Segments),
                 PathParts::Segment(_) => None,
             })
+            .chain(query.iter().map(|_| Placement::Query));
+        let captured = self
+            .captured_variables()
+            .into_iter()
+            .zip(placements)
+            .collect();
+        (format, captured)
+    }
+
+    /// Ranks each `/` delimited segment of the path, so routes of the same method can be tried
+    /// from the most specific to the least specific one.
+    pub(crate) fn specificity(&self) -> Vec<Specificity> {
+        #[derive(Default)]
+        struct Segment {
+            has_literal: bool,
+            has_variable: bool,
+            is_constrained: bool,
+            is_catch_all: bool,
+        }
+        let mut segments = vec![Segment::default()];
+        for part in &self.parts {
+            match part {
+                PathParts::Segment(literal) => {
+                    for (offset, piece) in literal.split('/').enumerate() {
+                        if offset > 0 {
+                            segments.push(Segment::default());
+                        }
+                        if let Some(segment) = segments.last_mut() {
+                            segment.has_literal |= !piece.is_empty();
+                        }
+                    }
+                }
+                PathParts::Variable(_, constraint) => {
+                    if let Some(segment) = segments.last_mut() {
+                        segment.has_variable = true;
+                        segment.is_constrained |= constraint.is_some();
+                    }
+                }
+                PathParts::CatchAll(_) => {
+                    if let Some(segment) = segments.last_mut() {
+                        segment.is_catch_all = true;
+                    }
+                }
+            }
+        }
+        // everything before the leading `/` isn't a segment
+        segments
+            .into_iter()
+            .skip(1)
+            .map(|segment| match segment {
+                Segment {
+                    is_catch_all: true, ..
+                } => Specificity::CatchAll,
+                Segment {
+                    has_variable: false,
+                    ..
+                } => Specificity::Literal,
+                Segment {
+                    has_literal: true, ..
+                } => Specificity::Mixed,
+                Segment {
+                    is_constrained: true,
+                    ..
+                } => Specificity::Constrained,
+                _ => Specificity::Variable,
+            })
             .collect()
     }
 
-    pub(crate) fn into_token_stream(
-        self,
-        parent: &Ident,
+    /// The literal that follows the variable at `offset` within the same segment, like `.json` in
+    /// `/files/{name}.json`, which bounds the variable's capture.
+    fn suffix_after(&self, offset: usize) -> Option<&str> {
+        match self.parts.get(offset + 1) {
+            Some(PathParts::Segment(next)) if !next.starts_with('/') => {
+                Some(&next[..next.find('/').unwrap_or(next.len())])
+            }
+            _ => None,
+        }
+    }
+
+    /// Generates the matcher for this path, starting from `slice`, which holds what's left of the
+    /// path after the first `consumed` bytes of the leading literal.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn make_token_stream(
+        &self,
+        context: &RouterContext,
         variant: &Ident,
         fields: &CaptureFields,
         variant_offset: usize,
         path_offset: usize,
-        after_prefix: &Ident,
+        slice: &Ident,
+        consumed: usize,
     ) -> TokenStream {
         let base_offset = format_ident!("_{variant_offset}_{path_offset}");
         let path_block_name = format!("'block{base_offset}");
         let path_block = Lifetime::new(path_block_name.as_str(), Span::call_site());
         let mut segment_matchers = Vec::new();
-        let mut last_slice = after_prefix.clone();
-        for (s_offset, part) in self.parts.into_iter().enumerate() {
+        let mut last_slice = slice.clone();
+        for (s_offset, part) in self.parts.iter().enumerate() {
             let segment_offset = format_ident!("{base_offset}_{s_offset}");
             let next_slice = format_ident!("after{segment_offset}");
             let segment_matcher = match part {
                 PathParts::Segment(segment) => {
+                    let segment = if s_offset == 0 {
+                        &segment[consumed..]
+                    } else {
+                        segment
+                    };
                     let segment_str = format_ident!("str{segment_offset}");
                     let segment_len = format_ident!("len{segment_offset}");
+                    let (len_binding, mismatch) =
+                        context.literal_match(&last_slice, &segment_str, &segment_len);
                     quote! {
                         let #segment_str = #segment;
-                        let #segment_len = #segment_str.len();
-                        if #last_slice.len() < #segment_len || &#last_slice[..#segment_len] != #segment_str {
+                        #len_binding
+                        if #mismatch {
                             break #path_block;
                         }
                         let #next_slice = &#last_slice[#segment_len..];
                     }
                 }
-                PathParts::Variable(variable) => {
+                PathParts::Variable(variable, constraint) => {
                     let end = format_ident!("end{segment_offset}");
                     let capture = format_ident!("capture{base_offset}_{variable}");
+                    let constraint_check = constraint
+                        .map(|constraint| constraint.into_token_stream(&capture, &path_block));
+                    let suffix_matcher = self.suffix_after(s_offset).map(|suffix| {
+                        let suffix_str = format_ident!("suffix{segment_offset}");
+                        // A single non-letter byte, like the `;` in `/items/{id};{rev}`, is a
+                        // plain delimiter that case sensitivity doesn't affect.
+                        let find = match suffix.as_bytes() {
+                            [delimiter] if !delimiter.is_ascii_alphabetic() => {
+                                let delimiter = Literal::byte_character(*delimiter);
+                                quote! {
+                                    front_line::memchr::memchr(#delimiter, &#last_slice[..#end])
+                                }
+                            }
+                            _ => {
+                                let find = context
+                                    .case_sensitivity
+                                    .find(&quote! { &#last_slice[..#end] }, &suffix_str);
+                                quote! {{
+                                    let #suffix_str = #suffix;
+                                    #find
+                                }}
+                            }
+                        };
+                        quote! {
+                            let #end = match #find {
+                                Some(end) => end,
+                                None => break #path_block,
+                            };
+                        }
+                    });
+                    // the capture stays raw bytes, at an offset no further than the end of the
+                    // slice, and is only validated as UTF-8 by the field that parses it, so a
+                    // multibyte character next to a delimiter can't split a `str` and panic, and
+                    // a `&[u8]` field can capture bytes that aren't UTF-8 at all
                     quote! {
-                        let #end = front_line::memchr::memchr(b'/', #last_slice.as_bytes()).unwrap_or(#last_slice.len());
+                        let #end = front_line::memchr::memchr(b'/', #last_slice).unwrap_or(#last_slice.len());
+                        #suffix_matcher
                         let #capture = &#last_slice[..#end];
+                        #constraint_check
                         let #next_slice = &#last_slice[#end..];
                     }
                 }
+                PathParts::CatchAll(variable) => {
+                    let capture = format_ident!("capture{base_offset}_{variable}");
+                    quote! {
+                        let #capture = #last_slice;
+                        let #next_slice: &[u8] = b"";
+                    }
+                }
             };
+            let track = context.track(&next_slice);
             last_slice = next_slice;
-            segment_matchers.push(segment_matcher);
+            segment_matchers.push(quote! {
+                #segment_matcher
+                #track
+            });
         }
-        let conversions =
-            fields.make_token_stream(parent, variant, variant_offset, path_offset, &path_block);
+        let query_matcher = make_query_matcher(
+            context.query,
+            &self.query,
+            |parameter| fields.is_optional_query(self, parameter),
+            &base_offset,
+            &path_block,
+        );
+        let trailing_slash_matcher = context.trailing_slash.into_token_stream(
+            &last_slice,
+            &path_block,
+            context.merge_slashes,
+        );
+        let conversions = fields.make_token_stream(
+            context,
+            variant,
+            self,
+            variant_offset,
+            path_offset,
+            &path_block,
+        );
         quote! {
             #path_block: {
                 #(
                     #segment_matchers
                 )*
-                if !#last_slice.is_empty() && #last_slice != "/" {
-                    break #path_block;
-                }
+                #trailing_slash_matcher
+                #query_matcher
                 #conversions
             }
         }
     }
 }
+
+/// A piece of a path template, as split by `scan`.
+#[derive(PartialEq, Eq, Debug)]
+enum Token<'a> {
+    /// A run of literal characters, up to the next brace, or a single escaped brace.
+    Literal(&'a str),
+    /// What's between a `{` and the next `}`, like `id` or `*path`.
+    Variable(&'a str),
+}
+
+/// Splits a path template into literals and `{..}` variables. A doubled `{{` or `}}` outside of a
+/// variable is a literal `{` or `}` of its own, like in a `format!` string, so any other brace,
+/// like the `}` of `/a}`, an unterminated `{id` or an empty `{}`, is an error describing it.
+fn scan(path: &str) -> Result<Vec<Token<'_>>, &'static str> {
+    let mut tokens = Vec::new();
+    let mut rest = path;
+    while !rest.is_empty() {
+        if let Some(after_brace) = rest.strip_prefix("{{").or_else(|| rest.strip_prefix("}}")) {
+            tokens.push(Token::Literal(&rest[..1]));
+            rest = after_brace;
+            continue;
+        }
+        if let Some(after_brace) = rest.strip_prefix('{') {
+            let end = after_brace.find(['{', '}']);
+            match end.map(|end| (end, &after_brace[end..])) {
+                Some((0, _)) => return Err("an empty {}"),
+                Some((end, close)) if close.starts_with('}') => {
+                    tokens.push(Token::Variable(&after_brace[..end]));
+                    rest = &after_brace[end + 1..];
+                }
+                _ => return Err("an unterminated {"),
+            }
+            continue;
+        }
+        if rest.starts_with('}') {
+            return Err("an unescaped }");
+        }
+        let end = rest.find(['{', '}']).unwrap_or(rest.len());
+        tokens.push(Token::Literal(&rest[..end]));
+        rest = &rest[end..];
+    }
+    Ok(tokens)
+}
+
+/// Parses a `{name}` or `{name:pattern}` variable, along with whether it's optional, declared as
+/// `{name?}` or `{name?:pattern}`.
+fn parse_variable(variable: &str, span: Span) -> syn::Result<(PathParts, bool)> {
+    let (name, pattern) = match variable.split_once(':') {
+        Some((name, pattern)) => (name, Some(pattern)),
+        None => (variable, None),
+    };
+    let (name, is_optional) = match name.strip_suffix('?') {
+        Some(name) => (name, true),
+        None => (name, false),
+    };
+    let pattern = match pattern {
+        Some(pattern) => pattern,
+        None => return Ok((PathParts::Variable(name.to_string(), None), is_optional)),
+    };
+    match Constraint::parse(pattern) {
+        Some(constraint) => Ok((
+            PathParts::Variable(name.to_string(), Some(constraint)),
+            is_optional,
+        )),
+        None => Err(syn::Error::new(
+            span,
+            format!("unsupported constraint {pattern} on {name}, expected \\d+ or [a-zA-Z0-9]+"),
+        )),
+    }
+}
+
+/// The offset of the `?` that starts the query, skipping any inside a `{name?}` variable.
+pub(crate) fn query_start(template: &str) -> Option<usize> {
+    let mut depth = 0;
+    template.char_indices().find_map(|(offset, c)| {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            '?' if depth == 0 => return Some(offset),
+            _ => {}
+        }
+        None
+    })
+}
+
+/// Whether `template` has an optional variable, like the `{day?}` of `/posts/{day?}`.
+pub(crate) fn has_optional_variable(template: &str) -> bool {
+    scan(template).is_ok_and(|tokens| {
+        tokens
+            .iter()
+            .any(|token| matches!(token, Token::Variable(variable) if variable.ends_with('?')))
+    })
+}
+
+/// Whether the `optional` variables are the last parts of `parts`, each a whole segment preceded
+/// by a literal ending in `/` and separated from the next by a single `/`.
+fn has_trailing_optional_segments(parts: &[PathParts], optional: &[String]) -> bool {
+    let trailing = optional.len() * 2;
+    if parts.len() < trailing {
+        return false;
+    }
+    parts[parts.len() - trailing..]
+        .chunks(2)
+        .zip(optional)
+        .enumerate()
+        .all(|(offset, (pair, optional))| match pair {
+            [PathParts::Segment(segment), PathParts::Variable(name, _)] => {
+                let separated = match offset {
+                    0 => segment.ends_with('/'),
+                    _ => segment == "/",
+                };
+                separated && name == optional
+            }
+            _ => false,
+        })
+}
+
+fn parse_query(template: &str, query: &str, span: Span) -> syn::Result<Vec<String>> {
+    query
+        .split('&')
+        .map(|parameter| {
+            parameter
+                .strip_prefix('{')
+                .and_then(|parameter| parameter.strip_suffix('}'))
+                .filter(|name| !name.is_empty())
+                .map(|name| name.to_string())
+                .ok_or_else(|| {
+                    syn::Error::new(
+                        span,
+                        format!("query parameters in {template} must be of the form {{name}}"),
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Generates the matcher that captures each query parameter from `query`. A missing parameter
+/// skips the route, unless `is_optional` says its field is an `Option`, which is then bound to
+/// `None` instead.
+fn make_query_matcher(
+    query: &Ident,
+    parameters: &[String],
+    is_optional: impl Fn(&str) -> bool,
+    base_offset: &Ident,
+    path_block: &Lifetime,
+) -> TokenStream {
+    if parameters.is_empty() {
+        return quote! {};
+    }
+    let captures: Vec<_> = parameters
+        .iter()
+        .map(|parameter| format_ident!("capture{base_offset}_{parameter}"))
+        .collect();
+    let missing = parameters
+        .iter()
+        .zip(&captures)
+        .map(|(parameter, capture)| match is_optional(parameter) {
+            true => quote! {
+                let #capture = #capture.map(str::as_bytes);
+            },
+            false => quote! {
+                let #capture = match #capture {
+                    Some(value) => value.as_bytes(),
+                    None => break #path_block,
+                };
+            },
+        });
+    quote! {
+        #(
+            let mut #captures: Option<&str> = None;
+        )*
+        for pair in #query.split('&') {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            match key {
+                #(
+                    #parameters => {
+                        if #captures.is_none() {
+                            #captures = Some(value);
+                        }
+                    }
+                )*
+                _ => {}
+            }
+        }
+        #(
+            #missing
+        )*
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Token::{Literal, Variable};
+
+    #[test]
+    fn test_scan() {
+        let cases: &[(&str, &[Token])] = &[
+            ("", &[]),
+            ("/", &[Literal("/")]),
+            ("/users/", &[Literal("/users/")]),
+            ("/users//posts", &[Literal("/users//posts")]),
+            ("//", &[Literal("//")]),
+            ("/users/{id}", &[Literal("/users/"), Variable("id")]),
+            (
+                "/users/{id}/posts/{post}/",
+                &[
+                    Literal("/users/"),
+                    Variable("id"),
+                    Literal("/posts/"),
+                    Variable("post"),
+                    Literal("/"),
+                ],
+            ),
+            (
+                "/{a}.{b}",
+                &[Literal("/"), Variable("a"), Literal("."), Variable("b")],
+            ),
+            ("{a}{b}", &[Variable("a"), Variable("b")]),
+            ("/files/{*path}", &[Literal("/files/"), Variable("*path")]),
+            (r"/{id:\d+}", &[Literal("/"), Variable(r"id:\d+")]),
+            (
+                "/{{id}}",
+                &[Literal("/"), Literal("{"), Literal("id"), Literal("}")],
+            ),
+            (
+                "/{{{id}}}",
+                &[Literal("/"), Literal("{"), Variable("id"), Literal("}")],
+            ),
+            ("/a}}b", &[Literal("/a"), Literal("}"), Literal("b")]),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(scan(path).as_deref(), Ok(*expected), "scanning {path}");
+        }
+        let invalid = [
+            ("/a/{}", "an empty {}"),
+            ("/a{}b", "an empty {}"),
+            ("/a{b", "an unterminated {"),
+            ("/{a{b}/", "an unterminated {"),
+            ("/a/}", "an unescaped }"),
+            ("/a}b", "an unescaped }"),
+            ("/a/{x}}", "an unescaped }"),
+            ("/a}}}b", "an unescaped }"),
+        ];
+        for (path, expected) in invalid {
+            assert_eq!(scan(path), Err(expected), "scanning {path}");
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let span = Span::call_site();
+        let path = Path::parse("/users/{id}/posts/{post?}?{tag}", span).unwrap();
+        assert_eq!(
+            path.parts,
+            [
+                PathParts::Segment("/users/".to_string()),
+                PathParts::Variable("id".to_string(), None),
+                PathParts::Segment("/posts/".to_string()),
+                PathParts::Variable("post".to_string(), None),
+            ]
+        );
+        assert_eq!(path.query, ["tag"]);
+        assert_eq!(path.optional, ["post"]);
+        let root = Path::parse("/", span).unwrap();
+        assert_eq!(root.parts, [PathParts::Segment("/".to_string())]);
+        assert!(Path::parse("", span).unwrap().parts.is_empty());
+        assert!(Path::parse("/{a}{b}", span).is_err());
+        assert!(Path::parse("/{*rest}/x", span).is_err());
+        assert!(Path::parse("/{id}/{id}", span).is_err());
+        assert!(Path::parse("/{**rest}/x", span).is_err());
+        for template in [
+            "/a/{1x}",
+            "/{user-id}",
+            "/{*}",
+            "/{**}",
+            "/{?}",
+            "/a/{r#type}",
+            "/s?{1x}",
+            "/s?{}",
+        ] {
+            assert!(Path::parse(template, span).is_err(), "parsing {template}");
+        }
+    }
+
+    #[test]
+    fn test_parse_escaped_braces() {
+        let span = Span::call_site();
+        let path = Path::parse("/templates/{{name}}/{id}", span).unwrap();
+        assert_eq!(
+            path.parts,
+            [
+                PathParts::Segment("/templates/{name}/".to_string()),
+                PathParts::Variable("id".to_string(), None),
+            ]
+        );
+        assert_eq!(path.to_format(true).0, "/templates/{{name}}/{}");
+    }
+
+    #[test]
+    fn test_parse_rest() {
+        let span = Span::call_site();
+        let path = Path::parse("/files/{**path}", span).unwrap();
+        assert_eq!(
+            path.parts.last(),
+            Some(&PathParts::CatchAll("path".to_string()))
+        );
+        assert_eq!(path.rest_variable(), Some("path"));
+        let path = Path::parse("/files/{*path}", span).unwrap();
+        assert_eq!(
+            path.parts.last(),
+            Some(&PathParts::CatchAll("path".to_string()))
+        );
+        assert_eq!(path.rest_variable(), None);
+    }
+}
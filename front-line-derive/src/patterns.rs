@@ -0,0 +1,245 @@
+use crate::method_tag::MethodTag;
+use proc_macro2::{Group, Ident, Span, TokenStream, TokenTree};
+use quote::{quote, ToTokens};
+use syn::Type;
+
+/// Generates the `Router::PATTERNS` table. For each prefix, in matching order, the table holds the
+/// enum's own leaf patterns followed by the table of each flattened sub-router in declaration
+/// order, all with that prefix applied, plus the mount path of mounted sub-routers.
+pub(crate) fn make_patterns_const(
+    prefixes: &[&str],
+    templates: &[String],
+    flattened: &[(&Type, &str)],
+) -> TokenStream {
+    let tables: Vec<_> = prefixes
+        .iter()
+        .flat_map(|prefix| make_prefixed_tables(prefix, templates, flattened))
+        .collect();
+    quote! {
+        const PATTERNS: &'static [&'static str] = {
+            const TABLES: &[&[&str]] = &[#( #tables ),*];
+            const TABLE: [&str; front_line::patterns::joined_len(TABLES)] =
+                front_line::patterns::joined_table(TABLES);
+            &TABLE
+        };
+    }
+}
+
+/// Generates the `Router::METHODS` set: the methods of the enum's own routes and fallback route,
+/// which answers every method unless it lists some, and of each flattened sub-router. With
+/// `#[head_implies_get]`, `HEAD` is added whenever `GET` is in the set.
+pub(crate) fn make_methods_const(
+    routes: &[(&MethodTag, &str)],
+    flattened: &[(&Type, &str)],
+    fallback: Option<&[MethodTag]>,
+    head_implies_get: bool,
+) -> TokenStream {
+    if fallback.is_some_and(|methods| methods.is_empty()) {
+        return quote! {
+            const METHODS: front_line::MethodSet = front_line::MethodSet::ALL;
+        };
+    }
+    let mut methods: Vec<&MethodTag> = Vec::new();
+    let declared = routes
+        .iter()
+        .map(|(method, _)| *method)
+        .chain(fallback.into_iter().flatten());
+    for method in declared {
+        if !methods.contains(&method) {
+            methods.push(method);
+        }
+    }
+    let methods = methods.into_iter().map(MethodTag::to_ident);
+    let flattened = flattened.iter().map(|(ty, _)| ty);
+    let head = match head_implies_get {
+        true => quote! {
+            if methods.contains(front_line::Method::Get) {
+                methods.with(front_line::Method::Head)
+            } else {
+                methods
+            }
+        },
+        false => quote! { methods },
+    };
+    quote! {
+        const METHODS: front_line::MethodSet = {
+            let methods = front_line::MethodSet::from_methods(&[
+                #( front_line::Method::#methods ),*
+            ])
+            #( .union(<#flattened as front_line::Router<'de>>::METHODS) )*;
+            #head
+        };
+    }
+}
+
+/// Generates the `Router::ROUTES` table, laid out like `PATTERNS`: for each prefix, in matching
+/// order, the enum's own routes followed by the table of each flattened sub-router.
+pub(crate) fn make_routes_const(
+    prefixes: &[&str],
+    routes: &[(&MethodTag, &str)],
+    flattened: &[(&Type, &str)],
+) -> TokenStream {
+    let tables: Vec<_> = prefixes
+        .iter()
+        .flat_map(|prefix| make_prefixed_route_tables(prefix, routes, flattened))
+        .collect();
+    quote! {
+        const ROUTES: &'static [(front_line::Method, &'static str)] = {
+            const TABLES: &[&[(front_line::Method, &str)]] = &[#( #tables ),*];
+            const TABLE: [(front_line::Method, &str); front_line::patterns::joined_routes_len(TABLES)] =
+                front_line::patterns::joined_routes(TABLES);
+            &TABLE
+        };
+    }
+}
+
+fn make_prefixed_route_tables(
+    prefix: &str,
+    routes: &[(&MethodTag, &str)],
+    flattened: &[(&Type, &str)],
+) -> Vec<TokenStream> {
+    let leaves = routes.iter().map(|(method, template)| {
+        let method = method.to_ident();
+        let pattern = prefixed(prefix, template);
+        quote! { (front_line::Method::#method, #pattern) }
+    });
+    let leaf_table = quote! { &[#( #leaves ),*] };
+    let flattened_tables = flattened.iter().map(|(ty, mount)| {
+        let ty = with_static_lifetimes(ty.to_token_stream());
+        let prefix = format!("{prefix}{mount}");
+        quote! {
+            {
+                const PREFIX: &str = #prefix;
+                const INNER: &[(front_line::Method, &str)] =
+                    <#ty as front_line::Router<'static>>::ROUTES;
+                const INNER_PATTERNS: [&str; INNER.len()] =
+                    front_line::patterns::route_patterns(INNER);
+                const LEN: usize = front_line::patterns::prefixed_len(PREFIX, &INNER_PATTERNS);
+                const BYTES: [u8; LEN] =
+                    front_line::patterns::prefixed_bytes(PREFIX, &INNER_PATTERNS);
+                const PATTERNS: [&str; INNER.len()] =
+                    front_line::patterns::prefixed_table(&BYTES, PREFIX, &INNER_PATTERNS);
+                const TABLE: [(front_line::Method, &str); INNER.len()] =
+                    front_line::patterns::routes_with_patterns(INNER, &PATTERNS);
+                &TABLE
+            }
+        }
+    });
+    std::iter::once(leaf_table)
+        .chain(flattened_tables)
+        .collect()
+}
+
+/// Applies `prefix` to a leaf pattern. The `*` pattern of `OPTIONS *` is never prefixed.
+fn prefixed(prefix: &str, template: &str) -> String {
+    match template {
+        "*" => template.to_string(),
+        _ => format!("{prefix}{template}"),
+    }
+}
+
+fn make_prefixed_tables(
+    prefix: &str,
+    templates: &[String],
+    flattened: &[(&Type, &str)],
+) -> Vec<TokenStream> {
+    let leaves: Vec<_> = templates
+        .iter()
+        .map(|template| prefixed(prefix, template))
+        .collect();
+    let leaf_table = quote! { &[#( #leaves ),*] };
+    let flattened_tables = flattened.iter().map(|(ty, mount)| {
+        // The table is evaluated outside of the impl's generics, so borrowed sub-routers
+        // are referenced through their 'static instantiation.
+        let ty = with_static_lifetimes(ty.to_token_stream());
+        let prefix = format!("{prefix}{mount}");
+        quote! {
+            {
+                const PREFIX: &str = #prefix;
+                const INNER: &[&str] = <#ty as front_line::Router<'static>>::PATTERNS;
+                const LEN: usize = front_line::patterns::prefixed_len(PREFIX, INNER);
+                const BYTES: [u8; LEN] =
+                    front_line::patterns::prefixed_bytes(PREFIX, INNER);
+                const TABLE: [&str; INNER.len()] =
+                    front_line::patterns::prefixed_table(&BYTES, PREFIX, INNER);
+                &TABLE
+            }
+        }
+    });
+    std::iter::once(leaf_table)
+        .chain(flattened_tables)
+        .collect()
+}
+
+/// Generates a compile time check, for each pair of flattened sub-routers, that they aren't tried
+/// under the same non-empty prefix, their mount path followed by their `Router::PREFIX`. The first
+/// of two such sub-routers would shadow every route of the second it also matches.
+pub(crate) fn make_prefix_checks(
+    parent: &Ident,
+    flattened: &[(&Ident, &Type, &str)],
+) -> TokenStream {
+    let mut checks = Vec::new();
+    for (offset, (first, first_ty, first_mount)) in flattened.iter().enumerate() {
+        let first_ty = with_static_lifetimes(first_ty.to_token_stream());
+        for (second, second_ty, second_mount) in &flattened[offset + 1..] {
+            let second_ty = with_static_lifetimes(second_ty.to_token_stream());
+            // mounted variants are named by where they're mounted, which is what their author
+            // wrote, rather than by a prefix they may not have
+            let conflict = match (*first_mount, *second_mount) {
+                ("", "") => format!(
+                    "flattened variants {first} and {second} of {parent} have the same prefix"
+                ),
+                (first_mount, second_mount) if first_mount == second_mount => format!(
+                    "variants {first} and {second} of {parent} are both mounted at {first_mount}"
+                ),
+                (first_mount, second_mount) => format!(
+                    "variants {} and {} of {parent} are tried under the same prefix",
+                    describe_mount(first, first_mount),
+                    describe_mount(second, second_mount),
+                ),
+            };
+            let message = format!("{conflict}, so {first} would shadow the routes of {second}");
+            checks.push(quote! {
+                const _: () = assert!(
+                    !front_line::patterns::same_prefix(
+                        (#first_mount, <#first_ty as front_line::Router<'static>>::PREFIX),
+                        (#second_mount, <#second_ty as front_line::Router<'static>>::PREFIX),
+                    ),
+                    #message
+                );
+            });
+        }
+    }
+    quote! { #( #checks )* }
+}
+
+/// Names a flattened `variant` along with its `mount` path, if it has one.
+fn describe_mount(variant: &Ident, mount: &str) -> String {
+    match mount {
+        "" => variant.to_string(),
+        _ => format!("{variant} (mounted at {mount})"),
+    }
+}
+
+fn with_static_lifetimes(tokens: TokenStream) -> TokenStream {
+    let mut after_apostrophe = false;
+    tokens
+        .into_iter()
+        .map(|token| {
+            let token = match token {
+                TokenTree::Ident(_) if after_apostrophe => {
+                    TokenTree::Ident(Ident::new("static", Span::call_site()))
+                }
+                TokenTree::Group(group) => {
+                    let stream = with_static_lifetimes(group.stream());
+                    let mut replaced = Group::new(group.delimiter(), stream);
+                    replaced.set_span(group.span());
+                    TokenTree::Group(replaced)
+                }
+                token => token,
+            };
+            after_apostrophe = matches!(&token, TokenTree::Punct(p) if p.as_char() == '\'');
+            token
+        })
+        .collect()
+}
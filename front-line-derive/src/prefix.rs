@@ -1,54 +1,294 @@
-use proc_macro2::{Literal, TokenStream};
-use quote::quote;
-use syn::{DeriveInput, Ident};
+use crate::path::{has_optional_variable, query_start};
+use crate::router_context::{Matching, RouterContext};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::punctuated::Punctuated;
+use syn::{DataEnum, DeriveInput, Ident, Lifetime, LitStr, Token, Variant};
 
+#[derive(Clone)]
 pub struct Prefix {
-    value: Option<String>,
+    values: Vec<String>,
+    /// A prefix with variables, like `/tenants/{tenant}`, which is prepended to every route
+    /// instead of being stripped up front, so its variables are captured like any other.
+    route_prefix: String,
 }
 
 impl Prefix {
-    pub(crate) fn parse(input: &DeriveInput) -> Self {
-        let value = input
+    pub(crate) fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        let attr = match input
             .attrs
             .iter()
             .find(|attr| attr.path().is_ident("prefix"))
-            .map(|prefix| {
-                let literal: Literal = prefix
-                    .parse_args()
-                    .expect("prefix value must be a simple &str");
-                let prefix = literal.to_string();
-                if !prefix.starts_with('"') {
-                    panic!("prefix value must be a simple &str");
-                }
-                if !prefix.starts_with("\"/") {
-                    panic!("prefix value must be a simple &str starting with '/'");
-                }
-                prefix[1..prefix.len() - 1].to_string()
+        {
+            Some(attr) => attr,
+            None => {
+                return Ok(Self {
+                    values: vec![],
+                    route_prefix: String::new(),
+                })
+            }
+        };
+        let literals = attr
+            .parse_args_with(Punctuated::<LitStr, Token![,]>::parse_terminated)
+            .map_err(|_| {
+                syn::Error::new_spanned(attr, "prefix values must be a list of simple &str")
+            })?;
+        if literals.is_empty() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "prefix must list at least one value",
+            ));
+        }
+        let mut values = Vec::with_capacity(literals.len());
+        for literal in literals.iter() {
+            let prefix = normalize_prefix(literal, "prefix")?;
+            check_prefix(&prefix, literal, "prefix")?;
+            values.push(prefix);
+        }
+        if values.iter().any(|prefix| prefix.contains('{')) {
+            if values.len() > 1 {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "a prefix with variables must be the only prefix",
+                ));
+            }
+            return Ok(Self {
+                values: vec![],
+                route_prefix: values.remove(0),
             });
-        Self { value }
+        }
+        Ok(Self {
+            values,
+            route_prefix: String::new(),
+        })
+    }
+
+    /// The prefix to prepend to the path of every route, which is only set when the prefix has
+    /// variables, or an empty string otherwise.
+    pub(crate) fn route_prefix(&self) -> &str {
+        &self.route_prefix
+    }
+
+    /// Whether the enum has no prefix at all.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.values.is_empty() && self.route_prefix.is_empty()
+    }
+
+    /// Whether the enum has prefixes without variables, which are stripped before matching.
+    pub(crate) fn is_stripped(&self) -> bool {
+        !self.values.is_empty()
+    }
+
+    /// The first declared prefix, or an empty string when there is none.
+    pub(crate) fn as_str(&self) -> &str {
+        self.values.first().map(String::as_str).unwrap_or("")
+    }
+
+    /// The prefix as declared, for `Router::PREFIX`: the first declared prefix, the prefix with
+    /// variables, or an empty string when there is none.
+    pub(crate) fn declared(&self) -> &str {
+        match self.route_prefix.is_empty() {
+            true => self.as_str(),
+            false => &self.route_prefix,
+        }
     }
 
+    /// Every prefix in the order they're tried, most specific first, or a single empty prefix
+    /// when there is none.
+    pub(crate) fn matching_order(&self) -> Vec<&str> {
+        if self.values.is_empty() {
+            return vec![""];
+        }
+        let mut prefixes: Vec<_> = self.values.iter().map(String::as_str).collect();
+        prefixes.sort_by_key(|prefix| std::cmp::Reverse(prefix.len()));
+        prefixes
+    }
+
+    /// The label of the block the first matching route breaks out of, so its pattern index can be
+    /// offset by the prefix it matched under, when there are several prefixes to try.
+    pub(crate) fn found_label(&self, context: &RouterContext) -> Option<Lifetime> {
+        let is_first = matches!(context.matching, Matching::First);
+        (is_first && self.values.len() > 1).then(|| Lifetime::new("'prefixed", Span::call_site()))
+    }
+
+    /// Generates the body of the router: strips the prefix into `after_prefix`, runs `track` to
+    /// record how far matching got, and then runs `matchers`, which return from the enclosing
+    /// function when they find a route.
+    ///
+    /// With multiple prefixes each is tried in turn, and the index of the matched pattern is
+    /// offset by `patterns_per_prefix` for every prefix before the one that matched. To do so the
+    /// matchers break out of the block labeled by [`Prefix::found_label`] instead of returning.
     pub(crate) fn into_token_stream(
         self,
+        context: &RouterContext,
         remaining_path: &Ident,
-        after_prefix: &Ident,
+        matchers: TokenStream,
+        patterns_per_prefix: &TokenStream,
     ) -> TokenStream {
-        match self.value {
-            None => quote! {
+        let after_prefix = context.after_prefix;
+        let track = context.track(after_prefix);
+        let prefix_str = format_ident!("prefix");
+        let prefix_len = format_ident!("prefix_len");
+        let (len_binding, mismatch) =
+            context.literal_match(remaining_path, &prefix_str, &prefix_len);
+        let prefixes = self.matching_order();
+        let no_match = context.no_match();
+        match (self.values.len(), context.matching) {
+            (0, _) => quote! {
                 let #after_prefix = #remaining_path;
+                #matchers
+                #no_match
             },
-            Some(prefix) => {
+            (1, _) => {
+                let prefix = prefixes[0];
+                // the matchers are nested rather than returning early, so a fallback or the
+                // rest of a collecting router still runs after a path outside the prefix
                 quote! {
-                    let prefix = #prefix;
-                    if #remaining_path.len() < prefix.len() {
-                        return None;
+                    let #prefix_str = #prefix;
+                    #len_binding
+                    if !(#mismatch) {
+                        let #after_prefix = &#remaining_path[#prefix_len..];
+                        #track
+                        #matchers
+                    }
+                    #no_match
+                }
+            }
+            (_, Matching::Methods(_)) => quote! {
+                for #prefix_str in [#( #prefixes ),*] {
+                    #len_binding
+                    if #mismatch {
+                        continue;
+                    }
+                    let #after_prefix = &#remaining_path[#prefix_len..];
+                    #track
+                    {
+                        #matchers
+                    }
+                }
+            },
+            (_, Matching::All(collected)) => quote! {
+                for (prefix_index, #prefix_str) in [#( #prefixes ),*].into_iter().enumerate() {
+                    #len_binding
+                    if #mismatch {
+                        continue;
                     }
-                    if &#remaining_path[..prefix.len()] != prefix {
-                        return None;
+                    let #after_prefix = &#remaining_path[#prefix_len..];
+                    #track
+                    let start = #collected.len();
+                    {
+                        #matchers
                     }
-                    let #after_prefix = &#remaining_path[prefix.len()..];
+                    let offset = prefix_index * (#patterns_per_prefix);
+                    for (_, index) in &mut #collected[start..] {
+                        *index = index.map(|index| offset + index);
+                    }
+                }
+            },
+            (_, Matching::First) => {
+                let found = self.found_label(context).expect("several prefixes");
+                quote! {
+                    for (prefix_index, #prefix_str) in [#( #prefixes ),*].into_iter().enumerate() {
+                        #len_binding
+                        if #mismatch {
+                            continue;
+                        }
+                        let #after_prefix = &#remaining_path[#prefix_len..];
+                        #track
+                        let matched = #found: {
+                            #matchers
+                            None
+                        };
+                        if let Some((route, index)) = matched {
+                            let offset = prefix_index * (#patterns_per_prefix);
+                            return Some((route, index.map(|index| offset + index)));
+                        }
+                    }
+                    None
                 }
             }
         }
     }
 }
+
+/// Parses the `#[prefix("/accounts")]` of a variant, which is prepended to each of its paths after
+/// the enum's own prefix, or an empty string when the variant has none.
+///
+/// Unlike the enum's prefix, a variant's prefix is part of its patterns, so it may capture
+/// variables like any other part of the path.
+pub(crate) fn parse_variant_prefix(variant: &Variant) -> syn::Result<String> {
+    let attr = match variant
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("prefix"))
+    {
+        Some(attr) => attr,
+        None => return Ok(String::new()),
+    };
+    let literal: LitStr = attr
+        .parse_args()
+        .map_err(|_| syn::Error::new_spanned(attr, "variant prefix value must be a simple &str"))?;
+    let prefix = normalize_prefix(&literal, "variant prefix")?;
+    check_prefix(&prefix, &literal, "prefix")?;
+    Ok(prefix)
+}
+
+/// Parses the `#[group("/v2")]` markers of an enum into the group of each variant, in order. A
+/// group applies to the variant it's declared on and every variant after it, until the next
+/// group, and `#[group("")]` ends a group without starting another.
+///
+/// Like a variant's prefix, a group is part of the patterns of the routes in it, so it may capture
+/// variables like any other part of the path.
+pub(crate) fn parse_variant_groups(data: &DataEnum) -> syn::Result<Vec<String>> {
+    let mut group = String::new();
+    let mut groups = Vec::with_capacity(data.variants.len());
+    for variant in data.variants.iter() {
+        let attr = variant
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("group"));
+        if let Some(attr) = attr {
+            let literal: LitStr = attr
+                .parse_args()
+                .map_err(|_| syn::Error::new_spanned(attr, "group value must be a simple &str"))?;
+            group = match literal.value().is_empty() {
+                true => String::new(),
+                false => normalize_prefix(&literal, "group")?,
+            };
+            check_prefix(&group, &literal, "group")?;
+        }
+        groups.push(group.clone());
+    }
+    Ok(groups)
+}
+
+/// Rejects a `prefix` parsed from the `literal` of an `attr` like `prefix` or `group` when it
+/// captures query parameters or has optional variables, which only a route's own path may have.
+fn check_prefix(prefix: &str, literal: &LitStr, attr: &str) -> syn::Result<()> {
+    let reason = if query_start(prefix).is_some() {
+        "capture query parameters"
+    } else if has_optional_variable(prefix) {
+        "have optional variables"
+    } else {
+        return Ok(());
+    };
+    Err(syn::Error::new(
+        literal.span(),
+        format!("{attr} value can't {reason}"),
+    ))
+}
+
+/// Normalizes a prefix or mount `literal` so it can be joined with the paths after it: the leading
+/// `/` may be left out, and trailing slashes are dropped, so `api/` and `/api` both become `/api`.
+pub(crate) fn normalize_prefix(literal: &LitStr, attr: &str) -> syn::Result<String> {
+    let value = literal.value();
+    let trimmed = value.trim_end_matches('/');
+    let trimmed = trimmed.strip_prefix('/').unwrap_or(trimmed);
+    if trimmed.is_empty() || trimmed.starts_with('/') {
+        return Err(syn::Error::new(
+            literal.span(),
+            format!("{attr} value must name at least one path segment"),
+        ));
+    }
+    Ok(format!("/{trimmed}"))
+}
@@ -0,0 +1,69 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{Data, DataEnum, DeriveInput, Ident, Variant};
+
+/// The variants of the type a derive is applied to. A struct is routed as if it were an enum with
+/// a single variant named after the struct, which carries the struct's attributes and fields.
+pub(crate) struct RouteData {
+    pub data: DataEnum,
+    pub is_struct: bool,
+}
+
+impl RouteData {
+    pub(crate) fn parse(input: &DeriveInput, derive: &str) -> syn::Result<Self> {
+        match &input.data {
+            Data::Enum(data) => Ok(Self {
+                data: data.clone(),
+                is_struct: false,
+            }),
+            Data::Struct(data) => {
+                let unsupported = input.attrs.iter().find(|attr| {
+                    ["flatten", "mount", "group", "fallback"]
+                        .iter()
+                        .any(|name| attr.path().is_ident(name))
+                });
+                if let Some(attr) = unsupported {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "#[flatten], #[mount], #[group] and #[fallback] only work on enum variants",
+                    ));
+                }
+                // the struct's prefix is the router's prefix, not a prefix of its only variant
+                let variant = Variant {
+                    attrs: input
+                        .attrs
+                        .iter()
+                        .filter(|attr| !attr.path().is_ident("prefix"))
+                        .cloned()
+                        .collect(),
+                    ident: input.ident.clone(),
+                    fields: data.fields.clone(),
+                    discriminant: None,
+                };
+                Ok(Self {
+                    data: DataEnum {
+                        enum_token: Default::default(),
+                        brace_token: Default::default(),
+                        variants: Punctuated::from_iter([variant]),
+                    },
+                    is_struct: true,
+                })
+            }
+            Data::Union(_) => Err(syn::Error::new_spanned(
+                &input.ident,
+                format!("{derive} derive macro only works on enum and struct types"),
+            )),
+        }
+    }
+}
+
+/// The path used to construct or match a variant: `Parent::Variant` for an enum, or just `Parent`
+/// for a struct.
+pub(crate) fn variant_path(parent: &Ident, variant: &Ident, is_struct: bool) -> TokenStream {
+    if is_struct {
+        quote! { #parent }
+    } else {
+        quote! { #parent::#variant }
+    }
+}
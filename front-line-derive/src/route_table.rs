@@ -0,0 +1,92 @@
+use crate::path::{Path, PathParts};
+use crate::variant_type::VariantType;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Generates the rows of a `ROUTE_TABLE`, one per path of each leaf variant in declaration order
+/// for each prefix in turn, along with the name of the variant of each row.
+///
+/// Only paths made of whole literal, variable and catch-all segments can be expressed as a table,
+/// so mixed segments, constraints and query parameters are rejected, as are flattened variants.
+pub(crate) fn make_route_table(
+    prefixes: &[&str],
+    variants: &[VariantType],
+) -> syn::Result<(Vec<TokenStream>, Vec<String>)> {
+    let mut rows = Vec::new();
+    let mut names = Vec::new();
+    if let Some(variant) = variants.iter().find(|v| v.flattened_type().is_some()) {
+        let ident = variant.ident();
+        return Err(syn::Error::new(
+            ident.span(),
+            format!("{ident} is a sub-router, which RouteTable can't express"),
+        ));
+    }
+    for prefix in prefixes {
+        for variant in variants {
+            let ident = variant.ident();
+            for (path, method) in variant.paths() {
+                let segments = table_segments(prefix, path).ok_or_else(|| {
+                    syn::Error::new(
+                        ident.span(),
+                        format!(
+                            "{} of {ident} can't be expressed by RouteTable, which only supports \
+                             whole literal, variable and catch-all segments",
+                            path.template
+                        ),
+                    )
+                })?;
+                let method = method.to_ident();
+                rows.push(quote! {
+                    (front_line::Method::#method, &[#( #segments ),*])
+                });
+                names.push(ident.to_string());
+            }
+        }
+    }
+    Ok((rows, names))
+}
+
+/// Splits `prefix` followed by `path` into its `/` delimited segments, or `None` when one of them
+/// isn't a whole literal, an unconstrained variable or a catch-all.
+fn table_segments(prefix: &str, path: &Path) -> Option<Vec<TokenStream>> {
+    if path.is_asterisk() || !path.query.is_empty() {
+        return None;
+    }
+    let prefix = PathParts::Segment(prefix.to_string());
+    let mut segments: Vec<Vec<PathParts>> = vec![vec![]];
+    for part in std::iter::once(&prefix).chain(&path.parts) {
+        match part {
+            PathParts::Segment(literal) => {
+                for (offset, piece) in literal.split('/').enumerate() {
+                    if offset > 0 {
+                        segments.push(vec![]);
+                    }
+                    if !piece.is_empty() {
+                        segments
+                            .last_mut()?
+                            .push(PathParts::Segment(piece.to_string()));
+                    }
+                }
+            }
+            _ => segments.last_mut()?.push(part.clone()),
+        }
+    }
+    // everything before the leading `/` isn't a segment
+    segments
+        .into_iter()
+        .skip(1)
+        .map(|parts| match parts.as_slice() {
+            [] => Some(quote! { front_line::route_table::Segment::Literal("") }),
+            [PathParts::Segment(literal)] => {
+                Some(quote! { front_line::route_table::Segment::Literal(#literal) })
+            }
+            [PathParts::Variable(name, None)] => {
+                Some(quote! { front_line::route_table::Segment::Variable(#name) })
+            }
+            [PathParts::CatchAll(name)] => {
+                Some(quote! { front_line::route_table::Segment::CatchAll(#name) })
+            }
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,190 @@
+use crate::capture_fields::CaptureFields;
+use crate::case_sensitivity::CaseSensitivity;
+use crate::method_tag::MethodTag;
+use crate::path::{Path, PathParts};
+use crate::router_context::{Matching, RouterContext};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::Ident;
+
+/// A single path of a leaf variant for one method.
+pub(crate) struct LeafRoute<'a> {
+    pub method: &'a MethodTag,
+    pub variant: &'a Ident,
+    pub fields: &'a CaptureFields<'a>,
+    pub path: &'a Path,
+    pub variant_offset: usize,
+    pub path_offset: usize,
+}
+
+/// A prefix tree over the leading literal of every route of a method, so a literal shared by many
+/// routes, like `/api/v1`, is compared once instead of once per route.
+///
+/// Routes are still tried in the order they're given. Each branch holds routes whose leading literal
+/// continues with the same `/segment`, along with a route that is exactly that literal, like
+/// `/api/users` next to `/api/users/{id}`. Routes in different branches can never match the same
+/// path, so only a route that ends at a node with a variable (and could therefore match alongside
+/// any branch) forces the branches after it to be split from the ones before it.
+pub(crate) struct RouteTree<'a> {
+    entries: Vec<Entry<'a>>,
+}
+
+enum Entry<'a> {
+    Route(LeafRoute<'a>),
+    Branch(String, RouteTree<'a>),
+}
+
+impl<'a> RouteTree<'a> {
+    pub(crate) fn new(routes: Vec<LeafRoute<'a>>, case_sensitivity: CaseSensitivity) -> Self {
+        Self::build(routes, 0, case_sensitivity)
+    }
+
+    /// Builds the tree for `routes`, the first `consumed` bytes of which have already been
+    /// matched by the enclosing branches.
+    fn build(
+        routes: Vec<LeafRoute<'a>>,
+        consumed: usize,
+        case_sensitivity: CaseSensitivity,
+    ) -> Self {
+        let mut entries: Vec<(Option<String>, Vec<LeafRoute<'a>>)> = Vec::new();
+        let mut run_start = 0;
+        for route in routes {
+            let segment = match next_segment(route.path, consumed)
+                .or_else(|| last_segment(route.path, consumed))
+            {
+                Some(segment) => segment.to_string(),
+                None => {
+                    entries.push((None, vec![route]));
+                    run_start = entries.len();
+                    continue;
+                }
+            };
+            let branch = entries[run_start..].iter_mut().find(|(key, _)| {
+                key.as_deref()
+                    .is_some_and(|key| case_sensitivity.same(key, &segment))
+            });
+            match branch {
+                Some((_, branch_routes)) => branch_routes.push(route),
+                None => entries.push((Some(segment), vec![route])),
+            }
+        }
+        let entries = entries
+            .into_iter()
+            .flat_map(|(key, routes)| match key {
+                Some(key) if routes.len() > 1 => {
+                    let subtree = Self::build(routes, consumed + key.len(), case_sensitivity);
+                    vec![Entry::Branch(key, subtree)]
+                }
+                _ => routes.into_iter().map(Entry::Route).collect(),
+            })
+            .map(Entry::merged)
+            .collect();
+        Self { entries }
+    }
+
+    /// Generates the matchers for every route in the tree, starting from `slice`, which holds
+    /// what's left of the path after the first `consumed` bytes of each route's leading literal.
+    pub(crate) fn into_token_stream(
+        self,
+        context: &RouterContext,
+        slice: &Ident,
+        consumed: usize,
+        node_count: &mut usize,
+    ) -> TokenStream {
+        let matchers = self.entries.into_iter().map(|entry| match entry {
+            Entry::Route(route) => {
+                let matcher = route.path.make_token_stream(
+                    context,
+                    route.variant,
+                    route.fields,
+                    route.variant_offset,
+                    route.path_offset,
+                    slice,
+                    consumed,
+                );
+                match context.matching {
+                    // routes of every method share the tree, so each binds its own
+                    Matching::Methods(_) => {
+                        let method = context.method;
+                        let method_ident = route.method.to_ident();
+                        quote! {
+                            {
+                                let #method = front_line::Method::#method_ident;
+                                #matcher
+                            }
+                        }
+                    }
+                    Matching::First | Matching::All(_) => matcher,
+                }
+            }
+            Entry::Branch(key, subtree) => {
+                *node_count += 1;
+                let node_str = format_ident!("node_str_{node_count}");
+                let node_len = format_ident!("node_len_{node_count}");
+                let next_slice = format_ident!("node_{node_count}");
+                let (len_binding, mismatch) = context.literal_match(slice, &node_str, &node_len);
+                let next_consumed = consumed + key.len();
+                let track = context.track(&next_slice);
+                let subtree =
+                    subtree.into_token_stream(context, &next_slice, next_consumed, node_count);
+                quote! {
+                    let #node_str = #key;
+                    #len_binding
+                    if !(#mismatch) {
+                        let #next_slice = &#slice[#node_len..];
+                        #track
+                        #subtree
+                    }
+                }
+            }
+        });
+        quote! {
+            #(
+                #matchers
+            )*
+        }
+    }
+}
+
+impl Entry<'_> {
+    /// Collapses a chain of branches that each hold a single branch into one branch.
+    fn merged(self) -> Self {
+        match self {
+            Entry::Branch(mut key, mut subtree) => {
+                while let [Entry::Branch(_, _)] = subtree.entries.as_slice() {
+                    if let Some(Entry::Branch(next_key, next_subtree)) = subtree.entries.pop() {
+                        key.push_str(&next_key);
+                        subtree = next_subtree;
+                    }
+                }
+                Entry::Branch(key, subtree)
+            }
+            route => route,
+        }
+    }
+}
+
+/// The rest of a route that is only a literal, after `consumed` bytes, when that's a single
+/// `/segment`.
+///
+/// The route only matches paths made of that segment, perhaps with a trailing slash, so it joins
+/// the branch of routes whose literal continues past the same segment, which can't match those.
+fn last_segment(path: &Path, consumed: usize) -> Option<&str> {
+    let literal = match path.parts.as_slice() {
+        [PathParts::Segment(literal)] => &literal[consumed..],
+        _ => return None,
+    };
+    let is_segment = literal.starts_with('/') && !literal[1..].contains('/');
+    is_segment.then_some(literal)
+}
+
+/// The next `/segment` of the route's leading literal after `consumed` bytes, when the literal
+/// continues past it with another `/`.
+fn next_segment(path: &Path, consumed: usize) -> Option<&str> {
+    let literal = match path.parts.first() {
+        Some(PathParts::Segment(literal)) => &literal[consumed..],
+        _ => return None,
+    };
+    let end = literal.get(1..)?.find('/')? + 1;
+    Some(&literal[..end])
+}
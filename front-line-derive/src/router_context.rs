@@ -0,0 +1,116 @@
+use crate::case_sensitivity::CaseSensitivity;
+use crate::route_data::variant_path;
+use crate::trailing_slash::TrailingSlash;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Ident, Lifetime};
+
+/// Enum-wide state shared by every generated matcher.
+pub(crate) struct RouterContext<'a> {
+    pub parent: &'a Ident,
+    pub is_struct: bool,
+    pub method: &'a Ident,
+    pub after_prefix: &'a Ident,
+    pub query: &'a Ident,
+    pub deepest: &'a Ident,
+    pub host: &'a Ident,
+    pub trailing_slash: TrailingSlash,
+    pub case_sensitivity: CaseSensitivity,
+    /// Whether every capture must be non-empty, per an enum-level `#[non_empty]`.
+    pub non_empty: bool,
+    /// Whether a `/` of a literal matches a run of consecutive slashes, per `#[merge_slashes]`.
+    pub merge_slashes: bool,
+    pub patterns: &'a [String],
+    pub matching: Matching<'a>,
+    /// The labeled block the first route that matches breaks out of with its value, or `None`
+    /// to return it from the router.
+    pub found: Option<&'a Lifetime>,
+}
+
+/// What a generated matcher does with the routes that match.
+#[derive(Copy, Clone)]
+pub(crate) enum Matching<'a> {
+    /// Return the first route that matches, for `handle_parsed_with_host`.
+    First,
+    /// Push every route that matches onto the given vector, for `handle_parsed_all`.
+    #[cfg_attr(not(feature = "resolve-all"), allow(dead_code))]
+    All(&'a Ident),
+    /// Insert the method of every route that matches into the given `MethodSet`, for
+    /// `handle_parsed_methods`. The method isn't a parameter then, but is bound by each route.
+    Methods(&'a Ident),
+}
+
+impl RouterContext<'_> {
+    /// The path used to construct `variant`.
+    pub(crate) fn variant_path(&self, variant: &Ident) -> TokenStream {
+        variant_path(self.parent, variant, self.is_struct)
+    }
+
+    /// Generates a statement that hands a matched `route`, with the index of its pattern, to the
+    /// caller, by returning it or by collecting it, or its method, to try the remaining routes too.
+    pub(crate) fn emit(&self, route: TokenStream, index: TokenStream) -> TokenStream {
+        let method = self.method;
+        match self.matching {
+            Matching::First => match self.found {
+                Some(label) => quote! { break #label Some((#route, #index)); },
+                None => quote! { return Some((#route, #index)); },
+            },
+            Matching::All(collected) => quote! { #collected.push((#route, #index)); },
+            // the fields have already parsed, building the route just keeps them from going unused
+            Matching::Methods(allowed) => quote! {
+                let _: Self = #route;
+                #allowed.insert(#method);
+            },
+        }
+    }
+
+    /// The value a matcher returns when no route matched, which is nothing when collecting.
+    pub(crate) fn no_match(&self) -> TokenStream {
+        match self.matching {
+            Matching::First => quote! { None },
+            Matching::All(_) | Matching::Methods(_) => quote! {},
+        }
+    }
+
+    /// Generates a statement binding `len` to the number of bytes of `slice` that `literal` spans,
+    /// and a boolean expression that is true when `slice` doesn't start with `literal`.
+    ///
+    /// With `#[merge_slashes]` the length is only known once `literal` has matched, since each of
+    /// its slashes spans a whole run of them, so a mismatch is marked by a length no slice has.
+    pub(crate) fn literal_match(
+        &self,
+        slice: &Ident,
+        literal: &Ident,
+        len: &Ident,
+    ) -> (TokenStream, TokenStream) {
+        if !self.merge_slashes {
+            let mismatch = self.case_sensitivity.mismatch(slice, literal, len);
+            return (quote! { let #len = #literal.len(); }, mismatch);
+        }
+        let case_insensitive = self.case_sensitivity == CaseSensitivity::Insensitive;
+        let len_binding = quote! {
+            let #len = front_line::slashes::literal_len(#slice, #literal.as_bytes(), #case_insensitive)
+                .unwrap_or(usize::MAX);
+        };
+        (len_binding, quote! { #len == usize::MAX })
+    }
+
+    /// Generates a statement that records `slice` as the deepest point matching reached, when
+    /// it's shorter than any seen so far.
+    pub(crate) fn track(&self, slice: &Ident) -> TokenStream {
+        let deepest = self.deepest;
+        quote! {
+            if #slice.len() < #deepest.len() {
+                *#deepest = #slice;
+            }
+        }
+    }
+
+    /// The index of a leaf route's declared path in the generated `PATTERNS` table.
+    pub(crate) fn pattern_index(&self, template: &str) -> usize {
+        self.patterns
+            .iter()
+            .position(|pattern| pattern == template)
+            .expect("every leaf route has a pattern")
+    }
+}
@@ -0,0 +1,61 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Ident, Lifetime};
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub(crate) enum TrailingSlash {
+    Lenient,
+    Strict,
+}
+
+impl TrailingSlash {
+    pub(crate) fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        let attr = input
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("trailing_slash"));
+        let attr = match attr {
+            Some(attr) => attr,
+            None => return Ok(TrailingSlash::Lenient),
+        };
+        let message = "trailing_slash value must be either strict or lenient";
+        let mode: Ident = attr
+            .parse_args()
+            .map_err(|_| syn::Error::new_spanned(attr, message))?;
+        if mode == "strict" {
+            Ok(TrailingSlash::Strict)
+        } else if mode == "lenient" {
+            Ok(TrailingSlash::Lenient)
+        } else {
+            Err(syn::Error::new(mode.span(), message))
+        }
+    }
+
+    /// Generates the check that what's left of the path once a route has matched, `last_slice`,
+    /// is at most a trailing slash. With `#[merge_slashes]`, a run of trailing slashes counts as
+    /// one when trailing slashes are lenient.
+    pub(crate) fn into_token_stream(
+        self,
+        last_slice: &Ident,
+        path_block: &Lifetime,
+        merge_slashes: bool,
+    ) -> TokenStream {
+        match self {
+            TrailingSlash::Lenient if merge_slashes => quote! {
+                if !front_line::slashes::is_trailing_slash(#last_slice) {
+                    break #path_block;
+                }
+            },
+            TrailingSlash::Lenient => quote! {
+                if !#last_slice.is_empty() && #last_slice != b"/" {
+                    break #path_block;
+                }
+            },
+            TrailingSlash::Strict => quote! {
+                if !#last_slice.is_empty() {
+                    break #path_block;
+                }
+            },
+        }
+    }
+}
@@ -1,73 +1,409 @@
-use crate::capture_fields::CaptureFields;
+use crate::capture_fields::{option_inner, CaptureFields};
+use crate::fallback::is_fallback;
 use crate::method_tag::MethodTag;
-use crate::path::Path;
+use crate::path::{Path, Placement};
+use crate::prefix::{normalize_prefix, parse_variant_groups, parse_variant_prefix, Prefix};
+use crate::route_data::variant_path;
+use crate::route_tree::LeafRoute;
+use crate::router_context::{Matching, RouterContext};
+use crate::trailing_slash::TrailingSlash;
 use proc_macro2::{Literal, Span, TokenStream};
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, ToTokens};
 use std::collections::BTreeMap;
-use syn::{DataEnum, Fields, Ident, Type};
+use syn::parse::ParseStream;
+use syn::{Attribute, DataEnum, Fields, Ident, Lifetime, LitStr, Token, Type, Variant};
 
 pub(crate) enum VariantType<'a> {
     LeafVariant(&'a Ident, CaptureFields<'a>, Vec<(Path, MethodTag)>),
-    FlattenedVariant(&'a Ident, &'a Type),
+    /// A `#[flatten]` or `#[mount("/path")]` variant, with the mount path, which is stripped
+    /// before delegating to the sub-router, or an empty string for `#[flatten]`.
+    FlattenedVariant(&'a Ident, &'a Type, String),
 }
 
 impl<'a> VariantType<'a> {
-    pub(crate) fn parse(data: &DataEnum) -> Vec<VariantType> {
-        let mut leaves = parse_leaf_variants(data);
-        let flattened = parse_flattened_variants(data);
+    /// Parses every routed variant, prepending the prefix's route prefix, if any, to the path of
+    /// each route.
+    pub(crate) fn parse<'d>(
+        data: &'d DataEnum,
+        prefix: &Prefix,
+    ) -> syn::Result<Vec<VariantType<'d>>> {
+        let route_prefix = prefix.route_prefix();
+        let groups = parse_variant_groups(data)?;
+        let mut leaves = parse_leaf_variants(data, &groups, route_prefix, prefix.is_empty())?;
+        let flattened = parse_flattened_variants(data, &groups)?;
+        if !route_prefix.is_empty() {
+            if let Some(VariantType::FlattenedVariant(variant, _, mount)) = flattened.first() {
+                let attr = if mount.is_empty() { "flatten" } else { "mount" };
+                return Err(syn::Error::new(
+                    variant.span(),
+                    format!("{variant} is #[{attr}], so the prefix can't have variables"),
+                ));
+            }
+        }
         leaves.extend(flattened);
-        leaves
+        Ok(leaves)
+    }
+
+    /// Finds the first route that has the same method and normalized path as a route of an
+    /// earlier variant, since the earlier variant would always shadow it.
+    pub(crate) fn find_conflict(
+        variants: &[VariantType],
+        trailing_slash: TrailingSlash,
+    ) -> Option<syn::Error> {
+        let mut seen: Vec<(&MethodTag, String, &Ident)> = Vec::new();
+        for variant in variants {
+            let (ident, routes) = match variant {
+                VariantType::LeafVariant(ident, _, routes) => (*ident, routes),
+                VariantType::FlattenedVariant(_, _, _) => continue,
+            };
+            for (path, method) in routes {
+                let normalized = path.normalized(trailing_slash);
+                let earlier = seen
+                    .iter()
+                    .find(|(m, n, i)| *m == method && *n == normalized && *i != ident);
+                if let Some((_, _, earlier)) = earlier {
+                    let message = format!(
+                        "{} {} of {} conflicts with the same route of {}",
+                        method.to_ident().to_string().to_uppercase(),
+                        path.template,
+                        ident,
+                        earlier
+                    );
+                    return Some(syn::Error::new(ident.span(), message));
+                }
+                seen.push((method, normalized, ident));
+            }
+        }
+        None
+    }
+
+    /// Every path of a leaf variant for each of its methods, with duplicate paths for the same
+    /// method removed.
+    pub(crate) fn leaf_routes(&self, variant_offset: usize) -> Vec<LeafRoute<'_>> {
+        let (variant, fields, routes) = match self {
+            VariantType::LeafVariant(variant, fields, routes) => (*variant, fields, routes),
+            VariantType::FlattenedVariant(_, _, _) => return vec![],
+        };
+        let mut leaf_routes = Vec::new();
+        for (method, paths) in into_paths_by_method(routes) {
+            for (path_offset, path) in paths.into_iter().enumerate() {
+                let route = LeafRoute {
+                    method,
+                    variant,
+                    fields,
+                    path,
+                    variant_offset,
+                    path_offset,
+                };
+                leaf_routes.push(route);
+            }
+        }
+        leaf_routes
+    }
+
+    /// Generates the matcher that delegates to the sub-router of a flattened variant.
+    ///
+    /// A mounted variant first strips its mount path, which must be followed by a `/` or the end
+    /// of the path, and the sub-router sees the bare mount path as `/` unless trailing slashes
+    /// are strict.
+    pub(crate) fn flattened_matcher(
+        &self,
+        context: &RouterContext,
+        variant_offset: usize,
+        pattern_offset: &TokenStream,
+    ) -> Option<TokenStream> {
+        let (variant, ty, mount) = match self {
+            VariantType::LeafVariant(_, _, _) => return None,
+            VariantType::FlattenedVariant(variant, ty, mount) => (variant, ty, mount),
+        };
+        let parent = context.parent;
+        let parsed_method = context.method;
+        let after_prefix = context.after_prefix;
+        let query = context.query;
+        let deepest = context.deepest;
+        let host = context.host;
+        let maybe_matched = format_ident!("maybe_{variant_offset}");
+        let matched = format_ident!("matched_{variant_offset}");
+        let mounted = format_ident!("mounted_{variant_offset}");
+        let emit = context.emit(
+            quote! { #parent::#variant(#matched) },
+            quote! { index.map(|index| #pattern_offset + index) },
+        );
+        let delegate = |path: &Ident| match context.matching {
+            Matching::First => quote! {
+                let #maybe_matched = <#ty>::handle_parsed_with_host(
+                    #parsed_method,
+                    #host,
+                    #path,
+                    #query,
+                    #deepest,
+                );
+                if let Some((#matched, index)) = #maybe_matched {
+                    #emit
+                }
+            },
+            Matching::All(_) => quote! {
+                let mut #maybe_matched = Vec::new();
+                <#ty>::handle_parsed_all(
+                    #parsed_method,
+                    #host,
+                    #path,
+                    #query,
+                    &mut #maybe_matched,
+                );
+                for (#matched, index) in #maybe_matched {
+                    #emit
+                }
+            },
+            Matching::Methods(allowed) => quote! {
+                <#ty>::handle_parsed_methods(#host, #path, #query, &mut #allowed);
+            },
+        };
+        if mount.is_empty() {
+            return Some(delegate(after_prefix));
+        }
+        let mount_block = Lifetime::new(&format!("'mount_{variant_offset}"), Span::call_site());
+        let mount_str = format_ident!("mount_str_{variant_offset}");
+        let mount_len = format_ident!("mount_len_{variant_offset}");
+        let (len_binding, mismatch) = context.literal_match(after_prefix, &mount_str, &mount_len);
+        let track = context.track(&mounted);
+        let bare_mount = match context.trailing_slash {
+            TrailingSlash::Lenient => quote! {
+                let #mounted: &'de [u8] = if #mounted.is_empty() { b"/" } else { #mounted };
+            },
+            TrailingSlash::Strict => quote! {},
+        };
+        let delegate = delegate(&mounted);
+        Some(quote! {
+            #mount_block: {
+                let #mount_str = #mount;
+                #len_binding
+                if #mismatch {
+                    break #mount_block;
+                }
+                let #mounted = &#after_prefix[#mount_len..];
+                if #mounted.first().is_some_and(|byte| *byte != b'/') {
+                    break #mount_block;
+                }
+                #track
+                #bare_mount
+                #delegate
+            }
+        })
+    }
+
+    pub(crate) fn ident(&self) -> &'a Ident {
+        match self {
+            VariantType::LeafVariant(ident, _, _) => ident,
+            VariantType::FlattenedVariant(ident, _, _) => ident,
+        }
     }
 
-    pub(crate) fn into_token_stream(
+    /// The declared path of every route of a leaf variant, in declaration order.
+    pub(crate) fn templates(&self) -> Vec<&str> {
+        match self {
+            VariantType::LeafVariant(_, _, routes) => routes
+                .iter()
+                .map(|(path, _)| path.template.as_str())
+                .collect(),
+            VariantType::FlattenedVariant(_, _, _) => vec![],
+        }
+    }
+
+    /// The method and declared path of every route of a leaf variant, in declaration order.
+    pub(crate) fn routes(&self) -> Vec<(&MethodTag, &str)> {
+        match self {
+            VariantType::LeafVariant(_, _, routes) => routes
+                .iter()
+                .map(|(path, method)| (method, path.template.as_str()))
+                .collect(),
+            VariantType::FlattenedVariant(_, _, _) => vec![],
+        }
+    }
+
+    /// Every path of a leaf variant, including each expansion of its optional variables, along
+    /// with its method, in declaration order.
+    pub(crate) fn paths(&self) -> &[(Path, MethodTag)] {
+        match self {
+            VariantType::LeafVariant(_, _, routes) => routes,
+            VariantType::FlattenedVariant(_, _, _) => &[],
+        }
+    }
+
+    /// The sub-router type of a flattened variant.
+    pub(crate) fn flattened_type(&self) -> Option<&'a Type> {
+        match self {
+            VariantType::LeafVariant(_, _, _) => None,
+            VariantType::FlattenedVariant(_, ty, _) => Some(*ty),
+        }
+    }
+
+    /// The sub-router type of a flattened variant, along with its mount path, which is empty for
+    /// `#[flatten]`.
+    pub(crate) fn flattened(&self) -> Option<(&'a Type, &str)> {
+        match self {
+            VariantType::LeafVariant(_, _, _) => None,
+            VariantType::FlattenedVariant(_, ty, mount) => Some((*ty, mount.as_str())),
+        }
+    }
+
+    /// The variant, type and mount path of a flattened variant.
+    pub(crate) fn flattened_variant(&self) -> Option<(&'a Ident, &'a Type, &str)> {
+        match self {
+            VariantType::LeafVariant(_, _, _) => None,
+            VariantType::FlattenedVariant(variant, ty, mount) => {
+                Some((*variant, *ty, mount.as_str()))
+            }
+        }
+    }
+
+    /// The capture fields of a leaf variant.
+    pub(crate) fn fields(&self) -> Option<&CaptureFields<'a>> {
+        match self {
+            VariantType::LeafVariant(_, fields, _) => Some(fields),
+            VariantType::FlattenedVariant(_, _, _) => None,
+        }
+    }
+
+    pub(crate) fn into_to_path_arm(
         self,
         parent: &Ident,
-        variant_offset: usize,
-        parsed_method: &Ident,
-        after_prefix: &Ident,
+        prefix: &str,
+        is_struct: bool,
     ) -> TokenStream {
         match self {
             VariantType::LeafVariant(variant, fields, routes) => {
-                let paths_by_method = into_paths_by_method(routes);
-                let mut by_method_matchers = Vec::with_capacity(paths_by_method.len());
-                for (method, paths) in paths_by_method {
-                    let path_blocks: Vec<_> = paths
-                        .into_iter()
-                        .enumerate()
-                        .map(|(path_offset, path)| {
-                            path.into_token_stream(
-                                parent,
-                                variant,
-                                &fields,
-                                variant_offset,
-                                path_offset,
-                                after_prefix,
-                            )
+                let variant_path = variant_path(parent, variant, is_struct);
+                let (path, _) = routes.first().expect("leaf variants always have a route");
+                let variables = path.variables();
+                // Named fields bind to the field rather than the variable, which may differ with
+                // `#[from("name")]`.
+                let bindings: Vec<_> = variables
+                    .iter()
+                    .map(|variable| fields.field_ident(variable).unwrap_or(variable))
+                    .collect();
+                let binding = |variable: &Ident| {
+                    let position = variables.iter().position(|v| v == variable);
+                    position.map(|position| (position, bindings[position]))
+                };
+                // a path with optional variables renders the longest expansion whose optional
+                // fields are all `Some`
+                let mut expansions: Vec<&Path> = Vec::new();
+                for (expansion, _) in routes.iter() {
+                    let is_new = expansions.iter().all(|e| e.omitted != expansion.omitted);
+                    if expansion.template == path.template && is_new {
+                        expansions.push(expansion);
+                    }
+                }
+                let renders = expansions.iter().map(|expansion| {
+                    // a query parameter bound to an `Option` field is left out when it's `None`,
+                    // so the query is appended one parameter at a time
+                    let optional_query: Vec<_> = expansion
+                        .query
+                        .iter()
+                        .map(|parameter| fields.is_optional_query(expansion, parameter))
+                        .collect();
+                    let dynamic_query = optional_query.contains(&true);
+                    let (format, captured) = expansion.to_format(!dynamic_query);
+                    let format = match expansion.is_asterisk() {
+                        true => format,
+                        false => format!("{prefix}{format}"),
+                    };
+                    // every value is percent-encoded for where it's placed, so it can't add
+                    // segments or parameters, or end the path, and routes back to this variant
+                    let arguments: Vec<_> = captured
+                        .iter()
+                        .filter_map(|(variable, placement)| {
+                            let (_, binding) = binding(variable)?;
+                            let encode = match placement {
+                                Placement::Segment(_) => quote! { front_line::percent_encode },
+                                Placement::Segments => quote! { front_line::percent_encode_path },
+                                Placement::Query => quote! { front_line::percent_encode_query },
+                            };
+                            let encoded = quote! { #encode(&#binding.to_string()) };
+                            Some(match placement {
+                                Placement::Segment(Some(delimiter)) => {
+                                    escape_delimiter(encoded, *delimiter)
+                                }
+                                _ => encoded,
+                            })
                         })
                         .collect();
-                    let method_ident = method.to_ident();
-                    let by_method_matcher = quote! {
-                        if #parsed_method == front_line::Method::#method_ident {
-                            #(
-                                #path_blocks
-                            )*
+                    let render = match arguments.is_empty() {
+                        true => {
+                            // without arguments the format string is used as is, so its literal
+                            // braces are unescaped here instead of by `format!`
+                            let path = format.replace("{{", "{").replace("}}", "}");
+                            quote! { { String::from(#path) } }
                         }
+                        false => quote! { { format!(#format, #( #arguments ),*) } },
                     };
-                    by_method_matchers.push(by_method_matcher);
-                }
+                    let render = match dynamic_query {
+                        true => {
+                            let parameters =
+                                expansion.query.iter().zip(&optional_query).filter_map(
+                                    |(parameter, is_optional)| {
+                                        let (_, binding) = binding(&format_ident!("{parameter}"))?;
+                                        let pair = quote! {
+                                            query.push(format!(
+                                                "{}={}",
+                                                #parameter,
+                                                front_line::percent_encode_query(&value.to_string())
+                                            ));
+                                        };
+                                        Some(match is_optional {
+                                            true => quote! {
+                                                if let Some(value) = #binding {
+                                                    #pair
+                                                }
+                                            },
+                                            false => quote! {
+                                                let value = #binding;
+                                                #pair
+                                            },
+                                        })
+                                    },
+                                );
+                            quote! {{
+                                let mut path = #render;
+                                let mut query: Vec<String> = Vec::new();
+                                #( #parameters )*
+                                if !query.is_empty() {
+                                    path.push('?');
+                                    path.push_str(&query.join("&"));
+                                }
+                                path
+                            }}
+                        }
+                        false => render,
+                    };
+                    let present: Vec<_> = expansion
+                        .optional
+                        .iter()
+                        .filter(|name| !expansion.omitted.contains(name))
+                        .filter_map(|name| binding(&format_ident!("{name}")))
+                        .map(|(_, binding)| binding)
+                        .collect();
+                    match present.is_empty() {
+                        true => render,
+                        false => quote! {
+                            if let ( #( Some(#present), )* ) = ( #( #present, )* ) {
+                                #render
+                            }
+                        },
+                    }
+                });
+                let render = quote! { #( #renders )else* };
+                let pattern = fields.pattern(&variant_path, &bindings);
                 quote! {
-                    #(
-                      #by_method_matchers
-                    )*
+                    #pattern => #render,
                 }
             }
-            VariantType::FlattenedVariant(variant, ty) => {
-                let maybe_matched = format_ident!("maybe_{variant_offset}");
-                let matched = format_ident!("matched_{variant_offset}");
+            VariantType::FlattenedVariant(variant, _, mount) => {
+                let prefix = format!("{prefix}{mount}");
                 quote! {
-                    let #maybe_matched = <#ty>::handle_parsed(#parsed_method, #after_prefix);
-                    if let Some(#matched) = #maybe_matched {
-                        return Some(#parent::#variant(#matched));
+                    #parent::#variant(inner) => {
+                        format!("{}{}", #prefix, front_line::ToPath::to_path(inner))
                     }
                 }
             }
@@ -75,114 +411,392 @@ impl<'a> VariantType<'a> {
     }
 }
 
-fn parse_leaf_variants(data: &DataEnum) -> Vec<VariantType> {
-    data.variants
-        .iter()
-        .filter_map(|variant| {
-            let fields = CaptureFields::new(variant);
-            let paths_and_methods: Vec<_> = variant
-                .attrs
-                .iter()
-                .filter_map(|attr| attr.path().get_ident().map(|ident| (attr, ident)))
-                .filter_map(|(attr, ident)| {
-                    MethodTag::try_from(ident).ok().map(|method| (attr, method))
-                })
-                .map(|(attr, method)| {
-                    let literal: Literal = attr.parse_args().unwrap_or_else(|_| {
-                        panic!("path argument for {} must be a simple &str", variant.ident);
-                    });
-                    let path_literal = literal.to_string();
-                    if !path_literal.starts_with('\"') {
-                        panic!("path argument for {} must be a simple &str", variant.ident);
-                    }
-                    if !path_literal.starts_with("\"/") {
-                        panic!(
-                            "path argument for {} must be a simple &str that starts with '/'",
-                            variant.ident
-                        );
-                    }
-                    let path = Path::parse(&path_literal[1..path_literal.len() - 1]);
-                    let path_variables = path.variables();
-                    if variant.fields.len() != path_variables.len() {
-                        panic!(
-                            "path variables for {} must match the named fields of the variant",
-                            variant.ident
-                        );
-                    }
-                    if path_variables.is_empty() {
-                        if !matches!(variant.fields, Fields::Unit) {
-                            panic!(
-                                "{} doesn't define path vars, so it must be a unit variant",
-                                variant.ident
-                            );
-                        }
-                    } else {
-                        if fields.is_empty() {
-                            panic!(
-                                "{} defines path variables, so it must have named fields",
-                                variant.ident
-                            );
-                        }
-                        let all_fields_match = fields.matches_all_idents(path_variables.as_slice());
-                        if !all_fields_match {
-                            panic!(
-                                "variant {} named fields and path variables must match",
-                                variant.ident
-                            );
-                        }
-                    }
-                    (path, method)
-                })
-                .collect();
-            if paths_and_methods.is_empty() {
-                None
-            } else {
-                Some(VariantType::LeafVariant(
+fn parse_leaf_variants<'d>(
+    data: &'d DataEnum,
+    groups: &[String],
+    route_prefix: &str,
+    unprefixed: bool,
+) -> syn::Result<Vec<VariantType<'d>>> {
+    let mut leaves = Vec::new();
+    for (variant, group) in data.variants.iter().zip(groups) {
+        if is_fallback(variant) {
+            continue;
+        }
+        let mut fields = CaptureFields::new(variant)?;
+        let variant_prefix = parse_variant_prefix(variant)?;
+        let route_prefix = format!("{route_prefix}{group}{variant_prefix}");
+        let unprefixed = unprefixed && group.is_empty() && variant_prefix.is_empty();
+        let mut paths_and_methods = Vec::new();
+        for attr in variant.attrs.iter() {
+            for (literal, method) in parse_route_attr(attr)? {
+                let path = parse_path(
+                    variant,
+                    &fields,
+                    &literal,
+                    &method,
+                    &route_prefix,
+                    unprefixed,
+                )?;
+                for path in path.expansions() {
+                    paths_and_methods.push((path, method.clone()));
+                }
+            }
+        }
+        mark_rest_fields(variant, &mut fields, &paths_and_methods)?;
+        if !paths_and_methods.is_empty() {
+            leaves.push(VariantType::LeafVariant(
+                &variant.ident,
+                fields,
+                paths_and_methods,
+            ));
+        }
+    }
+    Ok(leaves)
+}
+
+/// Marks the fields captured by a `{**name}` variable, which are parsed with `FromRouteRest`, and
+/// checks that every path of the variant captures them that way.
+fn mark_rest_fields(
+    variant: &Variant,
+    fields: &mut CaptureFields,
+    paths: &[(Path, MethodTag)],
+) -> syn::Result<()> {
+    for (path, _) in paths {
+        if let Some(rest) = path.rest_variable() {
+            let variables = path.variables();
+            if let Some(position) = variables.iter().position(|variable| variable == rest) {
+                fields.mark_rest(&variables[position], position);
+            }
+        }
+    }
+    for (path, _) in paths {
+        let variables = path.variables();
+        for (position, variable) in variables.iter().enumerate() {
+            let is_rest = path.rest_variable().is_some_and(|rest| variable == rest);
+            if fields.is_rest(variable, position) && !is_rest {
+                return Err(syn::Error::new_spanned(
                     &variant.ident,
+                    format!(
+                        "{} captures {{{variable}}} with {{**{variable}}} in one path, so every \
+                         path must capture it that way",
+                        variant.ident
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_route_attr(attr: &Attribute) -> syn::Result<Vec<(Literal, MethodTag)>> {
+    let ident = match attr.path().get_ident() {
+        Some(ident) => ident,
+        None => return Ok(vec![]),
+    };
+    if ident == "methods" {
+        return parse_methods_attr(attr);
+    }
+    let methods = match MethodTag::try_from(ident) {
+        Ok(method) => vec![method],
+        Err(_) if ident == "any" => MethodTag::ALL.to_vec(),
+        Err(_) => return Ok(vec![]),
+    };
+    let literal: Literal = attr
+        .parse_args()
+        .map_err(|_| syn::Error::new_spanned(attr, "path argument must be a simple &str"))?;
+    Ok(methods
+        .into_iter()
+        .map(|method| (literal.clone(), method))
+        .collect())
+}
+
+fn parse_methods_attr(attr: &Attribute) -> syn::Result<Vec<(Literal, MethodTag)>> {
+    let (methods, literal) = attr.parse_args_with(|input: ParseStream| {
+        let mut methods = Vec::new();
+        while input.peek(Ident) {
+            let ident: Ident = input.parse()?;
+            let method = MethodTag::try_from(&ident)
+                .map_err(|_| syn::Error::new(ident.span(), "unknown http method"))?;
+            methods.push(method);
+            input.parse::<Token![,]>()?;
+        }
+        let literal: Literal = input.parse().map_err(|error| {
+            syn::Error::new(
+                error.span(),
+                "methods argument must be a list of http methods followed by a simple &str",
+            )
+        })?;
+        Ok((methods, literal))
+    })?;
+    if methods.is_empty() {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "methods argument must list at least one http method",
+        ));
+    }
+    Ok(methods
+        .into_iter()
+        .map(|method| (literal.clone(), method))
+        .collect())
+}
+
+fn parse_path(
+    variant: &Variant,
+    fields: &CaptureFields,
+    literal: &Literal,
+    method: &MethodTag,
+    route_prefix: &str,
+    unprefixed: bool,
+) -> syn::Result<Path> {
+    let path_literal: LitStr = syn::parse2(literal.to_token_stream())
+        .map_err(|_| syn::Error::new(literal.span(), "path argument must be a simple &str"))?;
+    let template = path_literal.value();
+    if template == "*" && *method != MethodTag::Options {
+        return Err(syn::Error::new(
+            literal.span(),
+            "only options routes can use the \"*\" path",
+        ));
+    }
+    if template.is_empty() && unprefixed {
+        return Err(syn::Error::new(
+            literal.span(),
+            "only routes with a prefix can use the \"\" path",
+        ));
+    }
+    // a relative path like `users/{id}` is joined to the prefix as if it started with `/`
+    let template = match template.as_str() {
+        "*" => template,
+        "" => route_prefix.to_string(),
+        _ if template.starts_with('/') => format!("{route_prefix}{template}"),
+        _ => format!("{route_prefix}/{template}"),
+    };
+    let path = Path::parse(&template, literal.span())?;
+    let path_variables = path.variables();
+    if fields.len() != path_variables.len() {
+        return Err(syn::Error::new(
+            literal.span(),
+            format!(
+                "path variables for {} must match the fields of the variant",
+                variant.ident
+            ),
+        ));
+    }
+    if path_variables.is_empty() {
+        if !matches!(variant.fields, Fields::Unit) && !fields.has_method() {
+            return Err(syn::Error::new_spanned(
+                &variant.fields,
+                format!(
+                    "{} doesn't define path vars, so it must be a unit variant",
+                    variant.ident
+                ),
+            ));
+        }
+    } else {
+        if fields.is_empty() {
+            return Err(syn::Error::new_spanned(
+                variant,
+                format!(
+                    "{} defines path variables, so it must have named or positional fields",
+                    variant.ident
+                ),
+            ));
+        }
+        let all_fields_match = fields.matches_all_idents(path_variables.as_slice());
+        if !all_fields_match {
+            return Err(syn::Error::new_spanned(
+                &variant.fields,
+                format!(
+                    "variant {} fields and path variables must match",
+                    variant.ident
+                ),
+            ));
+        }
+    }
+    for (position, variable) in path_variables.iter().enumerate() {
+        let (is_optional, _) = path.optionality(variable);
+        let is_option = fields
+            .field_type(variable, position)
+            .and_then(option_inner)
+            .is_some();
+        if is_optional && !is_option {
+            return Err(syn::Error::new(
+                literal.span(),
+                format!("optional variable {{{variable}?}} must be captured by an Option field"),
+            ));
+        }
+    }
+    Ok(path)
+}
+
+fn parse_flattened_variants<'d>(
+    data: &'d DataEnum,
+    groups: &[String],
+) -> syn::Result<Vec<VariantType<'d>>> {
+    let mut flattened = Vec::new();
+    for (variant, group) in data.variants.iter().zip(groups) {
+        if is_fallback(variant) {
+            continue;
+        }
+        let is_flattened = variant
+            .attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("flatten"));
+        let mount = parse_mount(variant)?;
+        let attr = match (is_flattened, &mount) {
+            (false, None) => continue,
+            (true, Some(_)) => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    format!("{} can't be both #[flatten] and #[mount]", variant.ident),
+                ));
+            }
+            (true, None) => "flatten",
+            (false, Some(_)) => "mount",
+        };
+        let prefix = variant
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("prefix"));
+        if let Some(prefix) = prefix {
+            return Err(syn::Error::new_spanned(
+                prefix,
+                format!(
+                    "{} is #[{attr}], so it can't have a #[prefix]",
+                    variant.ident
+                ),
+            ));
+        }
+        // the sub-router is mounted under the group, which is stripped before delegating to it
+        if group.contains('{') {
+            return Err(syn::Error::new_spanned(
+                variant,
+                format!(
+                    "{} is #[{attr}], so it can't be in a #[group] with variables",
+                    variant.ident
+                ),
+            ));
+        }
+        match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let only_field = &fields.unnamed[0];
+                flattened.push(VariantType::FlattenedVariant(
+                    &variant.ident,
+                    &only_field.ty,
+                    format!("{group}{}", mount.unwrap_or_default()),
+                ));
+            }
+            Fields::Unnamed(fields) => {
+                return Err(syn::Error::new_spanned(
                     fields,
-                    paths_and_methods,
-                ))
+                    format!(
+                        "{} is #[{attr}], so it must have exactly 1 parameter",
+                        variant.ident
+                    ),
+                ));
             }
-        })
-        .collect()
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    format!(
+                        "{} is #[{attr}], so it must have a single unnamed parameter",
+                        variant.ident
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(flattened)
 }
 
-fn parse_flattened_variants(data: &DataEnum) -> Vec<VariantType> {
-    data.variants
+/// Parses the path of a `#[mount("/path")]` attribute, which must be a literal path without a
+/// trailing slash, variables or a query.
+fn parse_mount(variant: &Variant) -> syn::Result<Option<String>> {
+    let attr = match variant
+        .attrs
         .iter()
-        .filter_map(|variant| {
-            let flatten = Ident::new("flatten", Span::call_site());
-            variant
-                .attrs
-                .iter()
-                .filter_map(|attr| attr.path().get_ident())
-                .find(|ident| *ident == &flatten)
-                .map(|_| match &variant.fields {
-                    Fields::Unnamed(fields) => {
-                        if fields.unnamed.len() != 1 {
-                            panic!(
-                                "{} #[flattened], so it must have exactly 1 parameter",
-                                variant.ident
-                            );
-                        }
-                        let only_field = fields.unnamed.first().unwrap();
-                        VariantType::FlattenedVariant(&variant.ident, &only_field.ty)
-                    }
-                    _ => {
-                        panic!(
-                            "{} is #[flattened], so it must have a single unnamed parameter",
-                            variant.ident
-                        );
-                    }
-                })
-        })
-        .collect()
+        .find(|attr| attr.path().is_ident("mount"))
+    {
+        Some(attr) => attr,
+        None => return Ok(None),
+    };
+    let literal: LitStr = attr
+        .parse_args()
+        .map_err(|_| syn::Error::new_spanned(attr, "mount value must be a simple &str"))?;
+    let mount = normalize_prefix(&literal, "mount")?;
+    if mount.contains(['{', '}', '?']) {
+        return Err(syn::Error::new(
+            literal.span(),
+            "mount value can't capture variables or query parameters",
+        ));
+    }
+    Ok(Some(mount))
 }
 
-fn into_paths_by_method(routes: Vec<(Path, MethodTag)>) -> BTreeMap<MethodTag, Vec<Path>> {
-    let mut paths_by_method: BTreeMap<MethodTag, Vec<Path>> = BTreeMap::new();
-    for (path, method) in routes.into_iter() {
-        paths_by_method.entry(method).or_default().push(path);
+/// Escapes every `delimiter` in the `encoded` value of a variable that the delimiter follows
+/// within its segment, like the `.` of `{a}.{b}`, so the value can't end the variable's capture
+/// early. Delimiters `percent_encode` already escapes are left as is, and a letter is escaped in
+/// either case, since it may be matched case-insensitively.
+fn escape_delimiter(encoded: TokenStream, delimiter: char) -> TokenStream {
+    if !(delimiter.is_ascii_alphanumeric() || matches!(delimiter, '-' | '.' | '_' | '~')) {
+        return encoded;
+    }
+    let cases = match delimiter.is_ascii_alphabetic() {
+        true => vec![
+            delimiter.to_ascii_lowercase(),
+            delimiter.to_ascii_uppercase(),
+        ],
+        false => vec![delimiter],
+    };
+    cases.into_iter().fold(encoded, |escaped, case| {
+        let escape = format!("%{:02X}", case as u8);
+        quote! { #escaped.replace(#case, #escape) }
+    })
+}
+
+/// Groups the paths of a variant by method, in `MethodTag` order, keeping the declaration order of
+/// each method's paths.
+fn into_paths_by_method(routes: &[(Path, MethodTag)]) -> BTreeMap<&MethodTag, Vec<&Path>> {
+    let mut paths_by_method: BTreeMap<&MethodTag, Vec<&Path>> = BTreeMap::new();
+    for (path, method) in routes.iter() {
+        let paths = paths_by_method.entry(method).or_default();
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
     }
     paths_by_method
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paths_by_method_order() {
+        let span = Span::call_site();
+        let path = |template| Path::parse(template, span).unwrap();
+        let routes = [
+            (path("/b"), MethodTag::Patch),
+            (path("/b"), MethodTag::Post),
+            (path("/b"), MethodTag::Get),
+            (path("/a"), MethodTag::Get),
+            (path("/b"), MethodTag::Get),
+            (path("/c"), MethodTag::Delete),
+        ];
+        let paths_by_method: Vec<_> = into_paths_by_method(&routes)
+            .into_iter()
+            .map(|(method, paths)| {
+                let templates: Vec<_> = paths.iter().map(|path| path.template.as_str()).collect();
+                (method.clone(), templates)
+            })
+            .collect();
+        assert_eq!(
+            paths_by_method,
+            [
+                (MethodTag::Get, vec!["/b", "/a"]),
+                (MethodTag::Post, vec!["/b"]),
+                (MethodTag::Delete, vec!["/c"]),
+                (MethodTag::Patch, vec!["/b"]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use crate::{Error, HttpVersion, Method, Router, RouterResult, TargetForm};
+use core::ops::Range;
+
+/// A `RouterResult` that keeps the buffer the request was read into, created by `resolve_in`.
+///
+/// Instead of borrowing the parts of the request, it holds their byte ranges within the buffer,
+/// so a refcounted buffer like `Arc<[u8]>` or `bytes::Bytes` can be cloned into an async task
+/// along with the result, and the body sliced out of it without copying, e.g.
+/// `bytes.slice(result.body_range())`.
+#[derive(PartialEq, Debug, Clone)]
+pub struct BufferedRouterResult<B, T> {
+    /// The identified route from the HTTP request, see `RouterResult::route`.
+    pub route: Option<T>,
+
+    /// The declared pattern of the matched route, see `RouterResult::pattern`.
+    pub pattern: Option<&'static str>,
+
+    /// The method of the HTTP request.
+    pub method: Method,
+
+    /// The form of the request target, see `RouterResult::target_form`.
+    pub target_form: TargetForm,
+
+    /// The version of the HTTP protocol used in the request.
+    pub version: HttpVersion,
+
+    buffer: B,
+    authority: Option<Range<usize>>,
+    raw_path: Range<usize>,
+    query: Range<usize>,
+    raw_headers: Range<usize>,
+    raw_body: Range<usize>,
+}
+
+impl<B: AsRef<[u8]>, T> BufferedRouterResult<B, T> {
+    /// Borrows the result as a `RouterResult`, to use its helpers like `headers()`,
+    /// `query_pairs()` or `body()`.
+    pub fn as_router_result(&self) -> RouterResult<'_, &T> {
+        let buffer = self.buffer.as_ref();
+        RouterResult {
+            route: self.route.as_ref(),
+            pattern: self.pattern,
+            method: self.method,
+            authority: self.authority.clone().map(|range| str_at(buffer, range)),
+            target_form: self.target_form,
+            raw_path: &buffer[self.raw_path.clone()],
+            query: str_at(buffer, self.query.clone()),
+            version: self.version,
+            raw_headers: &buffer[self.raw_headers.clone()],
+            raw_body: &buffer[self.raw_body.clone()],
+            // `resolve_in` only routes requests strictly
+            lenient: false,
+        }
+    }
+
+    /// The range of the header block within the buffer, see `RouterResult::raw_headers`.
+    pub fn headers_range(&self) -> Range<usize> {
+        self.raw_headers.clone()
+    }
+
+    /// The range of the body within the buffer, everything after the blank line that ends the
+    /// headers, see `RouterResult::raw_body`.
+    pub fn body_range(&self) -> Range<usize> {
+        self.raw_body.clone()
+    }
+
+    /// The buffer the request was read into.
+    pub fn buffer(&self) -> &B {
+        &self.buffer
+    }
+
+    /// Gives back the buffer the request was read into.
+    pub fn into_buffer(self) -> B {
+        self.buffer
+    }
+}
+
+/// Parse and route an HTTP request held in `buffer`, keeping the buffer in the result.
+///
+/// This behaves like `Router::resolve`, but works on any byte container, like a `Vec<u8>`, an
+/// `Arc<[u8]>` or a `bytes::Bytes`, instead of a borrowed slice. `B::as_ref` must return the same
+/// bytes every time it's called, as every common container does.
+///
+/// Since the route is stored next to the buffer rather than borrowing from it, `T` must be a
+/// router for any lifetime, like a derived enum that only captures owned values such as `String`s
+/// or numbers.
+///
+/// # Arguments
+///
+/// * `buffer` - The buffer holding the raw HTTP request.
+///
+/// # Returns
+///
+/// Returns a `Result` containing the `BufferedRouterResult` if routing is successful. Otherwise,
+/// returns an `Error` indicating the reason for the failure.
+///
+/// # Examples
+///
+/// ```
+/// use front_line_router::{resolve_in, Method, Router};
+/// use std::sync::Arc;
+///
+/// struct Upload;
+///
+/// impl<'de> Router<'de> for Upload {
+///     fn handle_parsed(method: Method, path: &'de str) -> Option<Self> {
+///         (method == Method::Post && path == "/uploads").then_some(Upload)
+///     }
+/// }
+///
+/// let buffer: Arc<[u8]> = Arc::from(&b"POST /uploads HTTP/1.1\r\n\r\nhello"[..]);
+/// let result = resolve_in::<Upload, _>(buffer.clone()).unwrap();
+/// assert!(result.route.is_some());
+/// assert_eq!(&result.buffer()[result.body_range()], b"hello");
+/// ```
+pub fn resolve_in<T, B>(buffer: B) -> Result<BufferedRouterResult<B, T>, Error>
+where
+    T: for<'de> Router<'de>,
+    B: AsRef<[u8]>,
+{
+    let request = buffer.as_ref();
+    let result = T::resolve(request)?;
+    let authority = result
+        .authority
+        .map(|authority| range_of(request, authority.as_bytes()));
+    let raw_path = range_of(request, result.raw_path);
+    let query = range_of(request, result.query.as_bytes());
+    let raw_headers = range_of(request, result.raw_headers);
+    let raw_body = range_of(request, result.raw_body);
+    Ok(BufferedRouterResult {
+        route: result.route,
+        pattern: result.pattern,
+        method: result.method,
+        target_form: result.target_form,
+        version: result.version,
+        buffer,
+        authority,
+        raw_path,
+        query,
+        raw_headers,
+        raw_body,
+    })
+}
+
+/// The range `part` spans within `buffer`, which it was sliced from. An empty part that wasn't
+/// sliced from the buffer maps to an empty range at its start.
+pub(crate) fn range_of(buffer: &[u8], part: &[u8]) -> Range<usize> {
+    let start = (part.as_ptr() as usize).wrapping_sub(buffer.as_ptr() as usize);
+    match start <= buffer.len() && part.len() <= buffer.len() - start {
+        true => start..start + part.len(),
+        false => 0..0,
+    }
+}
+
+/// The UTF-8 text at `range` of `buffer`, which was validated when the request was parsed.
+fn str_at(buffer: &[u8], range: Range<usize>) -> &str {
+    core::str::from_utf8(&buffer[range]).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[derive(PartialEq, Debug, Clone)]
+    enum UploadRoute {
+        Upload(String),
+    }
+
+    impl<'de> Router<'de> for UploadRoute {
+        fn handle_parsed(method: Method, remaining_path: &'de str) -> Option<Self> {
+            match (method, remaining_path.strip_prefix("/uploads/")) {
+                (Method::Post, Some(name)) => Some(UploadRoute::Upload(name.to_string())),
+                _ => None,
+            }
+        }
+    }
+
+    const REQUEST: &[u8] =
+        b"POST http://example.com/uploads/a.bin?v=2 HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello";
+
+    #[test]
+    fn test_resolve_in_matches_resolve() {
+        let expected = UploadRoute::resolve(REQUEST).unwrap();
+        let vec = resolve_in::<UploadRoute, _>(REQUEST.to_vec()).unwrap();
+        assert_eq!(vec.as_router_result().map_route(Clone::clone), expected);
+        let arc = resolve_in::<UploadRoute, Arc<[u8]>>(Arc::from(REQUEST)).unwrap();
+        assert_eq!(arc.as_router_result().map_route(Clone::clone), expected);
+        let slice = resolve_in::<UploadRoute, _>(REQUEST).unwrap();
+        assert_eq!(slice.as_router_result().map_route(Clone::clone), expected);
+    }
+
+    #[test]
+    fn test_ranges() {
+        let result = resolve_in::<UploadRoute, _>(REQUEST.to_vec()).unwrap();
+        assert_eq!(&REQUEST[result.headers_range()], b"Content-Length: 5\r\n");
+        assert_eq!(&REQUEST[result.body_range()], b"hello");
+        assert_eq!(result.clone().into_buffer(), REQUEST);
+        assert_eq!(result.buffer(), REQUEST);
+    }
+
+    #[test]
+    fn test_resolve_in_error() {
+        let result = resolve_in::<UploadRoute, _>(b"POST /uploads/a.bin HTTP/1.1\r\n".to_vec());
+        assert_eq!(result, Err(Error::MissingHeaderTerminator));
+    }
+
+    #[test]
+    fn test_range_of() {
+        let buffer = b"abcdef";
+        assert_eq!(range_of(buffer, &buffer[2..4]), 2..4);
+        assert_eq!(range_of(buffer, &buffer[6..]), 6..6);
+        assert_eq!(range_of(&buffer[2..], &buffer[..4]), 0..0);
+    }
+}
@@ -0,0 +1,449 @@
+use crate::method::Method;
+use crate::parsed_request::ParsedRequest;
+use crate::router::Error;
+use crate::RouterResult;
+use std::collections::BTreeMap;
+
+/// The error returned when a pattern passed to `RouterBuilder::add` can't be parsed.
+#[derive(thiserror::Error, Eq, PartialEq, Clone, Debug)]
+#[error("invalid route pattern {pattern}: {reason}")]
+pub struct PatternError {
+    pattern: String,
+    reason: &'static str,
+}
+
+impl PatternError {
+    fn new(pattern: &str, reason: &'static str) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            reason,
+        }
+    }
+
+    /// The pattern that failed to parse.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Why the pattern failed to parse.
+    pub fn reason(&self) -> &'static str {
+        self.reason
+    }
+}
+
+/// Collects routes registered at runtime, e.g. by plugins, into a `DynRouter`.
+///
+/// Patterns use a subset of the `front_line::FrontLine` derive's syntax: literal text with `{{`
+/// and `}}` for literal braces, `{name}` variables that capture up to the next `/` (or up to the
+/// literal that follows them within the segment), and a final `{*name}` or `{**name}` catch-all
+/// that captures the rest of the path. Constraints like `{id:\d+}`, optional variables like `{id?}` and query
+/// parameters aren't supported, and `RouterBuilder::add` rejects them with a `PatternError`.
+/// Routes are tried in the order they were added, and a single trailing slash is tolerated.
+///
+/// # Examples
+///
+/// ```
+/// use front_line_router::{Method, RouterBuilder};
+///
+/// let router = RouterBuilder::new()
+///     .add(Method::Get, "/plugins/{id}", "get_plugin")?
+///     .add(Method::Get, "/plugins/{id}/assets/{*path}", "get_asset")?
+///     .build();
+///
+/// let matched = router.handle(Method::Get, "/plugins/7/assets/css/app.css").unwrap();
+/// assert_eq!(*matched.handler, "get_asset");
+/// assert_eq!(matched.captures["id"], "7");
+/// assert_eq!(matched.captures["path"], "css/app.css");
+/// # Ok::<(), front_line_router::PatternError>(())
+/// ```
+pub struct RouterBuilder<H> {
+    routes: Vec<DynRoute<H>>,
+}
+
+impl<H> RouterBuilder<H> {
+    /// Creates a builder without any routes.
+    ///
+    /// # Returns
+    ///
+    /// Returns an empty builder, which builds a `DynRouter` that matches nothing until routes
+    /// are registered with `add`.
+    pub fn new() -> Self {
+        Self { routes: Vec::new() }
+    }
+
+    /// Registers `handler` for requests with `method` whose path matches `pattern`.
+    ///
+    /// # Returns
+    ///
+    /// Returns the builder with the route added, or a `PatternError` if `pattern` doesn't start
+    /// with `/`, has an unescaped `}` or a `?`, has an unterminated variable, a variable that
+    /// isn't named like a field or whose name is taken, has two variables without a literal
+    /// between them, or has a catch-all that isn't the final part of the pattern.
+    pub fn add(mut self, method: Method, pattern: &str, handler: H) -> Result<Self, PatternError> {
+        self.routes.push(DynRoute {
+            method,
+            parts: parse_pattern(pattern)?,
+            pattern: pattern.to_string(),
+            handler,
+        });
+        Ok(self)
+    }
+
+    /// Builds the router from the routes registered so far.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `DynRouter` that tries the routes in the order they were added. Duplicate and
+    /// overlapping registrations are kept as they are, neither merged nor rejected, so for a path
+    /// that several routes of the same method match, the one added first wins, and a later route
+    /// whose paths are all matched by earlier ones, like a duplicate, is never matched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use front_line_router::{Method, RouterBuilder};
+    ///
+    /// let router = RouterBuilder::new()
+    ///     .add(Method::Get, "/plugins/{id}", "get_plugin")?
+    ///     .add(Method::Get, "/plugins/latest", "get_latest")?
+    ///     .add(Method::Get, "/plugins/{name}", "get_by_name")?
+    ///     .build();
+    ///
+    /// let matched = router.handle(Method::Get, "/plugins/latest").unwrap();
+    /// assert_eq!(*matched.handler, "get_plugin");
+    /// # Ok::<(), front_line_router::PatternError>(())
+    /// ```
+    pub fn build(self) -> DynRouter<H> {
+        DynRouter {
+            routes: self.routes,
+        }
+    }
+}
+
+impl<H> Default for RouterBuilder<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A router over routes registered at runtime through a `RouterBuilder`.
+pub struct DynRouter<H> {
+    routes: Vec<DynRoute<H>>,
+}
+
+/// A route matched by a `DynRouter`.
+#[derive(PartialEq, Debug)]
+pub struct DynMatch<'r, 'p, H> {
+    /// The handler the matched route was registered with.
+    pub handler: &'r H,
+
+    /// The pattern the matched route was registered with, e.g. `/plugins/{id}`.
+    pub pattern: &'r str,
+
+    /// The captured path segments, keyed by the name of their variable.
+    pub captures: BTreeMap<&'r str, &'p str>,
+}
+
+impl<H> DynRouter<H> {
+    /// Handle the parsed method and path segment.
+    ///
+    /// # Returns
+    ///
+    /// Returns the first route, in the order they were added, that matches. Otherwise, returns
+    /// `None`.
+    pub fn handle<'r, 'p>(&'r self, method: Method, path: &'p str) -> Option<DynMatch<'r, 'p, H>> {
+        self.routes
+            .iter()
+            .filter(|route| route.method == method)
+            .find_map(|route| route.matches(path))
+    }
+
+    /// Parse and route an HTTP request.
+    ///
+    /// This behaves like `Router::resolve`, with the matched route in `RouterResult::route`. The
+    /// pattern is reported by `DynMatch::pattern` rather than `RouterResult::pattern`, which only
+    /// holds patterns known at compile time.
+    pub fn resolve<'r, 'de>(
+        &'r self,
+        request: &'de [u8],
+    ) -> Result<RouterResult<'de, DynMatch<'r, 'de, H>>, Error> {
+        let parsed = ParsedRequest::parse(request)?;
+        let path = parsed.path_str()?;
+        let route = match parsed.target_form.is_routable() {
+            true => self.handle(parsed.method, path),
+            false => None,
+        };
+        Ok(parsed.into_result(route, None))
+    }
+}
+
+struct DynRoute<H> {
+    method: Method,
+    parts: Vec<Part>,
+    pattern: String,
+    handler: H,
+}
+
+enum Part {
+    Literal(String),
+    Variable(String),
+    CatchAll(String),
+}
+
+impl<H> DynRoute<H> {
+    fn matches<'r, 'p>(&'r self, path: &'p str) -> Option<DynMatch<'r, 'p, H>> {
+        let mut captures = BTreeMap::new();
+        let mut remaining = path;
+        for (offset, part) in self.parts.iter().enumerate() {
+            match part {
+                Part::Literal(literal) => remaining = remaining.strip_prefix(literal.as_str())?,
+                Part::Variable(name) => {
+                    let mut end = remaining.find('/').unwrap_or(remaining.len());
+                    if let Some(Part::Literal(next)) = self.parts.get(offset + 1) {
+                        if !next.starts_with('/') {
+                            let suffix = &next[..next.find('/').unwrap_or(next.len())];
+                            end = remaining[..end].find(suffix)?;
+                        }
+                    }
+                    captures.insert(name.as_str(), &remaining[..end]);
+                    remaining = &remaining[end..];
+                }
+                Part::CatchAll(name) => {
+                    captures.insert(name.as_str(), remaining);
+                    remaining = "";
+                }
+            }
+        }
+        if !remaining.is_empty() && remaining != "/" {
+            return None;
+        }
+        Some(DynMatch {
+            handler: &self.handler,
+            pattern: &self.pattern,
+            captures,
+        })
+    }
+}
+
+fn parse_pattern(pattern: &str) -> Result<Vec<Part>, PatternError> {
+    if !pattern.starts_with('/') {
+        return Err(PatternError::new(pattern, "patterns must start with '/'"));
+    }
+    let mut parts = Vec::new();
+    let mut names = Vec::new();
+    let mut literal = String::new();
+    let mut rest = pattern;
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix("{{").or_else(|| rest.strip_prefix("}}")) {
+            literal.push(c);
+            rest = after;
+            continue;
+        }
+        match c {
+            '}' => {
+                return Err(PatternError::new(
+                    pattern,
+                    "literal braces must be written as {{ or }}",
+                ))
+            }
+            '?' => {
+                return Err(PatternError::new(
+                    pattern,
+                    "query parameters can't be matched",
+                ))
+            }
+            '{' => {}
+            _ => {
+                literal.push(c);
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(core::mem::take(&mut literal)));
+        }
+        let variable_end = rest
+            .find('}')
+            .ok_or_else(|| PatternError::new(pattern, "variables must end with '}'"))?;
+        let variable = &rest[1..variable_end];
+        // `{**name}` only differs from `{*name}` in how the derive parses its field, and the
+        // captures here are plain strings
+        let catch_all = variable
+            .strip_prefix("**")
+            .or_else(|| variable.strip_prefix('*'));
+        let (name, catch_all) = match catch_all {
+            Some(name) => (name, true),
+            None => (variable, false),
+        };
+        let reason = if name.is_empty() {
+            Some("variables must have a name")
+        } else if name.contains(':') {
+            Some("variables can't have constraints")
+        } else if name.ends_with('?') {
+            Some("variables can't be optional")
+        } else if !is_identifier(name) {
+            Some("variables must be named like a field, e.g. {user_id}")
+        } else if names.contains(&name) {
+            Some("variables must have unique names")
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            return Err(PatternError::new(pattern, reason));
+        }
+        if let Some(Part::Variable(_) | Part::CatchAll(_)) = parts.last() {
+            return Err(PatternError::new(
+                pattern,
+                "variables must be separated by a literal",
+            ));
+        }
+        names.push(name);
+        parts.push(match catch_all {
+            true => Part::CatchAll(name.to_string()),
+            false => Part::Variable(name.to_string()),
+        });
+        rest = &rest[variable_end + 1..];
+    }
+    if !literal.is_empty() {
+        parts.push(Part::Literal(literal));
+    }
+    let catch_all = parts
+        .iter()
+        .position(|part| matches!(part, Part::CatchAll(_)));
+    if catch_all.is_some_and(|position| position != parts.len() - 1) {
+        return Err(PatternError::new(
+            pattern,
+            "a catch-all must be the final part of the pattern",
+        ));
+    }
+    Ok(parts)
+}
+
+/// Whether `name` is an ASCII identifier, which is all a variable name may be.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HttpVersion, TargetForm};
+    use rstest::rstest;
+
+    fn router() -> DynRouter<u32> {
+        RouterBuilder::new()
+            .add(Method::Get, "/plugins", 1)
+            .and_then(|builder| builder.add(Method::Get, "/plugins/{id}", 2))
+            .and_then(|builder| builder.add(Method::Put, "/plugins/{id}", 3))
+            .and_then(|builder| builder.add(Method::Get, "/plugins/{id}/assets/{*path}", 4))
+            .and_then(|builder| builder.add(Method::Get, "/files/{name}.json", 5))
+            .and_then(|builder| builder.add(Method::Get, "/v{version}/status", 6))
+            .and_then(|builder| builder.add(Method::Get, "/{{raw}}/{id}", 7))
+            .and_then(|builder| builder.add(Method::Get, "/sums/{**values}", 8))
+            .map(RouterBuilder::build)
+            .unwrap()
+    }
+
+    #[rstest]
+    #[case(Method::Get, "/plugins", Some((1, vec![])))]
+    #[case(Method::Get, "/plugins/", Some((1, vec![])))]
+    #[case(Method::Get, "/plugins/7", Some((2, vec![("id", "7")])))]
+    #[case(Method::Put, "/plugins/7", Some((3, vec![("id", "7")])))]
+    #[case(
+        Method::Get,
+        "/plugins/7/assets/css/app.css",
+        Some((4, vec![("id", "7"), ("path", "css/app.css")]))
+    )]
+    #[case(Method::Get, "/files/a.json", Some((5, vec![("name", "a")])))]
+    #[case(Method::Get, "/v2/status", Some((6, vec![("version", "2")])))]
+    #[case(Method::Get, "/{raw}/7", Some((7, vec![("id", "7")])))]
+    #[case(Method::Get, "/sums/1/2", Some((8, vec![("values", "1/2")])))]
+    #[case(Method::Get, "/files/a.txt", None)]
+    #[case(Method::Get, "/plugins/7/other", None)]
+    #[case(Method::Delete, "/plugins/7", None)]
+    #[case(Method::Get, "/unknown", None)]
+    fn test_handle(
+        #[case] method: Method,
+        #[case] path: &str,
+        #[case] expected: Option<(u32, Vec<(&str, &str)>)>,
+    ) {
+        let router = router();
+        let matched = router
+            .handle(method, path)
+            .map(|matched| (*matched.handler, matched.captures.into_iter().collect()));
+        assert_eq!(matched, expected);
+    }
+
+    #[test]
+    fn test_resolve() {
+        let router = router();
+        let result = router.resolve(b"GET /plugins/7?a=b HTTP/1.1\r\nHost: a\r\n\r\nbody");
+        let mut captures = BTreeMap::new();
+        captures.insert("id", "7");
+        assert_eq!(
+            result,
+            Ok(RouterResult {
+                route: Some(DynMatch {
+                    handler: &2,
+                    pattern: "/plugins/{id}",
+                    captures,
+                }),
+                pattern: None,
+                method: Method::Get,
+                authority: None,
+                target_form: TargetForm::Origin,
+                raw_path: b"/plugins/7",
+                query: "a=b",
+                version: HttpVersion::OneOne,
+                raw_headers: b"Host: a\r\n",
+                raw_body: b"body",
+                lenient: false,
+            })
+        );
+        assert_eq!(
+            router.resolve(b"GET /plugins HTTP/1.1"),
+            Err(Error::MissingHeaderTerminator)
+        );
+    }
+
+    #[rstest]
+    #[case("plugins", "patterns must start with '/'")]
+    #[case("/plugins/{id", "variables must end with '}'")]
+    #[case("/plugins/{}", "variables must have a name")]
+    #[case("/plugins/{*}", "variables must have a name")]
+    #[case("/plugins/{id}{name}", "variables must be separated by a literal")]
+    #[case(
+        "/plugins/{***path}",
+        "variables must be named like a field, e.g. {user_id}"
+    )]
+    #[case(
+        "/plugins/{id-x}",
+        "variables must be named like a field, e.g. {user_id}"
+    )]
+    #[case(
+        "/plugins/{1x}",
+        "variables must be named like a field, e.g. {user_id}"
+    )]
+    #[case("/plugins/{id:\\d+}", "variables can't have constraints")]
+    #[case("/plugins/{id?}", "variables can't be optional")]
+    #[case("/plugins?{q}", "query parameters can't be matched")]
+    #[case("/plugins/}", "literal braces must be written as {{ or }}")]
+    #[case("/plugins/{id}/{id}", "variables must have unique names")]
+    #[case("/plugins/{id}/{*id}", "variables must have unique names")]
+    #[case(
+        "/plugins/{*path}/edit",
+        "a catch-all must be the final part of the pattern"
+    )]
+    fn test_pattern_error(#[case] pattern: &str, #[case] reason: &str) {
+        let error = RouterBuilder::new()
+            .add(Method::Get, pattern, ())
+            .err()
+            .unwrap();
+        assert_eq!(error.pattern(), pattern);
+        assert_eq!(error.reason(), reason);
+    }
+}
@@ -1,3 +1,37 @@
+use crate::segments::{segments, Segments};
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+};
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+
+/// The reason a path variable couldn't be parsed, as reported by
+/// `FromRoute::try_parse_path_variable`.
+///
+/// With the `std` feature enabled this implements `std::error::Error` and `Display`, so it can be
+/// turned into a 400 response body directly.
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[cfg_attr(feature = "std", error("expected {expected}"))]
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct ParseError {
+    expected: &'static str,
+}
+
+impl ParseError {
+    /// Creates an error describing what the path variable was expected to be, e.g.
+    /// `"a positive integer"`.
+    pub const fn new(expected: &'static str) -> Self {
+        Self { expected }
+    }
+
+    /// What the path variable was expected to be.
+    pub const fn expected(&self) -> &'static str {
+        self.expected
+    }
+}
+
 /// A trait to enable zero-copy parsing from route paths.
 ///
 /// This trait is designed as an alternative to `FromStr` to support both zero-copy and copy parsing.
@@ -47,6 +81,11 @@
 /// // Given a route segment "/user/alice", "alice" can be parsed directly as UserName
 /// // without creating a new String.
 /// ```
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be captured by a path variable",
+    label = "this field must implement `FromRoute`",
+    note = "implement `FromRoute` for `{Self}`, or parse the field with `#[parse_with(...)]`"
+)]
 pub trait FromRoute<'de>: Sized {
     /// Parses a value from a route segment.
     ///
@@ -59,6 +98,44 @@ pub trait FromRoute<'de>: Sized {
     /// Returns `Some(T)` if the segment can be successfully parsed into type `T`. Otherwise,
     /// returns `None`.
     fn parse_path_variable(slice: &'de str) -> Option<Self>;
+
+    /// Parses a value from a route segment, reporting why it failed.
+    ///
+    /// The default implementation delegates to `parse_path_variable` and reports the name of the
+    /// type as what was expected. Override it to give a more helpful reason.
+    ///
+    /// # Arguments
+    ///
+    /// * `slice` - A segment of a route, typically a part between slashes in a URL.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(T)` if the segment can be successfully parsed into type `T`. Otherwise,
+    /// returns the `ParseError` describing what was expected.
+    fn try_parse_path_variable(slice: &'de str) -> Result<Self, ParseError> {
+        Self::parse_path_variable(slice)
+            .ok_or_else(|| ParseError::new(core::any::type_name::<Self>()))
+    }
+
+    /// Parses a value from the raw bytes of a route segment, which may not be valid UTF-8.
+    ///
+    /// Generated routers parse captures with this method. The default implementation validates
+    /// the bytes as UTF-8 and delegates to `parse_path_variable`, so a segment that isn't valid
+    /// UTF-8 yields `None`. Override it for types that can hold any bytes, like `&[u8]`.
+    ///
+    /// # Arguments
+    ///
+    /// * `slice` - The raw bytes of a segment of a route.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(T)` if the segment can be successfully parsed into type `T`. Otherwise,
+    /// returns `None`.
+    fn parse_path_bytes(slice: &'de [u8]) -> Option<Self> {
+        core::str::from_utf8(slice)
+            .ok()
+            .and_then(Self::parse_path_variable)
+    }
 }
 
 impl<'de> FromRoute<'de> for bool {
@@ -71,112 +148,376 @@ impl<'de> FromRoute<'de> for bool {
     }
 }
 
-impl<'de> FromRoute<'de> for u8 {
+/// Parses an integer straight from the ASCII digits of a capture, so its bytes don't need a
+/// separate UTF-8 validation pass first. It accepts what `str::parse` does: an optional `+`, or a
+/// `-` for signed types, followed by at least one digit, without overflowing.
+macro_rules! impl_integer_from_route {
+    ($($ty:ty),*) => {
+        $(
+            impl<'de> FromRoute<'de> for $ty {
+                fn parse_path_variable(slice: &'de str) -> Option<Self> {
+                    Self::parse_path_bytes(slice.as_bytes())
+                }
+
+                fn parse_path_bytes(slice: &'de [u8]) -> Option<Self> {
+                    let (negative, digits) = match slice {
+                        [b'-', digits @ ..] if <$ty>::MIN != 0 => (true, digits),
+                        [b'+', digits @ ..] => (false, digits),
+                        digits => (false, digits),
+                    };
+                    if digits.is_empty() {
+                        return None;
+                    }
+                    let mut value: $ty = 0;
+                    for byte in digits {
+                        if !byte.is_ascii_digit() {
+                            return None;
+                        }
+                        let digit = (byte - b'0') as $ty;
+                        value = value.checked_mul(10)?;
+                        value = match negative {
+                            true => value.checked_sub(digit)?,
+                            false => value.checked_add(digit)?,
+                        };
+                    }
+                    Some(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_from_route!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Parses a non-zero integer like the integer it wraps, rejecting zero.
+macro_rules! impl_non_zero_from_route {
+    ($($ty:ty => $inner:ty),*) => {
+        $(
+            impl<'de> FromRoute<'de> for $ty {
+                fn parse_path_variable(slice: &'de str) -> Option<Self> {
+                    Self::parse_path_bytes(slice.as_bytes())
+                }
+
+                fn parse_path_bytes(slice: &'de [u8]) -> Option<Self> {
+                    <$inner>::parse_path_bytes(slice).and_then(Self::new)
+                }
+            }
+        )*
+    };
+}
+
+impl_non_zero_from_route!(
+    NonZeroU8 => u8,
+    NonZeroU16 => u16,
+    NonZeroU32 => u32,
+    NonZeroU64 => u64,
+    NonZeroU128 => u128,
+    NonZeroUsize => usize,
+    NonZeroI8 => i8,
+    NonZeroI16 => i16,
+    NonZeroI32 => i32,
+    NonZeroI64 => i64,
+    NonZeroI128 => i128,
+    NonZeroIsize => isize
+);
+
+impl<'de> FromRoute<'de> for f32 {
     fn parse_path_variable(slice: &'de str) -> Option<Self> {
         slice.parse().ok()
     }
 }
 
-impl<'de> FromRoute<'de> for u16 {
+impl<'de> FromRoute<'de> for f64 {
     fn parse_path_variable(slice: &'de str) -> Option<Self> {
         slice.parse().ok()
     }
 }
 
-impl<'de> FromRoute<'de> for u32 {
+impl<'de> FromRoute<'de> for char {
     fn parse_path_variable(slice: &'de str) -> Option<Self> {
-        slice.parse().ok()
+        let mut chars = slice.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => None,
+        }
     }
 }
 
-impl<'de> FromRoute<'de> for u64 {
+impl<'de> FromRoute<'de> for IpAddr {
     fn parse_path_variable(slice: &'de str) -> Option<Self> {
         slice.parse().ok()
     }
 }
 
-impl<'de> FromRoute<'de> for u128 {
+impl<'de> FromRoute<'de> for Ipv4Addr {
     fn parse_path_variable(slice: &'de str) -> Option<Self> {
         slice.parse().ok()
     }
 }
 
-impl<'de> FromRoute<'de> for usize {
+impl<'de> FromRoute<'de> for Ipv6Addr {
     fn parse_path_variable(slice: &'de str) -> Option<Self> {
         slice.parse().ok()
     }
 }
 
-impl<'de> FromRoute<'de> for i8 {
+impl<'de> FromRoute<'de> for SocketAddr {
     fn parse_path_variable(slice: &'de str) -> Option<Self> {
         slice.parse().ok()
     }
 }
 
-impl<'de> FromRoute<'de> for i16 {
+impl<'de> FromRoute<'de> for SocketAddrV4 {
     fn parse_path_variable(slice: &'de str) -> Option<Self> {
         slice.parse().ok()
     }
 }
 
-impl<'de> FromRoute<'de> for i32 {
+impl<'de> FromRoute<'de> for SocketAddrV6 {
     fn parse_path_variable(slice: &'de str) -> Option<Self> {
         slice.parse().ok()
     }
 }
 
-impl<'de> FromRoute<'de> for i64 {
+/// Captures the raw bytes of a path variable, which don't have to be valid UTF-8.
+impl<'de> FromRoute<'de> for &'de [u8] {
     fn parse_path_variable(slice: &'de str) -> Option<Self> {
-        slice.parse().ok()
+        Some(slice.as_bytes())
+    }
+
+    fn parse_path_bytes(slice: &'de [u8]) -> Option<Self> {
+        Some(slice)
     }
 }
 
-impl<'de> FromRoute<'de> for i128 {
+impl<'de> FromRoute<'de> for &'de str {
     fn parse_path_variable(slice: &'de str) -> Option<Self> {
-        slice.parse().ok()
+        Some(slice)
     }
 }
 
-impl<'de> FromRoute<'de> for isize {
+#[cfg(feature = "std")]
+impl<'de> FromRoute<'de> for String {
     fn parse_path_variable(slice: &'de str) -> Option<Self> {
-        slice.parse().ok()
+        Some(slice.to_owned())
     }
 }
 
-impl<'de> FromRoute<'de> for f32 {
-    fn parse_path_variable(slice: &'de str) -> Option<Self> {
-        slice.parse().ok()
+/// A path variable that's decoded as latin-1 (ISO-8859-1) when it isn't valid UTF-8, for legacy
+/// clients that send latin-1 paths.
+///
+/// A capture that's valid UTF-8 is borrowed as is, and any other capture is decoded byte by byte
+/// into an owned string, mapping each byte to the character with the same code point. Since every
+/// byte is a latin-1 character, a `Latin1` capture never fails to parse.
+///
+/// # Examples
+///
+/// ```
+/// use front_line_router::{FromRoute, Latin1};
+///
+/// let name = Latin1::parse_path_bytes(b"caf\xe9").unwrap();
+/// assert_eq!(name.as_str(), "café");
+/// ```
+#[cfg(feature = "std")]
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Latin1<'a>(pub Cow<'a, str>);
+
+#[cfg(feature = "std")]
+impl Latin1<'_> {
+    /// The decoded capture.
+    pub fn as_str(&self) -> &str {
+        &self.0
     }
 }
 
-impl<'de> FromRoute<'de> for f64 {
+#[cfg(feature = "std")]
+impl core::ops::Deref for Latin1<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'de> FromRoute<'de> for Latin1<'de> {
     fn parse_path_variable(slice: &'de str) -> Option<Self> {
-        slice.parse().ok()
+        Some(Latin1(Cow::Borrowed(slice)))
+    }
+
+    fn parse_path_bytes(slice: &'de [u8]) -> Option<Self> {
+        let decoded = match core::str::from_utf8(slice) {
+            Ok(slice) => Cow::Borrowed(slice),
+            Err(_) => Cow::Owned(slice.iter().map(|&byte| char::from(byte)).collect()),
+        };
+        Some(Latin1(decoded))
     }
 }
 
-impl<'de> FromRoute<'de> for &'de [u8] {
+/// The delimiter between the elements of array and tuple captures.
+const LIST_DELIMITER: char = ',';
+
+/// Captures `N` comma separated values from a single path variable, e.g. `1,2,3` for `[u32; 3]`.
+///
+/// The slice must hold exactly `N` elements that each parse as `T`, otherwise it yields `None`.
+/// Elements are split on every comma, so they can't contain commas themselves.
+impl<'de, T: FromRoute<'de>, const N: usize> FromRoute<'de> for [T; N] {
     fn parse_path_variable(slice: &'de str) -> Option<Self> {
-        Some(slice.as_bytes())
+        let mut elements = slice.split(LIST_DELIMITER);
+        let parsed = [(); N].map(|_| elements.next().and_then(T::parse_path_variable));
+        if elements.next().is_some() || parsed.iter().any(Option::is_none) {
+            return None;
+        }
+        Some(parsed.map(|element| element.expect("every element was parsed")))
     }
 }
 
-impl<'de> FromRoute<'de> for &'de str {
+/// Captures comma separated values of different types from a single path variable, e.g. `1.5,2`
+/// for `(f64, u32)`.
+///
+/// Implemented for tuples of two to four elements. Like arrays, the slice must hold exactly one
+/// element per field of the tuple, each parsed by its own `FromRoute`.
+macro_rules! impl_tuple_from_route {
+    ($( $element:ident ),+) => {
+        impl<'de, $( $element: FromRoute<'de> ),+> FromRoute<'de> for ($( $element, )+) {
+            fn parse_path_variable(slice: &'de str) -> Option<Self> {
+                let mut elements = slice.split(LIST_DELIMITER);
+                let parsed = ($( $element::parse_path_variable(elements.next()?)?, )+);
+                match elements.next() {
+                    Some(_) => None,
+                    None => Some(parsed),
+                }
+            }
+        }
+    };
+}
+
+impl_tuple_from_route!(A, B);
+impl_tuple_from_route!(A, B, C);
+impl_tuple_from_route!(A, B, C, D);
+
+/// Captures a path variable that may be empty or unparseable.
+///
+/// An empty slice or a slice that `T` fails to parse yields `Some(None)` instead of `None`.
+/// This changes match semantics: an `Option` field never causes its route to be skipped, so a
+/// route like `/items/{id}` with `id: Option<u32>` also matches `/items/` and `/items/abc`.
+impl<'de, T: FromRoute<'de>> FromRoute<'de> for Option<T> {
     fn parse_path_variable(slice: &'de str) -> Option<Self> {
-        Some(slice)
+        if slice.is_empty() {
+            return Some(None);
+        }
+        Some(T::parse_path_variable(slice))
+    }
+
+    fn parse_path_bytes(slice: &'de [u8]) -> Option<Self> {
+        if slice.is_empty() {
+            return Some(None);
+        }
+        Some(T::parse_path_bytes(slice))
     }
 }
 
-impl<'de> FromRoute<'de> for String {
+/// Captures a path variable along with the reason it failed to parse.
+///
+/// Like `Option<T>`, a `Result` field never causes its route to be skipped. Instead, a slice that
+/// `T` fails to parse yields the `ParseError` from `T::try_parse_path_variable`, so the route can
+/// respond with a 400 that explains what was expected rather than a generic 404.
+impl<'de, T: FromRoute<'de>> FromRoute<'de> for Result<T, ParseError> {
     fn parse_path_variable(slice: &'de str) -> Option<Self> {
-        Some(slice.to_owned())
+        Some(T::try_parse_path_variable(slice))
+    }
+
+    fn parse_path_bytes(slice: &'de [u8]) -> Option<Self> {
+        match core::str::from_utf8(slice) {
+            Ok(slice) => Self::parse_path_variable(slice),
+            Err(_) => Some(T::parse_path_bytes(slice).ok_or(ParseError::new("valid utf-8"))),
+        }
+    }
+}
+
+/// A trait for types that parse the rest of a path, slashes included, from a `{**name}` variable.
+///
+/// A `{**name}` variable is a catch-all like `{*name}`, so it must be the final part of its path,
+/// but its field is parsed with `FromRouteRest` instead of `FromRoute`. That lets a type consume
+/// several segments at once, e.g. `Vec<u32>` from `1/2/3`, while keeping `FromRoute` about a
+/// single segment.
+///
+/// # Examples
+///
+/// ```
+/// use front_line_router::FromRouteRest;
+///
+/// // A path to a file, like `css/app.css`, that can't escape its root directory.
+/// struct FilePath<'a>(&'a str);
+///
+/// impl<'de> FromRouteRest<'de> for FilePath<'de> {
+///     fn parse_path_rest(rest: &'de str) -> Option<Self> {
+///         let escapes = rest.split('/').any(|segment| segment == "..");
+///         (!rest.is_empty() && !escapes).then_some(FilePath(rest))
+///     }
+/// }
+/// ```
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be captured by a `{{**name}}` catch-all",
+    label = "this field must implement `FromRouteRest`",
+    note = "implement `FromRouteRest` for `{Self}`, or parse the field with `#[parse_with(...)]`"
+)]
+pub trait FromRouteRest<'de>: Sized {
+    /// Parses a value from the rest of a path.
+    ///
+    /// # Arguments
+    ///
+    /// * `rest` - The rest of the path after the literal before the variable, without a query.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(T)` if the rest of the path can be successfully parsed into type `T`.
+    /// Otherwise, returns `None`.
+    fn parse_path_rest(rest: &'de str) -> Option<Self>;
+}
+
+impl<'de> FromRouteRest<'de> for &'de str {
+    fn parse_path_rest(rest: &'de str) -> Option<Self> {
+        Some(rest)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'de> FromRouteRest<'de> for String {
+    fn parse_path_rest(rest: &'de str) -> Option<Self> {
+        Some(rest.to_owned())
+    }
+}
+
+/// Captures the rest of a path as its `/` delimited segments, split like `segments`.
+impl<'de> FromRouteRest<'de> for Segments<'de> {
+    fn parse_path_rest(rest: &'de str) -> Option<Self> {
+        Some(segments(rest))
+    }
+}
+
+/// Captures every `/` delimited segment of the rest of a path as a `T`, e.g. `1/2/3` for
+/// `Vec<u32>`.
+///
+/// The rest is split like `segments`, so a single trailing slash is ignored and an empty rest
+/// yields an empty `Vec`. Every segment must parse as `T`, otherwise it yields `None`.
+#[cfg(feature = "std")]
+impl<'de, T: FromRoute<'de>> FromRouteRest<'de> for Vec<T> {
+    fn parse_path_rest(rest: &'de str) -> Option<Self> {
+        segments(rest).map(T::parse_path_variable).collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::FromRoute;
+    use super::{FromRoute, FromRouteRest, Latin1, ParseError};
     use rstest::rstest;
+    use std::borrow::Cow;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+    use std::num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+        NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+    };
 
     #[rstest]
     #[case("true", Some(true))]
@@ -319,6 +660,126 @@ mod tests {
         assert_eq!(f64::parse_path_variable(input), expected);
     }
 
+    #[rstest]
+    #[case("42", NonZeroU32::new(42))]
+    #[case("4294967295", NonZeroU32::new(4294967295))]
+    #[case("0", None)]
+    #[case("-1", None)]
+    #[case("not_a_number", None)]
+    fn test_non_zero_u32(#[case] input: &str, #[case] expected: Option<NonZeroU32>) {
+        assert_eq!(NonZeroU32::parse_path_variable(input), expected);
+    }
+
+    #[rstest]
+    #[case("42", NonZeroI32::new(42))]
+    #[case("-42", NonZeroI32::new(-42))]
+    #[case("0", None)]
+    #[case("-0", None)]
+    #[case("2147483648", None)]
+    fn test_non_zero_i32(#[case] input: &str, #[case] expected: Option<NonZeroI32>) {
+        assert_eq!(NonZeroI32::parse_path_variable(input), expected);
+    }
+
+    #[test]
+    fn test_non_zero_unsigned() {
+        assert_eq!(NonZeroU8::parse_path_variable("1"), NonZeroU8::new(1));
+        assert_eq!(NonZeroU8::parse_path_variable("0"), None);
+        assert_eq!(NonZeroU16::parse_path_variable("1"), NonZeroU16::new(1));
+        assert_eq!(NonZeroU16::parse_path_variable("0"), None);
+        assert_eq!(NonZeroU64::parse_path_variable("1"), NonZeroU64::new(1));
+        assert_eq!(NonZeroU64::parse_path_variable("0"), None);
+        assert_eq!(NonZeroU128::parse_path_variable("1"), NonZeroU128::new(1));
+        assert_eq!(NonZeroU128::parse_path_variable("0"), None);
+        assert_eq!(NonZeroUsize::parse_path_variable("1"), NonZeroUsize::new(1));
+        assert_eq!(NonZeroUsize::parse_path_variable("0"), None);
+    }
+
+    #[test]
+    fn test_non_zero_signed() {
+        assert_eq!(NonZeroI8::parse_path_variable("-1"), NonZeroI8::new(-1));
+        assert_eq!(NonZeroI8::parse_path_variable("0"), None);
+        assert_eq!(NonZeroI16::parse_path_variable("-1"), NonZeroI16::new(-1));
+        assert_eq!(NonZeroI16::parse_path_variable("0"), None);
+        assert_eq!(NonZeroI64::parse_path_variable("-1"), NonZeroI64::new(-1));
+        assert_eq!(NonZeroI64::parse_path_variable("0"), None);
+        assert_eq!(NonZeroI128::parse_path_variable("-1"), NonZeroI128::new(-1));
+        assert_eq!(NonZeroI128::parse_path_variable("0"), None);
+        assert_eq!(
+            NonZeroIsize::parse_path_variable("-1"),
+            NonZeroIsize::new(-1)
+        );
+        assert_eq!(NonZeroIsize::parse_path_variable("0"), None);
+    }
+
+    #[rstest]
+    #[case("a", Some('a'))]
+    #[case("é", Some('é'))]
+    #[case("🦀", Some('🦀'))]
+    #[case("", None)]
+    #[case("ab", None)]
+    #[case("éé", None)]
+    fn test_char(#[case] input: &str, #[case] expected: Option<char>) {
+        assert_eq!(char::parse_path_variable(input), expected);
+    }
+
+    #[rstest]
+    #[case("127.0.0.1", Some(IpAddr::V4(Ipv4Addr::LOCALHOST)))]
+    #[case("::1", Some(IpAddr::V6(Ipv6Addr::LOCALHOST)))]
+    #[case("not_an_ip", None)]
+    fn test_ip_addr(#[case] input: &str, #[case] expected: Option<IpAddr>) {
+        assert_eq!(IpAddr::parse_path_variable(input), expected);
+    }
+
+    #[rstest]
+    #[case("192.168.0.1", Some(Ipv4Addr::new(192, 168, 0, 1)))]
+    #[case("::1", None)]
+    #[case("256.0.0.1", None)]
+    #[case("not_an_ip", None)]
+    fn test_ipv4_addr(#[case] input: &str, #[case] expected: Option<Ipv4Addr>) {
+        assert_eq!(Ipv4Addr::parse_path_variable(input), expected);
+    }
+
+    #[rstest]
+    #[case("fe80::1", Some(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1)))]
+    #[case("127.0.0.1", None)]
+    #[case("not_an_ip", None)]
+    fn test_ipv6_addr(#[case] input: &str, #[case] expected: Option<Ipv6Addr>) {
+        assert_eq!(Ipv6Addr::parse_path_variable(input), expected);
+    }
+
+    #[rstest]
+    #[case(
+        "127.0.0.1:8080",
+        Some(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8080)))
+    )]
+    #[case(
+        "[::1]:8080",
+        Some(SocketAddr::V6(SocketAddrV6::new(Ipv6Addr::LOCALHOST, 8080, 0, 0)))
+    )]
+    #[case("127.0.0.1", None)]
+    #[case("not_a_socket", None)]
+    fn test_socket_addr(#[case] input: &str, #[case] expected: Option<SocketAddr>) {
+        assert_eq!(SocketAddr::parse_path_variable(input), expected);
+    }
+
+    #[test]
+    fn test_socket_addr_v4() {
+        assert_eq!(
+            SocketAddrV4::parse_path_variable("127.0.0.1:80"),
+            Some(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 80))
+        );
+        assert_eq!(SocketAddrV4::parse_path_variable("[::1]:80"), None);
+    }
+
+    #[test]
+    fn test_socket_addr_v6() {
+        assert_eq!(
+            SocketAddrV6::parse_path_variable("[::1]:80"),
+            Some(SocketAddrV6::new(Ipv6Addr::LOCALHOST, 80, 0, 0))
+        );
+        assert_eq!(SocketAddrV6::parse_path_variable("127.0.0.1:80"), None);
+    }
+
     #[test]
     fn test_bytes() {
         assert_eq!(
@@ -327,6 +788,117 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bytes_non_utf8() {
+        assert_eq!(
+            <&[u8]>::parse_path_bytes(b"t\xffst"),
+            Some(b"t\xffst".as_slice())
+        );
+    }
+
+    #[rstest]
+    #[case(b"42", Some(42))]
+    #[case(b"4\xff", None)]
+    fn test_parse_path_bytes(#[case] input: &[u8], #[case] expected: Option<u32>) {
+        assert_eq!(u32::parse_path_bytes(input), expected);
+    }
+
+    #[rstest]
+    #[case("0")]
+    #[case("42")]
+    #[case("+42")]
+    #[case("-42")]
+    #[case("-0")]
+    #[case("007")]
+    #[case("")]
+    #[case("+")]
+    #[case("-")]
+    #[case("+-1")]
+    #[case("4 2")]
+    #[case("0x1f")]
+    #[case("127")]
+    #[case("128")]
+    #[case("-128")]
+    #[case("-129")]
+    #[case("255")]
+    #[case("256")]
+    #[case("65535")]
+    #[case("4294967295")]
+    #[case("4294967296")]
+    #[case("-2147483648")]
+    #[case("-2147483649")]
+    #[case("18446744073709551615")]
+    #[case("18446744073709551616")]
+    #[case("١٢")]
+    fn test_integers_parse_like_str(#[case] input: &str) {
+        fn check<'de, T>(input: &'de str)
+        where
+            T: FromRoute<'de> + core::str::FromStr + PartialEq + core::fmt::Debug,
+        {
+            assert_eq!(
+                T::parse_path_variable(input),
+                input.parse::<T>().ok(),
+                "{input}"
+            );
+            assert_eq!(
+                T::parse_path_bytes(input.as_bytes()),
+                input.parse::<T>().ok(),
+                "{input}"
+            );
+        }
+        check::<u8>(input);
+        check::<i8>(input);
+        check::<u16>(input);
+        check::<u32>(input);
+        check::<i32>(input);
+        check::<u64>(input);
+        check::<i64>(input);
+        check::<u128>(input);
+        check::<isize>(input);
+        check::<NonZeroU8>(input);
+        check::<NonZeroI32>(input);
+        check::<NonZeroU64>(input);
+    }
+
+    #[rstest]
+    #[case(b"caf\xc3\xa9", Latin1(Cow::Borrowed("café")))]
+    #[case(b"caf\xe9", Latin1(Cow::Owned(String::from("café"))))]
+    #[case(b"\xff\xfe", Latin1(Cow::Owned(String::from("ÿþ"))))]
+    fn test_latin1(#[case] input: &[u8], #[case] expected: Latin1) {
+        let parsed = Latin1::parse_path_bytes(input).unwrap();
+        assert_eq!(parsed, expected);
+        assert_eq!(
+            matches!(parsed.0, Cow::Borrowed(_)),
+            matches!(expected.0, Cow::Borrowed(_))
+        );
+    }
+
+    #[test]
+    fn test_option_bytes() {
+        assert_eq!(
+            <Option<&[u8]>>::parse_path_bytes(b"\xff"),
+            Some(Some(b"\xff".as_slice()))
+        );
+        assert_eq!(<Option<u32>>::parse_path_bytes(b"\xff"), Some(None));
+        assert_eq!(<Option<u32>>::parse_path_bytes(b""), Some(None));
+    }
+
+    #[test]
+    fn test_result_bytes() {
+        assert_eq!(
+            <Result<u32, ParseError>>::parse_path_bytes(b"\xff"),
+            Some(Err(ParseError::new("valid utf-8")))
+        );
+        assert_eq!(
+            <Result<&[u8], ParseError>>::parse_path_bytes(b"\xff"),
+            Some(Ok(b"\xff".as_slice()))
+        );
+        assert_eq!(
+            <Result<u32, ParseError>>::parse_path_bytes(b"-1"),
+            Some(Err(ParseError::new("u32")))
+        );
+    }
+
     #[test]
     fn test_str() {
         assert_eq!(<&str>::parse_path_variable("test"), Some("test"));
@@ -336,4 +908,104 @@ mod tests {
     fn test_string() {
         assert_eq!(String::parse_path_variable("test"), Some("test".to_owned()));
     }
+
+    #[rstest]
+    #[case("42", Some(Some(42)))]
+    #[case("", Some(None))]
+    #[case("not_a_number", Some(None))]
+    fn test_option(#[case] input: &str, #[case] expected: Option<Option<u32>>) {
+        assert_eq!(Option::<u32>::parse_path_variable(input), expected);
+    }
+
+    #[test]
+    fn test_option_str() {
+        assert_eq!(
+            <Option<&str>>::parse_path_variable("test"),
+            Some(Some("test"))
+        );
+        assert_eq!(<Option<&str>>::parse_path_variable(""), Some(None));
+    }
+
+    #[rstest]
+    #[case("42", Ok(42))]
+    #[case("-1", Err(ParseError::new("u32")))]
+    #[case("", Err(ParseError::new("u32")))]
+    fn test_result(#[case] input: &str, #[case] expected: Result<u32, ParseError>) {
+        assert_eq!(
+            Result::<u32, ParseError>::parse_path_variable(input),
+            Some(expected)
+        );
+    }
+
+    #[rstest]
+    #[case("1,2,3", Some([1.0, 2.0, 3.0]))]
+    #[case("-1.5,0,2e3", Some([-1.5, 0.0, 2000.0]))]
+    #[case("1,2", None)]
+    #[case("1,2,3,4", None)]
+    #[case("1,x,3", None)]
+    #[case("1,,3", None)]
+    #[case("", None)]
+    fn test_array(#[case] input: &str, #[case] expected: Option<[f64; 3]>) {
+        assert_eq!(<[f64; 3]>::parse_path_variable(input), expected);
+    }
+
+    #[test]
+    fn test_array_str() {
+        assert_eq!(<[&str; 2]>::parse_path_variable("a,b"), Some(["a", "b"]));
+        assert_eq!(<[&str; 2]>::parse_path_variable("a,"), Some(["a", ""]));
+    }
+
+    #[rstest]
+    #[case("1.5,2", Some((1.5, 2)))]
+    #[case("1.5", None)]
+    #[case("1.5,2,3", None)]
+    #[case("1.5,two", None)]
+    #[case("x,2", None)]
+    fn test_tuple(#[case] input: &str, #[case] expected: Option<(f64, u32)>) {
+        assert_eq!(<(f64, u32)>::parse_path_variable(input), expected);
+    }
+
+    #[test]
+    fn test_tuple_arity() {
+        assert_eq!(
+            <(u8, char, &str)>::parse_path_variable("1,a,b"),
+            Some((1, 'a', "b"))
+        );
+        assert_eq!(
+            <(u8, u8, u8, bool)>::parse_path_variable("1,2,3,true"),
+            Some((1, 2, 3, true))
+        );
+        assert_eq!(<(u8, u8, u8, bool)>::parse_path_variable("1,2,3"), None);
+    }
+
+    #[test]
+    fn test_parse_error_display() {
+        assert_eq!(
+            ParseError::new("a positive integer").to_string(),
+            "expected a positive integer"
+        );
+    }
+
+    #[test]
+    fn test_rest_str() {
+        assert_eq!(<&str>::parse_path_rest("css/app.css"), Some("css/app.css"));
+        assert_eq!(String::parse_path_rest(""), Some(String::new()));
+    }
+
+    #[rstest]
+    #[case("1/2/3", Some(vec![1, 2, 3]))]
+    #[case("1/2/3/", Some(vec![1, 2, 3]))]
+    #[case("7", Some(vec![7]))]
+    #[case("", Some(vec![]))]
+    #[case("1//3", None)]
+    #[case("1/x/3", None)]
+    fn test_rest_vec(#[case] input: &str, #[case] expected: Option<Vec<u32>>) {
+        assert_eq!(Vec::<u32>::parse_path_rest(input), expected);
+    }
+
+    #[test]
+    fn test_rest_segments() {
+        let segments = crate::Segments::parse_path_rest("a/b/c").unwrap();
+        assert_eq!(segments.collect::<Vec<_>>(), ["a", "b", "c"]);
+    }
 }
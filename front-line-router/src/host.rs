@@ -0,0 +1,79 @@
+//! Runtime helpers used by `front_line::FrontLine` to match a request's host against the hosts a
+//! router was declared for with `#[host(..)]`.
+
+/// Whether `host`, as sent in a `Host` header or an absolute-form target, names one of `hosts`.
+///
+/// The port and a trailing dot are ignored, and names are compared case-insensitively, so
+/// `API.example.com.:8080` matches `api.example.com`.
+pub fn matches(host: &str, hosts: &[&str]) -> bool {
+    let name = host_name(host);
+    hosts
+        .iter()
+        .any(|expected| expected.eq_ignore_ascii_case(name))
+}
+
+/// The name of `host` without its port or a trailing dot. IPv6 literals keep their brackets.
+fn host_name(host: &str) -> &str {
+    let (name, _) = split_port(host);
+    name.strip_suffix('.').unwrap_or(name)
+}
+
+/// Splits `host` into its name and the port after it, if any, e.g. `("example.com", Some("8443"))`
+/// for `example.com:8443`.
+///
+/// An IPv6 literal keeps its brackets, and any zone ID within them, so `[fe80::1%25eth0]:8080`
+/// splits into `[fe80::1%25eth0]` and `8080`. An unclosed bracket is taken to be part of the name.
+pub(crate) fn split_port(host: &str) -> (&str, Option<&str>) {
+    let name_end = match host.strip_prefix('[') {
+        Some(literal) => match literal.find(']') {
+            Some(end) => end + 2,
+            None => return (host, None),
+        },
+        None => host.find(':').unwrap_or(host.len()),
+    };
+    let (name, rest) = host.split_at(name_end);
+    (name, rest.strip_prefix(':'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("api.example.com", true)]
+    #[case("API.Example.com", true)]
+    #[case("api.example.com:8080", true)]
+    #[case("api.example.com.", true)]
+    #[case("api.example.com.:443", true)]
+    #[case("www.example.com", true)]
+    #[case("example.com", false)]
+    #[case("api.example.com.evil", false)]
+    #[case("", false)]
+    #[case("[::1]:8080", true)]
+    #[case("[::1]", true)]
+    #[case("[::2]", false)]
+    #[case("[::1", false)]
+    fn test_matches(#[case] host: &str, #[case] expected: bool) {
+        let hosts = ["api.example.com", "www.example.com", "[::1]"];
+        assert_eq!(matches(host, &hosts), expected);
+    }
+
+    #[rstest]
+    #[case("example.com", "example.com", None)]
+    #[case("example.com:8443", "example.com", Some("8443"))]
+    #[case("example.com:", "example.com", Some(""))]
+    #[case("[::1]", "[::1]", None)]
+    #[case("[::1]:8080", "[::1]", Some("8080"))]
+    #[case("[fe80::1%25eth0]:8080", "[fe80::1%25eth0]", Some("8080"))]
+    #[case("[::1", "[::1", None)]
+    #[case("[::1]x", "[::1]", None)]
+    fn test_split_port(#[case] host: &str, #[case] name: &str, #[case] port: Option<&str>) {
+        assert_eq!(split_port(host), (name, port));
+    }
+
+    #[test]
+    fn test_matches_without_hosts() {
+        assert!(!matches("api.example.com", &[]));
+    }
+}
@@ -0,0 +1,228 @@
+use crate::http_version::HttpVersion;
+use crate::method::Method;
+use crate::router_result::RouterResult;
+
+/// The error returned when converting an `http::Method` that front-line doesn't model, such as an
+/// extension method.
+#[derive(thiserror::Error, PartialEq, Eq, Clone, Debug)]
+#[error("the http method {0} is not supported")]
+pub struct UnsupportedMethod(pub http::Method);
+
+impl From<Method> for http::Method {
+    fn from(method: Method) -> Self {
+        match method {
+            Method::Get => http::Method::GET,
+            Method::Post => http::Method::POST,
+            Method::Put => http::Method::PUT,
+            Method::Delete => http::Method::DELETE,
+            Method::Head => http::Method::HEAD,
+            Method::Options => http::Method::OPTIONS,
+            Method::Connect => http::Method::CONNECT,
+            Method::Trace => http::Method::TRACE,
+            Method::Patch => http::Method::PATCH,
+        }
+    }
+}
+
+impl TryFrom<&http::Method> for Method {
+    type Error = UnsupportedMethod;
+
+    fn try_from(method: &http::Method) -> Result<Self, Self::Error> {
+        match *method {
+            http::Method::GET => Ok(Method::Get),
+            http::Method::POST => Ok(Method::Post),
+            http::Method::PUT => Ok(Method::Put),
+            http::Method::DELETE => Ok(Method::Delete),
+            http::Method::HEAD => Ok(Method::Head),
+            http::Method::OPTIONS => Ok(Method::Options),
+            http::Method::CONNECT => Ok(Method::Connect),
+            http::Method::TRACE => Ok(Method::Trace),
+            http::Method::PATCH => Ok(Method::Patch),
+            _ => Err(UnsupportedMethod(method.clone())),
+        }
+    }
+}
+
+impl From<HttpVersion> for http::Version {
+    fn from(version: HttpVersion) -> Self {
+        match version {
+            HttpVersion::OneZero => http::Version::HTTP_10,
+            HttpVersion::OneOne => http::Version::HTTP_11,
+        }
+    }
+}
+
+impl<'a, T> RouterResult<'a, T> {
+    /// Builds the `http::request::Parts` of the request, with its method, version and headers,
+    /// for handing a routed request to code built on the `http` crate, like a hyper service.
+    ///
+    /// The URI is the `raw_path` the request was routed with, followed by the query. An
+    /// absolute-form target's scheme isn't kept, so its URI is in origin form too, with the
+    /// authority left in the `Host` header and `authority`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `http::Error` when the path, or a header name or value, isn't valid for the
+    /// `http` crate, e.g. an empty path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use front_line_router::{Method, Router};
+    ///
+    /// struct Users;
+    ///
+    /// impl<'de> Router<'de> for Users {
+    ///     fn handle_parsed(_: Method, remaining_path: &'de str) -> Option<Self> {
+    ///         (remaining_path == "/users").then_some(Users)
+    ///     }
+    /// }
+    ///
+    /// let request = b"GET /users?page=2 HTTP/1.1\r\nHost: example.com\r\n\r\n";
+    /// let result = Users::resolve(request)?;
+    /// let parts = result.to_http_parts().unwrap();
+    /// assert_eq!(parts.method, http::Method::GET);
+    /// assert_eq!(parts.uri, "/users?page=2");
+    /// assert_eq!(parts.version, http::Version::HTTP_11);
+    /// assert_eq!(parts.headers["host"], "example.com");
+    /// # Ok::<(), front_line_router::Error>(())
+    /// ```
+    pub fn to_http_parts(&self) -> Result<http::request::Parts, http::Error> {
+        Ok(self.to_http_request()?.into_parts().0)
+    }
+
+    /// Builds an `http::Request` of the request, like `to_http_parts`, with `body()` as its body,
+    /// or an empty body when the request doesn't declare one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `http::Error` when the path, or a header name or value, isn't valid for the
+    /// `http` crate.
+    pub fn to_http_request(&self) -> Result<http::Request<&'a [u8]>, http::Error> {
+        let mut target = Vec::with_capacity(self.raw_path.len() + 1 + self.query.len());
+        target.extend_from_slice(self.raw_path);
+        if !self.query.is_empty() {
+            target.push(b'?');
+            target.extend_from_slice(self.query.as_bytes());
+        }
+        let mut builder = http::Request::builder()
+            .method(self.method)
+            .uri(target)
+            .version(self.version.into());
+        for (name, value) in self.headers() {
+            builder = builder.header(name, value);
+        }
+        builder.body(self.body().unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TargetForm;
+    use rstest::rstest;
+
+    fn result<'a>(
+        path: &'a [u8],
+        query: &'a str,
+        headers: &'a [u8],
+        body: &'a [u8],
+    ) -> RouterResult<'a, ()> {
+        RouterResult {
+            route: None,
+            pattern: None,
+            method: Method::Post,
+            authority: None,
+            target_form: TargetForm::Origin,
+            raw_path: path,
+            query,
+            version: HttpVersion::OneZero,
+            raw_headers: headers,
+            raw_body: body,
+            lenient: false,
+        }
+    }
+
+    #[rstest]
+    #[case(Method::Get, http::Method::GET)]
+    #[case(Method::Post, http::Method::POST)]
+    #[case(Method::Put, http::Method::PUT)]
+    #[case(Method::Delete, http::Method::DELETE)]
+    #[case(Method::Head, http::Method::HEAD)]
+    #[case(Method::Options, http::Method::OPTIONS)]
+    #[case(Method::Connect, http::Method::CONNECT)]
+    #[case(Method::Trace, http::Method::TRACE)]
+    #[case(Method::Patch, http::Method::PATCH)]
+    fn test_round_trip(#[case] method: Method, #[case] expected: http::Method) {
+        let converted = http::Method::from(method);
+        assert_eq!(converted, expected);
+        assert_eq!(Method::try_from(&converted), Ok(method));
+    }
+
+    #[test]
+    fn test_unsupported_method() {
+        let purge = http::Method::from_bytes(b"PURGE").unwrap();
+        assert_eq!(
+            Method::try_from(&purge),
+            Err(UnsupportedMethod(purge.clone()))
+        );
+    }
+
+    #[rstest]
+    #[case(HttpVersion::OneZero, http::Version::HTTP_10)]
+    #[case(HttpVersion::OneOne, http::Version::HTTP_11)]
+    fn test_version(#[case] version: HttpVersion, #[case] expected: http::Version) {
+        assert_eq!(http::Version::from(version), expected);
+    }
+
+    #[rstest]
+    #[case(b"/users", "", "/users")]
+    #[case(b"/users", "page=2&q=a%20b", "/users?page=2&q=a%20b")]
+    #[case(b"*", "", "*")]
+    #[case(b"example.com:443", "", "example.com:443")]
+    fn test_to_http_parts_uri(#[case] path: &[u8], #[case] query: &str, #[case] expected: &str) {
+        let parts = result(path, query, b"", b"").to_http_parts().unwrap();
+        assert_eq!(parts.uri, expected);
+    }
+
+    #[test]
+    fn test_to_http_parts_resolved() {
+        let request = b"GET /users/john%20doe?page=2 HTTP/1.1\r\nHost: example.com\r\n\r\n";
+        let parsed = crate::parsed_request::ParsedRequest::parse(request).unwrap();
+        let parts = parsed
+            .into_result::<()>(None, None)
+            .to_http_parts()
+            .unwrap();
+        assert_eq!(parts.uri, "/users/john%20doe?page=2");
+    }
+
+    #[test]
+    fn test_to_http_parts() {
+        let headers = b"Host: example.com\r\nAccept: a\r\nAccept: b\r\n";
+        let parts = result(b"/", "", headers, b"").to_http_parts().unwrap();
+        assert_eq!(parts.method, http::Method::POST);
+        assert_eq!(parts.version, http::Version::HTTP_10);
+        assert_eq!(parts.headers["host"], "example.com");
+        let accept: Vec<_> = parts.headers.get_all("accept").iter().collect();
+        assert_eq!(accept, ["a", "b"]);
+    }
+
+    #[rstest]
+    #[case(b"Content-Length: 5\r\n", b"hello, world", b"hello")]
+    #[case(b"", b"hello", b"")]
+    fn test_to_http_request_body(
+        #[case] headers: &[u8],
+        #[case] body: &[u8],
+        #[case] expected: &[u8],
+    ) {
+        let request = result(b"/", "", headers, body).to_http_request().unwrap();
+        assert_eq!(*request.body(), expected);
+    }
+
+    #[rstest]
+    #[case(b"", b"")]
+    #[case(b"/users", b"Bad(Name): value\r\n")]
+    fn test_to_http_parts_invalid(#[case] path: &[u8], #[case] headers: &[u8]) {
+        assert!(result(path, "", headers, b"").to_http_parts().is_err());
+    }
+}
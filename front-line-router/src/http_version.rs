@@ -1,7 +1,9 @@
+use core::fmt;
+
 /// Represents versions of the HTTP protocol.
 ///
 /// Currently supports only HTTP/1.0 and HTTP/1.1.
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum HttpVersion {
     /// Represents the HTTP/1.0 version.
     OneZero,
@@ -13,6 +15,19 @@ pub enum HttpVersion {
 }
 
 impl HttpVersion {
+    /// The version's token as it appears in a request line, e.g. `"HTTP/1.1"`.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            HttpVersion::OneZero => "HTTP/1.0",
+            HttpVersion::OneOne => "HTTP/1.1",
+        }
+    }
+
+    /// The version's token as raw bytes, e.g. `b"HTTP/1.1"`, for writing into a response buffer.
+    pub const fn as_bytes(&self) -> &'static [u8] {
+        self.as_str().as_bytes()
+    }
+
     /// Parse an HTTP version from the given request line slice.
     ///
     /// This function will attempt to parse the provided slice and return the identified
@@ -38,6 +53,12 @@ impl HttpVersion {
     }
 }
 
+impl fmt::Display for HttpVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::HttpVersion;
@@ -54,4 +75,14 @@ mod tests {
     fn test_http_version_parsing(#[case] input: &[u8], #[case] expected: Option<HttpVersion>) {
         assert_eq!(HttpVersion::parse(input), expected);
     }
+
+    #[rstest]
+    #[case(HttpVersion::OneZero, "HTTP/1.0")]
+    #[case(HttpVersion::OneOne, "HTTP/1.1")]
+    fn test_as_str(#[case] version: HttpVersion, #[case] expected: &str) {
+        assert_eq!(version.as_str(), expected);
+        assert_eq!(version.as_bytes(), expected.as_bytes());
+        assert_eq!(version.to_string(), expected);
+        assert_eq!(HttpVersion::parse(version.as_bytes()), Some(version));
+    }
 }
@@ -1,17 +1,75 @@
 //! Do not import or use this crate directly, import and use `front-line` instead.
 //! See: [front-line](https://docs.rs/front-line/latest/front_line/)
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod buffered_result;
+#[cfg(feature = "std")]
+mod dyn_router;
 mod from_route;
+#[doc(hidden)]
+pub mod host;
+#[cfg(feature = "http")]
+mod http_interop;
 mod http_version;
 mod method;
+mod method_set;
+#[cfg(feature = "openapi")]
+mod openapi;
+mod or;
+mod parsed_request;
+#[doc(hidden)]
+pub mod patterns;
+#[cfg(feature = "std")]
+mod percent_encoding;
+#[cfg(feature = "std")]
+mod reader;
+mod request_spans;
+#[cfg(feature = "std")]
+mod route_status;
+pub mod route_table;
 mod router;
 mod router_result;
+mod segments;
+#[doc(hidden)]
+pub mod slashes;
+mod target_form;
+#[cfg(feature = "std")]
+mod to_path;
 
-pub use from_route::FromRoute;
+pub use buffered_result::{resolve_in, BufferedRouterResult};
+#[cfg(feature = "std")]
+pub use dyn_router::{DynMatch, DynRouter, PatternError, RouterBuilder};
+#[cfg(feature = "std")]
+pub use from_route::Latin1;
+pub use from_route::{FromRoute, FromRouteRest, ParseError};
+#[cfg(feature = "http")]
+pub use http_interop::UnsupportedMethod;
 pub use http_version::HttpVersion;
-pub use method::Method;
+pub use method::{Method, ParseMethodError};
+pub use method_set::MethodSet;
+#[cfg(feature = "openapi")]
+pub use openapi::{OpenApi, OpenApiParameter, OpenApiRoute, ParameterLocation, SchemaType};
+pub use or::Or;
+#[cfg(feature = "std")]
+pub use percent_encoding::{
+    percent_decode, percent_encode, percent_encode_path, percent_encode_query,
+};
+#[cfg(feature = "std")]
+pub use reader::{resolve_reader, ReaderError};
+pub use request_spans::RequestSpans;
+#[cfg(feature = "std")]
+pub use route_status::RouteStatus;
 pub use router::Error;
 pub use router::Router;
-pub use router_result::RouterResult;
+pub use router_result::{
+    AnyMethodResult, Headers, PartialResult, RouterResult, UnknownMethodRequest,
+};
+#[cfg(feature = "std")]
+pub use router_result::{OwnedRouterResult, QueryPairs};
+pub use segments::{segments, Segments};
+pub use target_form::TargetForm;
+#[cfg(feature = "std")]
+pub use to_path::ToPath;
 
 pub use memchr;
@@ -1,8 +1,14 @@
+use core::fmt;
+use core::str::FromStr;
+
 /// Represents HTTP methods.
 ///
 /// These methods are tokens that indicate the desired action to be performed
 /// on the identified resource.
-#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+///
+/// Methods are ordered as they're listed in `Method::ALL`, so they can key both hash maps and
+/// ordered maps, e.g. for method dispatch tables.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone, Debug)]
 pub enum Method {
     /// Represents the HTTP `GET` method.
     ///
@@ -51,6 +57,55 @@ pub enum Method {
 }
 
 impl Method {
+    /// Every HTTP method, in declaration order.
+    pub const ALL: [Method; 9] = [
+        Method::Get,
+        Method::Post,
+        Method::Put,
+        Method::Delete,
+        Method::Head,
+        Method::Options,
+        Method::Connect,
+        Method::Trace,
+        Method::Patch,
+    ];
+
+    /// The method's token as it appears in a request line, e.g. `"GET"`.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Delete => "DELETE",
+            Method::Head => "HEAD",
+            Method::Options => "OPTIONS",
+            Method::Connect => "CONNECT",
+            Method::Trace => "TRACE",
+            Method::Patch => "PATCH",
+        }
+    }
+
+    /// Whether the method is safe, i.e. read-only, as defined by RFC 7231, section 4.2.1:
+    /// `GET`, `HEAD`, `OPTIONS` and `TRACE`.
+    ///
+    /// Responses to safe methods may be cached or prefetched without changing the state of the
+    /// server.
+    pub const fn is_safe(&self) -> bool {
+        matches!(
+            self,
+            Method::Get | Method::Head | Method::Options | Method::Trace
+        )
+    }
+
+    /// Whether the method is idempotent, as defined by RFC 7231, section 4.2.2: the safe methods,
+    /// plus `PUT` and `DELETE`.
+    ///
+    /// Sending an idempotent request several times has the same effect as sending it once, so it
+    /// can be retried automatically after a connection failure.
+    pub const fn is_idempotent(&self) -> bool {
+        self.is_safe() || matches!(self, Method::Put | Method::Delete)
+    }
+
     /// Parse an HTTP request line to determine the method.
     ///
     /// This function will attempt to parse the provided request line slice and
@@ -64,58 +119,172 @@ impl Method {
     ///
     /// Returns `Some((Method, &[u8]))` if a valid HTTP method is found. Otherwise,
     /// returns `None`.
+    #[inline]
     pub fn parse(request_line: &[u8]) -> Option<(Self, &[u8])> {
-        // method parsers are sorted by method length, and max length was calculated
-        // from "[METHOD_NAME] / HTTP/1.1".len()
-        if request_line.len() < 14 {
-            return None;
-        }
-        if &request_line[..4] == b"GET " {
-            return Some((Method::Get, &request_line[4..]));
-        }
-        if &request_line[..4] == b"PUT " {
-            return Some((Method::Put, &request_line[4..]));
-        }
-        if request_line.len() < 15 {
+        // the shortest request line is "GET  HTTP/1.1", with an empty target, and every method
+        // token fits in the first 8 bytes along with the space that ends it
+        if request_line.len() < 13 {
             return None;
         }
-        if &request_line[..5] == b"POST " {
-            return Some((Method::Post, &request_line[5..]));
-        }
-        if &request_line[..5] == b"HEAD " {
-            return Some((Method::Head, &request_line[5..]));
-        }
-        if request_line.len() < 16 {
-            return None;
-        }
-        if &request_line[..6] == b"TRACE " {
-            return Some((Method::Trace, &request_line[6..]));
-        }
-        if &request_line[..6] == b"PATCH " {
-            return Some((Method::Patch, &request_line[6..]));
-        }
-        if request_line.len() < 17 {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&request_line[..8]);
+        let entry = lookup(u64::from_le_bytes(bytes))?;
+        // the token must still be followed by at least "  HTTP/1.1" minus its leading space
+        if request_line.len() < entry.len + 9 {
             return None;
         }
-        if &request_line[..7] == b"DELETE " {
-            return Some((Method::Delete, &request_line[7..]));
-        }
-        if request_line.len() < 18 {
+        Some((entry.method, &request_line[entry.len..]))
+    }
+
+    /// Parse a standalone method token, like the value of an `Access-Control-Request-Method`
+    /// header, without the space that follows it in a request line.
+    ///
+    /// The token must match exactly, so it's case-sensitive and can't have surrounding
+    /// whitespace. This uses the same single table lookup as `parse`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use front_line_router::Method;
+    ///
+    /// assert_eq!(Method::from_token(b"DELETE"), Some(Method::Delete));
+    /// assert_eq!(Method::from_token(b"DELETE "), None);
+    /// assert_eq!(Method::from_token(b"delete"), None);
+    /// ```
+    pub fn from_token(token: &[u8]) -> Option<Self> {
+        // the token and the space that would follow it must fit in a word, like in `parse`
+        if token.len() > 7 {
             return None;
         }
-        if &request_line[..8] == b"OPTIONS " {
-            return Some((Method::Options, &request_line[8..]));
-        }
-        if &request_line[..8] == b"CONNECT " {
-            return Some((Method::Connect, &request_line[8..]));
+        let mut bytes = [0; 8];
+        bytes[..token.len()].copy_from_slice(token);
+        bytes[token.len()] = b' ';
+        let entry = lookup(u64::from_le_bytes(bytes))?;
+        (entry.len == token.len() + 1).then_some(entry.method)
+    }
+
+    /// Parse the method of an HTTP request line like `parse`, but ignoring the case of the method
+    /// token, so `get / HTTP/1.1` is parsed as a `GET` request.
+    ///
+    /// Method tokens are case-sensitive, so only lenient parsers should use this. It compares the
+    /// token against every method instead of doing `parse`'s single lookup, and doesn't check
+    /// that the rest of the line is long enough to hold a request target and version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use front_line_router::Method;
+    ///
+    /// assert_eq!(Method::parse_ci(b"get / HTTP/1.1"), Some((Method::Get, &b"/ HTTP/1.1"[..])));
+    /// assert_eq!(Method::parse(b"get / HTTP/1.1"), None);
+    /// ```
+    pub fn parse_ci(request_line: &[u8]) -> Option<(Self, &[u8])> {
+        let token_end = memchr::memchr(b' ', request_line)?;
+        let token = &request_line[..token_end];
+        let method = Method::ALL
+            .into_iter()
+            .find(|method| method.as_str().as_bytes().eq_ignore_ascii_case(token))?;
+        Some((method, &request_line[token_end + 1..]))
+    }
+}
+
+/// A method token followed by a space, packed into the low bytes of a little-endian word the way
+/// `Method::parse` reads the start of a request line.
+struct TokenEntry {
+    token: u64,
+    mask: u64,
+    len: usize,
+    method: Method,
+}
+
+/// The method tokens, placed at their `token_slot`. Every other slot holds an empty token, which
+/// never matches since its mask keeps no bytes while its token requires a space.
+const TOKEN_TABLE: [TokenEntry; 16] = {
+    const EMPTY: TokenEntry = TokenEntry {
+        token: b' ' as u64,
+        mask: 0,
+        len: 0,
+        method: Method::Get,
+    };
+    let mut table = [EMPTY; 16];
+    let mut offset = 0;
+    while offset < Method::ALL.len() {
+        let method = Method::ALL[offset];
+        let name = method.as_str().as_bytes();
+        let mut token = (b' ' as u64) << (name.len() * 8);
+        let mut byte = 0;
+        while byte < name.len() {
+            token |= (name[byte] as u64) << (byte * 8);
+            byte += 1;
         }
-        None
+        let len = name.len() + 1;
+        let mask = match len {
+            8 => u64::MAX,
+            len => (1 << (len * 8)) - 1,
+        };
+        let slot = token_slot(token);
+        assert!(
+            table[slot].mask == 0,
+            "method tokens must have distinct slots"
+        );
+        table[slot] = TokenEntry {
+            token,
+            mask,
+            len,
+            method,
+        };
+        offset += 1;
+    }
+    table
+};
+
+/// Finds the entry of the method token, followed by a space, at the start of `word`.
+#[inline]
+fn lookup(word: u64) -> Option<&'static TokenEntry> {
+    let entry = &TOKEN_TABLE[token_slot(word)];
+    (word & entry.mask == entry.token).then_some(entry)
+}
+
+/// A perfect hash of the first 4 bytes of the packed method tokens, which already tell every
+/// method apart, into the 16 slots of `TOKEN_TABLE`. This way parsing a method takes a single
+/// comparison instead of one per candidate token.
+const fn token_slot(word: u64) -> usize {
+    ((word as u32).wrapping_mul(0x2b49_1045) >> 28) as usize
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The error returned when parsing a string that isn't a known HTTP method token.
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[cfg_attr(feature = "std", error("the string was not a known http method"))]
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct ParseMethodError;
+
+impl FromStr for Method {
+    type Err = ParseMethodError;
+
+    /// Parses a method token, e.g. `"GET"`. Tokens are case-sensitive.
+    fn from_str(token: &str) -> Result<Self, Self::Err> {
+        Method::from_token(token.as_bytes()).ok_or(ParseMethodError)
+    }
+}
+
+impl TryFrom<&[u8]> for Method {
+    type Error = ParseMethodError;
+
+    /// Parses a method token like `Method::from_token`.
+    fn try_from(token: &[u8]) -> Result<Self, Self::Error> {
+        Method::from_token(token).ok_or(ParseMethodError)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Method;
+    use super::{Method, ParseMethodError};
     use rstest::rstest;
 
     #[rstest]
@@ -147,22 +316,147 @@ mod tests {
     #[case(b"PUT/ HTTP/1.1")]
     #[case(b"POST/ HTTP/1.1")]
     #[case(b"HEAD/ HTTP/1.1")]
+    #[case(b"get / HTTP/1.1")]
+    #[case(b"GETS / HTTP/1.1")]
+    #[case(b" GET / HTTP/1.1")]
+    #[case(b"OPTIONSX / HTTP/1.1")]
+    #[case(b"CONNECTED / HTTP/1.1")]
+    #[case(b"GET\t/ HTTP/1.1")]
     fn test_malformed_request(#[case] request: &[u8]) {
         assert_eq!(Method::parse(request), None);
     }
 
+    #[rstest]
+    #[case(b"GET / HTTP/1.1", Some((Method::Get, b"/ HTTP/1.1".as_slice())))]
+    #[case(b"get / HTTP/1.1", Some((Method::Get, b"/ HTTP/1.1".as_slice())))]
+    #[case(b"Delete /x", Some((Method::Delete, b"/x".as_slice())))]
+    #[case(b"oPtIoNs * HTTP/1.1", Some((Method::Options, b"* HTTP/1.1".as_slice())))]
+    #[case(b"patch ", Some((Method::Patch, b"".as_slice())))]
+    #[case(b"get", None)]
+    #[case(b"gets / HTTP/1.1", None)]
+    #[case(b" get / HTTP/1.1", None)]
+    #[case(b"get\t/ HTTP/1.1", None)]
+    fn test_parse_ci(#[case] request: &[u8], #[case] expected: Option<(Method, &[u8])>) {
+        assert_eq!(Method::parse_ci(request), expected);
+    }
+
     #[rstest]
     #[case(b"GE")]
     #[case(b"POS")]
     #[case(b"TRAC")]
     #[case(b"DELET")]
     #[case(b"OPTION")]
-    #[case(b"GET / HTTP/1.")]
-    #[case(b"POST / HTTP/1.")]
-    #[case(b"TRACE / HTTP/1.")]
-    #[case(b"DELETE / HTTP/1.")]
-    #[case(b"OPTIONS / HTTP/1.")]
+    #[case(b"GET  HTTP/1.")]
+    #[case(b"POST  HTTP/1.")]
+    #[case(b"TRACE  HTTP/1.")]
+    #[case(b"PATCH  HTTP/1.")]
+    #[case(b"DELETE  HTTP/1.")]
+    #[case(b"OPTIONS  HTTP/1.")]
+    #[case(b"CONNECT  HTTP/1.")]
     fn test_short_request(#[case] request: &[u8]) {
         assert_eq!(Method::parse(request), None);
     }
+
+    #[rstest]
+    #[case(b"GET  HTTP/1.1", Method::Get)]
+    #[case(b"OPTIONS  HTTP/1.1", Method::Options)]
+    #[case(b"CONNECT  HTTP/1.0", Method::Connect)]
+    fn test_empty_target(#[case] request: &[u8], #[case] method: Method) {
+        assert_eq!(
+            Method::parse(request),
+            Some((method, &request[request.len() - 9..]))
+        );
+    }
+
+    #[rstest]
+    #[case(Method::Get, "GET")]
+    #[case(Method::Post, "POST")]
+    #[case(Method::Put, "PUT")]
+    #[case(Method::Delete, "DELETE")]
+    #[case(Method::Head, "HEAD")]
+    #[case(Method::Options, "OPTIONS")]
+    #[case(Method::Connect, "CONNECT")]
+    #[case(Method::Trace, "TRACE")]
+    #[case(Method::Patch, "PATCH")]
+    fn test_as_str(#[case] method: Method, #[case] expected: &str) {
+        assert_eq!(method.as_str(), expected);
+        assert_eq!(method.to_string(), expected);
+        assert_eq!(expected.parse::<Method>(), Ok(method));
+    }
+
+    #[rstest]
+    #[case(Method::Get, true, true)]
+    #[case(Method::Post, false, false)]
+    #[case(Method::Put, false, true)]
+    #[case(Method::Delete, false, true)]
+    #[case(Method::Head, true, true)]
+    #[case(Method::Options, true, true)]
+    #[case(Method::Connect, false, false)]
+    #[case(Method::Trace, true, true)]
+    #[case(Method::Patch, false, false)]
+    fn test_is_safe_and_idempotent(
+        #[case] method: Method,
+        #[case] safe: bool,
+        #[case] idempotent: bool,
+    ) {
+        assert_eq!(method.is_safe(), safe);
+        assert_eq!(method.is_idempotent(), idempotent);
+    }
+
+    #[rstest]
+    #[case(b"GET", Some(Method::Get))]
+    #[case(b"PUT", Some(Method::Put))]
+    #[case(b"OPTIONS", Some(Method::Options))]
+    #[case(b"CONNECT", Some(Method::Connect))]
+    #[case(b"PATCH", Some(Method::Patch))]
+    #[case(b"", None)]
+    #[case(b"G", None)]
+    #[case(b"GE", None)]
+    #[case(b"GET ", None)]
+    #[case(b" GET", None)]
+    #[case(b"get", None)]
+    #[case(b"GETS", None)]
+    #[case(b"OPTION", None)]
+    #[case(b"OPTIONSX", None)]
+    #[case(b"CONNECTED", None)]
+    #[case(b"GET\0", None)]
+    #[case(b"\xff\xff", None)]
+    fn test_from_token(#[case] token: &[u8], #[case] expected: Option<Method>) {
+        assert_eq!(Method::from_token(token), expected);
+        assert_eq!(Method::try_from(token).ok(), expected);
+    }
+
+    #[test]
+    fn test_from_token_all() {
+        for method in Method::ALL {
+            assert_eq!(Method::from_token(method.as_str().as_bytes()), Some(method));
+        }
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("get")]
+    #[case("GET ")]
+    #[case("PURGE")]
+    fn test_from_str_invalid(#[case] token: &str) {
+        assert_eq!(token.parse::<Method>(), Err(ParseMethodError));
+    }
+
+    #[test]
+    fn test_ord_follows_all() {
+        let mut methods = Method::ALL;
+        methods.reverse();
+        methods.sort();
+        assert_eq!(methods, Method::ALL);
+    }
+
+    #[test]
+    fn test_map_keys() {
+        let hashed: std::collections::HashMap<Method, &str> =
+            Method::ALL.map(|method| (method, method.as_str())).into();
+        assert_eq!(hashed[&Method::Patch], "PATCH");
+        let ordered: std::collections::BTreeMap<Method, &str> =
+            Method::ALL.map(|method| (method, method.as_str())).into();
+        assert!(ordered.keys().eq(Method::ALL.iter()));
+    }
 }
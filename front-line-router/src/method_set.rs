@@ -0,0 +1,158 @@
+use crate::Method;
+use core::fmt;
+
+/// A set of HTTP methods, stored as a bitset so it can be built and passed around without
+/// allocating, e.g. for an `Allow` header.
+///
+/// Methods are iterated, and displayed, in `Method::ALL` order.
+///
+/// # Examples
+///
+/// ```
+/// use front_line_router::{Method, MethodSet};
+///
+/// let methods = MethodSet::from_methods(&[Method::Head, Method::Get]);
+/// assert!(methods.contains(Method::Get));
+/// assert!(!methods.contains(Method::Post));
+/// assert_eq!(methods.len(), 2);
+/// assert_eq!(methods.to_string(), "GET, HEAD");
+/// ```
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Default)]
+pub struct MethodSet(u16);
+
+impl MethodSet {
+    /// The set without any methods.
+    pub const EMPTY: MethodSet = MethodSet(0);
+
+    /// The set of every method in `Method::ALL`.
+    pub const ALL: MethodSet = MethodSet::from_methods(&Method::ALL);
+
+    /// The set of the given methods.
+    pub const fn from_methods(methods: &[Method]) -> Self {
+        let mut set = MethodSet::EMPTY;
+        let mut i = 0;
+        while i < methods.len() {
+            set = set.with(methods[i]);
+            i += 1;
+        }
+        set
+    }
+
+    /// This set with `method` added.
+    pub const fn with(self, method: Method) -> Self {
+        MethodSet(self.0 | bit(method))
+    }
+
+    /// The methods in either this set or `other`.
+    pub const fn union(self, other: MethodSet) -> Self {
+        MethodSet(self.0 | other.0)
+    }
+
+    /// Whether `method` is in the set.
+    pub const fn contains(self, method: Method) -> bool {
+        self.0 & bit(method) != 0
+    }
+
+    /// Whether the set has no methods.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The number of methods in the set.
+    pub const fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Adds `method` to the set.
+    pub fn insert(&mut self, method: Method) {
+        *self = self.with(method);
+    }
+
+    /// Iterate over the methods in the set, in `Method::ALL` order.
+    pub fn iter(self) -> impl Iterator<Item = Method> {
+        Method::ALL
+            .into_iter()
+            .filter(move |method| self.contains(*method))
+    }
+}
+
+/// The bit of `method` in a `MethodSet`.
+const fn bit(method: Method) -> u16 {
+    1 << method as u16
+}
+
+impl FromIterator<Method> for MethodSet {
+    fn from_iter<I: IntoIterator<Item = Method>>(methods: I) -> Self {
+        let mut set = MethodSet::EMPTY;
+        for method in methods {
+            set.insert(method);
+        }
+        set
+    }
+}
+
+impl fmt::Debug for MethodSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Formats the set as the value of an `Allow` header, e.g. `GET, HEAD`.
+impl fmt::Display for MethodSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (offset, method) in self.iter().enumerate() {
+            if offset > 0 {
+                f.write_str(", ")?;
+            }
+            f.write_str(method.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(&[], "", 0)]
+    #[case(&[Method::Get], "GET", 1)]
+    #[case(&[Method::Patch, Method::Get, Method::Patch], "GET, PATCH", 2)]
+    #[case(
+        &Method::ALL,
+        "GET, POST, PUT, DELETE, HEAD, OPTIONS, CONNECT, TRACE, PATCH",
+        9
+    )]
+    fn test_from_methods(
+        #[case] methods: &[Method],
+        #[case] expected: &str,
+        #[case] expected_len: usize,
+    ) {
+        let set = MethodSet::from_methods(methods);
+        assert_eq!(set.to_string(), expected);
+        assert_eq!(set.len(), expected_len);
+        assert_eq!(set.is_empty(), expected_len == 0);
+        assert_eq!(methods.iter().copied().collect::<MethodSet>(), set);
+        assert!(methods.iter().all(|method| set.contains(*method)));
+    }
+
+    #[test]
+    fn test_union_and_insert() {
+        let mut set = MethodSet::EMPTY.with(Method::Post);
+        set.insert(Method::Delete);
+        let union = set.union(MethodSet::from_methods(&[Method::Get, Method::Post]));
+        assert_eq!(
+            union.iter().collect::<Vec<_>>(),
+            [Method::Get, Method::Post, Method::Delete]
+        );
+        assert!(!union.contains(Method::Head));
+        assert_eq!(MethodSet::ALL.iter().collect::<Vec<_>>(), Method::ALL);
+    }
+
+    #[test]
+    fn test_debug() {
+        let set = MethodSet::from_methods(&[Method::Put, Method::Get]);
+        assert_eq!(format!("{set:?}"), "{Get, Put}");
+    }
+}
@@ -0,0 +1,155 @@
+use crate::method::Method;
+use serde_json::{json, Map, Value};
+
+/// The OpenAPI metadata of the routes of a router, generated by `front_line::FrontLine` when the
+/// `openapi` feature is enabled.
+pub trait OpenApi {
+    /// Every route the router declares, with any prefixes applied and flattened sub-routers
+    /// expanded, in the same order as `Router::ROUTES`.
+    fn openapi_routes() -> Vec<OpenApiRoute>;
+
+    /// Renders `openapi_routes` as an OpenAPI paths object, mapping each templated path to an
+    /// operation per method along with its parameters.
+    fn openapi_paths() -> Value {
+        let mut paths = Map::new();
+        for route in Self::openapi_routes() {
+            let operations = paths
+                .entry(route.path.clone())
+                .or_insert_with(|| Value::Object(Map::new()));
+            let method = route.method.as_str().to_ascii_lowercase();
+            operations[method] = route.to_operation();
+        }
+        Value::Object(paths)
+    }
+}
+
+/// A single method and templated path, e.g. `GET /users/{id}`, along with its parameters.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct OpenApiRoute {
+    pub method: Method,
+    /// The templated path, with variable constraints and catch-all markers removed, e.g.
+    /// `/users/{id}`. Query parameters are listed in `parameters` instead.
+    pub path: String,
+    pub parameters: Vec<OpenApiParameter>,
+}
+
+impl OpenApiRoute {
+    /// Renders the route as an OpenAPI operation object.
+    pub fn to_operation(&self) -> Value {
+        let parameters: Vec<_> = self
+            .parameters
+            .iter()
+            .map(OpenApiParameter::to_json)
+            .collect();
+        json!({ "parameters": parameters })
+    }
+}
+
+/// A path variable or query parameter captured by a route.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct OpenApiParameter {
+    pub name: &'static str,
+    pub location: ParameterLocation,
+    pub schema: SchemaType,
+    /// Path variables are always required, while query parameters captured by an `Option` field
+    /// aren't.
+    pub required: bool,
+}
+
+impl OpenApiParameter {
+    /// Renders the parameter as an OpenAPI parameter object.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "name": self.name,
+            "in": self.location.as_str(),
+            "required": self.required,
+            "schema": { "type": self.schema.as_str() },
+        })
+    }
+}
+
+/// Where a route parameter is captured from.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum ParameterLocation {
+    Path,
+    Query,
+}
+
+impl ParameterLocation {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            ParameterLocation::Path => "path",
+            ParameterLocation::Query => "query",
+        }
+    }
+}
+
+/// The OpenAPI schema type of a captured field, derived from its Rust type. Integer types map to
+/// `integer`, floats to `number`, `bool` to `boolean` and everything else to `string`.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum SchemaType {
+    Integer,
+    Number,
+    Boolean,
+    String,
+}
+
+impl SchemaType {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            SchemaType::Integer => "integer",
+            SchemaType::Number => "number",
+            SchemaType::Boolean => "boolean",
+            SchemaType::String => "string",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestRoutes;
+
+    impl OpenApi for TestRoutes {
+        fn openapi_routes() -> Vec<OpenApiRoute> {
+            vec![
+                OpenApiRoute {
+                    method: Method::Get,
+                    path: String::from("/users/{id}"),
+                    parameters: vec![OpenApiParameter {
+                        name: "id",
+                        location: ParameterLocation::Path,
+                        schema: SchemaType::Integer,
+                        required: true,
+                    }],
+                },
+                OpenApiRoute {
+                    method: Method::Delete,
+                    path: String::from("/users/{id}"),
+                    parameters: vec![],
+                },
+            ]
+        }
+    }
+
+    #[test]
+    fn test_openapi_paths() {
+        assert_eq!(
+            TestRoutes::openapi_paths(),
+            json!({
+                "/users/{id}": {
+                    "get": {
+                        "parameters": [{
+                            "name": "id",
+                            "in": "path",
+                            "required": true,
+                            "schema": { "type": "integer" },
+                        }],
+                    },
+                    "delete": { "parameters": [] },
+                },
+            })
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use crate::method::Method;
+use crate::method_set::MethodSet;
+use crate::router::Router;
+
+/// A router that tries the router `A` and then the router `B`, so two independently derived
+/// routers, e.g. from different crates, can be combined at runtime without a combined enum.
+///
+/// The route is `Or::Left` when `A` matched and `Or::Right` when only `B` did. `METHODS` is the
+/// union of both routers' methods, while `PATTERNS` and `ROUTES` are left empty since the tables
+/// of two generic routers can't be joined at compile time, so no pattern is reported for the
+/// matched route. Each router still checks its own `HOSTS` when routed with
+/// `resolve_with_host`. Nest `Or`s, like `Or<A, Or<B, C>>`, to combine more routers.
+///
+/// # Examples
+///
+/// ```
+/// use front_line_router::{Method, Or, Router};
+///
+/// #[derive(PartialEq, Debug)]
+/// struct Health;
+///
+/// impl<'de> Router<'de> for Health {
+///     fn handle_parsed(method: Method, remaining_path: &'de str) -> Option<Self> {
+///         (method == Method::Get && remaining_path == "/health").then_some(Health)
+///     }
+/// }
+///
+/// #[derive(PartialEq, Debug)]
+/// struct Plugin<'a>(&'a str);
+///
+/// impl<'de> Router<'de> for Plugin<'de> {
+///     fn handle_parsed(_: Method, remaining_path: &'de str) -> Option<Self> {
+///         remaining_path.strip_prefix("/plugins/").map(Plugin)
+///     }
+/// }
+///
+/// let result = Or::<Health, Plugin>::resolve(b"GET /plugins/auth HTTP/1.1\r\n\r\n")?;
+/// assert_eq!(result.route, Some(Or::Right(Plugin("auth"))));
+/// # Ok::<(), front_line_router::Error>(())
+/// ```
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Or<A, B> {
+    /// The route matched by the first router.
+    Left(A),
+    /// The route matched by the second router, when the first didn't match.
+    Right(B),
+}
+
+impl<'de, A: Router<'de>, B: Router<'de>> Router<'de> for Or<A, B> {
+    const METHODS: MethodSet = A::METHODS.union(B::METHODS);
+
+    fn handle_parsed(method: Method, remaining_path: &'de str) -> Option<Self> {
+        match A::handle_parsed(method, remaining_path) {
+            Some(route) => Some(Or::Left(route)),
+            None => B::handle_parsed(method, remaining_path).map(Or::Right),
+        }
+    }
+
+    fn handle_parsed_with_query(
+        method: Method,
+        remaining_path: &'de str,
+        query: &'de str,
+    ) -> Option<Self> {
+        match A::handle_parsed_with_query(method, remaining_path, query) {
+            Some(route) => Some(Or::Left(route)),
+            None => B::handle_parsed_with_query(method, remaining_path, query).map(Or::Right),
+        }
+    }
+
+    fn handle_parsed_with_pattern(
+        method: Method,
+        remaining_path: &'de str,
+        query: &'de str,
+    ) -> Option<(Self, Option<usize>)> {
+        Self::handle_parsed_with_query(method, remaining_path, query).map(|route| (route, None))
+    }
+
+    fn handle_parsed_bytes(
+        method: Method,
+        remaining_path: &'de [u8],
+        query: &'de str,
+    ) -> Option<(Self, Option<usize>)> {
+        let mut deepest = remaining_path;
+        Self::handle_parsed_tracking(method, remaining_path, query, &mut deepest)
+    }
+
+    fn handle_parsed_tracking(
+        method: Method,
+        remaining_path: &'de [u8],
+        query: &'de str,
+        deepest: &mut &'de [u8],
+    ) -> Option<(Self, Option<usize>)> {
+        Self::handle_parsed_with_host(method, None, remaining_path, query, deepest)
+    }
+
+    fn handle_parsed_with_host(
+        method: Method,
+        host: Option<&'de str>,
+        remaining_path: &'de [u8],
+        query: &'de str,
+        deepest: &mut &'de [u8],
+    ) -> Option<(Self, Option<usize>)> {
+        if let Some((route, _)) =
+            A::handle_parsed_with_host(method, host, remaining_path, query, deepest)
+        {
+            return Some((Or::Left(route), None));
+        }
+        B::handle_parsed_with_host(method, host, remaining_path, query, deepest)
+            .map(|(route, _)| (Or::Right(route), None))
+    }
+
+    fn handle_parsed_methods(
+        host: Option<&'de str>,
+        remaining_path: &'de [u8],
+        query: &'de str,
+        allowed: &mut MethodSet,
+    ) {
+        A::handle_parsed_methods(host, remaining_path, query, allowed);
+        B::handle_parsed_methods(host, remaining_path, query, allowed);
+    }
+
+    #[cfg(feature = "resolve-all")]
+    fn handle_parsed_all(
+        method: Method,
+        host: Option<&'de str>,
+        remaining_path: &'de [u8],
+        query: &'de str,
+        matches: &mut Vec<(Self, Option<usize>)>,
+    ) {
+        let mut left = Vec::new();
+        A::handle_parsed_all(method, host, remaining_path, query, &mut left);
+        matches.extend(left.into_iter().map(|(route, _)| (Or::Left(route), None)));
+        let mut right = Vec::new();
+        B::handle_parsed_all(method, host, remaining_path, query, &mut right);
+        matches.extend(right.into_iter().map(|(route, _)| (Or::Right(route), None)));
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A: crate::ToPath, B: crate::ToPath> crate::ToPath for Or<A, B> {
+    fn to_path(&self) -> String {
+        match self {
+            Or::Left(route) => route.to_path(),
+            Or::Right(route) => route.to_path(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[derive(PartialEq, Debug)]
+    enum Users {
+        List,
+        Get(u32),
+    }
+
+    impl<'de> Router<'de> for Users {
+        const METHODS: MethodSet = MethodSet::from_methods(&[Method::Get]);
+
+        fn handle_parsed(method: Method, remaining_path: &'de str) -> Option<Self> {
+            match (method, remaining_path) {
+                (Method::Get, "/users") => Some(Users::List),
+                (Method::Get, path) => path.strip_prefix("/users/")?.parse().ok().map(Users::Get),
+                _ => None,
+            }
+        }
+    }
+
+    #[derive(PartialEq, Debug)]
+    enum Catalog<'a> {
+        Users,
+        Item(&'a str),
+    }
+
+    impl<'de> Router<'de> for Catalog<'de> {
+        const METHODS: MethodSet = MethodSet::from_methods(&[Method::Get, Method::Post]);
+
+        fn handle_parsed(_: Method, remaining_path: &'de str) -> Option<Self> {
+            match remaining_path {
+                "/users" => Some(Catalog::Users),
+                path => path.strip_prefix("/items/").map(Catalog::Item),
+            }
+        }
+    }
+
+    type Combined<'a> = Or<Users, Catalog<'a>>;
+
+    #[rstest]
+    #[case(Method::Get, "/users", Some(Or::Left(Users::List)))]
+    #[case(Method::Get, "/users/7", Some(Or::Left(Users::Get(7))))]
+    #[case(Method::Post, "/users", Some(Or::Right(Catalog::Users)))]
+    #[case(Method::Get, "/items/a", Some(Or::Right(Catalog::Item("a"))))]
+    #[case(Method::Get, "/other", None)]
+    fn test_handle_parsed(
+        #[case] method: Method,
+        #[case] path: &str,
+        #[case] expected: Option<Combined<'_>>,
+    ) {
+        assert_eq!(Combined::handle_parsed(method, path), expected);
+        let bytes = Combined::handle_parsed_bytes(method, path.as_bytes(), "");
+        assert_eq!(bytes.map(|(route, _)| route), expected);
+    }
+
+    #[test]
+    fn test_resolve() {
+        let result = Combined::resolve(b"GET /items/42 HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(result.route, Some(Or::Right(Catalog::Item("42"))));
+        assert_eq!(result.pattern, None);
+    }
+
+    #[test]
+    fn test_methods() {
+        assert_eq!(
+            Combined::METHODS,
+            MethodSet::from_methods(&[Method::Get, Method::Post])
+        );
+    }
+}
@@ -0,0 +1,409 @@
+use crate::http_version::HttpVersion;
+use crate::method::Method;
+use crate::router::Error;
+use crate::router::Router;
+use crate::router_result::{request_host, Headers};
+use crate::target_form::TargetForm;
+use crate::{RouterResult, UnknownMethodRequest};
+use memchr::memmem;
+
+/// The components of an HTTP request that routing depends on.
+pub(crate) struct ParsedRequest<'de> {
+    pub method: Method,
+    pub path: &'de [u8],
+    pub authority: Option<&'de str>,
+    pub target_form: TargetForm,
+    pub query: &'de str,
+    pub version: HttpVersion,
+    pub headers: &'de [u8],
+    pub body: &'de [u8],
+    /// Whether the request was parsed leniently, so its header lines may end with a bare `\n`.
+    pub lenient: bool,
+}
+
+impl<'de> ParsedRequest<'de> {
+    pub(crate) fn parse(request: &'de [u8]) -> Result<Self, Error> {
+        let end = memmem::find(request, b"\r\n").ok_or(Error::MissingHeaderTerminator)?;
+        let request_line = RequestLine::parse(&request[..end])?;
+        let (headers, body) = split_headers(&request[end + 2..])?;
+        Ok(request_line.with_headers_and_body(headers, body))
+    }
+
+    /// Like `parse`, but the request line, headers and the blank line after them must fit within
+    /// the first `max_head_len` bytes of the request.
+    pub(crate) fn parse_with_limit(request: &'de [u8], max_head_len: usize) -> Result<Self, Error> {
+        let head = &request[..request.len().min(max_head_len)];
+        let too_large = |error| match error {
+            Error::MissingHeaderTerminator if request.len() > max_head_len => Error::HeadTooLarge,
+            error => error,
+        };
+        let end =
+            memmem::find(head, b"\r\n").ok_or_else(|| too_large(Error::MissingHeaderTerminator))?;
+        let request_line = RequestLine::parse(&request[..end])?;
+        let (headers, _) = split_headers(&head[end + 2..]).map_err(too_large)?;
+        let body = &request[end + 2 + headers.len() + 2..];
+        Ok(request_line.with_headers_and_body(headers, body))
+    }
+
+    /// Like `parse`, but also accepts bare `\n` line endings anywhere `\r\n` is expected, and
+    /// runs of spaces around the tokens of the request line.
+    pub(crate) fn parse_lenient(request: &'de [u8]) -> Result<Self, Error> {
+        let end = memchr::memchr(b'\n', request).ok_or(Error::MissingHeaderTerminator)?;
+        let line = &request[..end];
+        let request_line = RequestLine::parse_lenient(line.strip_suffix(b"\r").unwrap_or(line))?;
+        let (headers, body) = split_headers_lenient(&request[end + 1..])?;
+        Ok(ParsedRequest {
+            lenient: true,
+            ..request_line.with_headers_and_body(headers, body)
+        })
+    }
+
+    /// Like `parse`, but a request that ends right after the `\r\n` of its request line is
+    /// taken to have no headers and no body, instead of missing its blank line.
+    pub(crate) fn parse_unterminated(request: &'de [u8]) -> Result<Self, Error> {
+        let end = memmem::find(request, b"\r\n").ok_or(Error::MissingHeaderTerminator)?;
+        let request_line = RequestLine::parse(&request[..end])?;
+        let (headers, body) = match &request[end + 2..] {
+            b"" => (&request[end + 2..], &request[end + 2..]),
+            after_request_line => split_headers(after_request_line)?,
+        };
+        Ok(request_line.with_headers_and_body(headers, body))
+    }
+
+    /// Checks that a request `parse` rejected for missing its terminator could still become a
+    /// valid request once more bytes arrive, i.e. that what's been read so far is a prefix of
+    /// one `parse` accepts, up to the header block.
+    pub(crate) fn check_incomplete(request: &[u8]) -> Result<(), Error> {
+        match memmem::find(request, b"\r\n") {
+            Some(end) => RequestLine::parse(&request[..end]).map(drop),
+            None => check_partial_request_line(request),
+        }
+    }
+
+    /// Like `parse`, for a request line whose method `Method` doesn't model, e.g. `PROPFIND`.
+    ///
+    /// The method still has to be a valid token, and the rest of the request is parsed as usual.
+    pub(crate) fn parse_unknown_method(
+        request: &'de [u8],
+    ) -> Result<UnknownMethodRequest<'de>, Error> {
+        let end = memmem::find(request, b"\r\n").ok_or(Error::MissingHeaderTerminator)?;
+        let request_line = &request[..end];
+        let method_end = memchr::memchr(b' ', request_line).ok_or(Error::InvalidMethod)?;
+        let method = core::str::from_utf8(&request_line[..method_end])
+            .ok()
+            .filter(|token| is_token(token))
+            .ok_or(Error::InvalidMethod)?;
+        let after_method = &request_line[method_end + 1..];
+        let full_path_end =
+            memchr::memchr(b' ', after_method).ok_or(Error::MalformedRequestTarget)?;
+        let version = HttpVersion::parse(&after_method[full_path_end + 1..])
+            .ok_or(Error::UnsupportedVersion)?;
+        let (authority, path, query) = split_full_path(&after_method[..full_path_end])?;
+        if path == b"*" {
+            return Err(Error::MalformedRequestTarget);
+        }
+        let (headers, body) = split_headers(&request[end + 2..])?;
+        Ok(UnknownMethodRequest {
+            method,
+            authority,
+            query,
+            version,
+            raw_headers: headers,
+            raw_body: body,
+        })
+    }
+
+    /// Routes the request. The path is only validated as UTF-8 when no route matches, since a
+    /// match means every byte of it was either a literal or a capture its field accepted, which
+    /// may not be UTF-8 for fields like `&[u8]`.
+    pub(crate) fn route<R: Router<'de>>(&self) -> Result<(Option<R>, Option<&'static str>), Error> {
+        if !self.target_form.is_routable() {
+            return self.path_str().map(|_| (None, None));
+        }
+        match R::handle_parsed_bytes(self.method, self.path, self.query) {
+            Some((route, index)) => {
+                Ok((Some(route), index.and_then(|i| R::PATTERNS.get(i).copied())))
+            }
+            None => self.path_str().map(|_| (None, None)),
+        }
+    }
+
+    /// Like `route`, but only routers declared for the request's host, if any, match.
+    pub(crate) fn route_with_host<R: Router<'de>>(
+        &self,
+    ) -> Result<(Option<R>, Option<&'static str>), Error> {
+        let host = request_host(self.authority, Headers::new(self.headers, self.lenient))?;
+        if !self.target_form.is_routable() {
+            return self.path_str().map(|_| (None, None));
+        }
+        let mut deepest = self.path;
+        match R::handle_parsed_with_host(self.method, host, self.path, self.query, &mut deepest) {
+            Some((route, index)) => {
+                Ok((Some(route), index.and_then(|i| R::PATTERNS.get(i).copied())))
+            }
+            None => self.path_str().map(|_| (None, None)),
+        }
+    }
+
+    pub(crate) fn path_str(&self) -> Result<&'de str, Error> {
+        core::str::from_utf8(self.path).map_err(|_| Error::NonUtf8Path)
+    }
+
+    pub(crate) fn into_result<T>(
+        self,
+        route: Option<T>,
+        pattern: Option<&'static str>,
+    ) -> RouterResult<'de, T> {
+        RouterResult {
+            route,
+            pattern,
+            method: self.method,
+            authority: self.authority,
+            target_form: self.target_form,
+            raw_path: self.path,
+            query: self.query,
+            version: self.version,
+            raw_headers: self.headers,
+            raw_body: self.body,
+            lenient: self.lenient,
+        }
+    }
+}
+
+/// The components of an HTTP request line.
+struct RequestLine<'de> {
+    method: Method,
+    path: &'de [u8],
+    authority: Option<&'de str>,
+    target_form: TargetForm,
+    query: &'de str,
+    version: HttpVersion,
+}
+
+impl<'de> RequestLine<'de> {
+    fn parse(request_line: &'de [u8]) -> Result<Self, Error> {
+        let (method, after_method) =
+            Method::parse(request_line).ok_or_else(|| method_error(request_line))?;
+        let full_path_end =
+            memchr::memchr(b' ', after_method).ok_or(Error::MalformedRequestTarget)?;
+        let after_path = &after_method[full_path_end + 1..];
+        Self::from_parts(method, &after_method[..full_path_end], after_path)
+    }
+
+    /// Like `parse`, but the method, request target and version may be separated by runs of
+    /// spaces, the line may start or end with them, and the method may be in any case.
+    fn parse_lenient(request_line: &'de [u8]) -> Result<Self, Error> {
+        let request_line = trim_spaces_end(trim_spaces_start(request_line));
+        let (method, after_method) =
+            Method::parse_ci(request_line).ok_or_else(|| method_error(request_line))?;
+        let after_method = trim_spaces_start(after_method);
+        let full_path_end =
+            memchr::memchr(b' ', after_method).ok_or(Error::MalformedRequestTarget)?;
+        let after_path = trim_spaces_start(&after_method[full_path_end..]);
+        Self::from_parts(method, &after_method[..full_path_end], after_path)
+    }
+
+    fn from_parts(method: Method, full_path: &'de [u8], version: &[u8]) -> Result<Self, Error> {
+        let version = HttpVersion::parse(version).ok_or(Error::UnsupportedVersion)?;
+        let (authority, path, query) = split_request_target(method, full_path)?;
+        Ok(Self {
+            method,
+            path,
+            authority,
+            target_form: TargetForm::classify(authority, path),
+            query,
+            version,
+        })
+    }
+
+    fn with_headers_and_body(self, headers: &'de [u8], body: &'de [u8]) -> ParsedRequest<'de> {
+        ParsedRequest {
+            method: self.method,
+            path: self.path,
+            authority: self.authority,
+            target_form: self.target_form,
+            query: self.query,
+            version: self.version,
+            headers,
+            body,
+            lenient: false,
+        }
+    }
+}
+
+/// Like `split_full_path`, but also rejects the asterisk-form target `*` for any method but
+/// `OPTIONS`.
+fn split_request_target(
+    method: Method,
+    full_path: &[u8],
+) -> Result<(Option<&str>, &[u8], &str), Error> {
+    let (authority, path, query) = split_full_path(full_path)?;
+    if path == b"*" && method != Method::Options {
+        return Err(Error::MalformedRequestTarget);
+    }
+    Ok((authority, path, query))
+}
+
+/// Splits a request target into its authority, path and query.
+fn split_full_path(full_path: &[u8]) -> Result<(Option<&str>, &[u8], &str), Error> {
+    let query_start = memchr::memchr(b'?', full_path).unwrap_or(full_path.len());
+    let query_bytes = &full_path[full_path.len().min(query_start + 1)..];
+    let query = core::str::from_utf8(query_bytes).map_err(|_| Error::NonUtf8Path)?;
+    let (authority, path) = split_target(&full_path[..query_start])?;
+    Ok((authority, path, query))
+}
+
+/// Splits a request target, without its query, into its authority and path.
+///
+/// Origin-form targets, like `/users`, are the common case and are returned as is. Absolute-form
+/// targets, like `http://example.com/users`, have their scheme and authority stripped, and an
+/// empty path is treated as `/`.
+fn split_target(target: &[u8]) -> Result<(Option<&str>, &[u8]), Error> {
+    if target.first() == Some(&b'/') {
+        return Ok((None, target));
+    }
+    let after_scheme = match memmem::find(target, b"://") {
+        Some(end) if is_scheme(&target[..end]) => &target[end + 3..],
+        _ => return Ok((None, target)),
+    };
+    let authority_end = memchr::memchr(b'/', after_scheme).unwrap_or(after_scheme.len());
+    let authority = core::str::from_utf8(&after_scheme[..authority_end])
+        .map_err(|_| Error::MalformedRequestTarget)?;
+    if authority.is_empty() {
+        return Err(Error::MalformedRequestTarget);
+    }
+    let path = match &after_scheme[authority_end..] {
+        b"" => b"/",
+        path => path,
+    };
+    Ok((Some(authority), path))
+}
+
+/// Whether `scheme` is a URI scheme: a letter followed by letters, digits, `+`, `-` or `.`.
+fn is_scheme(scheme: &[u8]) -> bool {
+    match scheme.split_first() {
+        Some((first, rest)) => {
+            first.is_ascii_alphabetic()
+                && rest
+                    .iter()
+                    .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'-' | b'.'))
+        }
+        None => false,
+    }
+}
+
+fn trim_spaces_start(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|&b| b != b' ').unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+fn trim_spaces_end(bytes: &[u8]) -> &[u8] {
+    let end = bytes
+        .iter()
+        .rposition(|&b| b != b' ')
+        .map_or(0, |end| end + 1);
+    &bytes[..end]
+}
+
+/// Whether `token` is an HTTP token, as methods must be: one or more letters, digits or any of
+/// ``!#$%&'*+-.^_`|~``.
+fn is_token(token: &str) -> bool {
+    !token.is_empty()
+        && token.bytes().all(|b| {
+            b.is_ascii_alphanumeric()
+                || matches!(
+                    b,
+                    b'!' | b'#'
+                        | b'$'
+                        | b'%'
+                        | b'&'
+                        | b'\''
+                        | b'*'
+                        | b'+'
+                        | b'-'
+                        | b'.'
+                        | b'^'
+                        | b'_'
+                        | b'`'
+                        | b'|'
+                        | b'~'
+                )
+        })
+}
+
+/// Explains why `Method::parse` rejected a request line.
+///
+/// `Method::parse` also rejects lines that are too short to hold a request target and version,
+/// so a line that does start with a known method is reported as a malformed request target.
+fn method_error(request_line: &[u8]) -> Error {
+    let token_end = memchr::memchr(b' ', request_line).unwrap_or(request_line.len());
+    if Method::from_token(&request_line[..token_end]).is_some() {
+        Error::MalformedRequestTarget
+    } else {
+        Error::InvalidMethod
+    }
+}
+
+/// Checks that `line` is a prefix of a request line `RequestLine::parse` accepts.
+fn check_partial_request_line(line: &[u8]) -> Result<(), Error> {
+    if let Some(line) = line.strip_suffix(b"\r") {
+        return RequestLine::parse(line).map(drop);
+    }
+    let method_end = match memchr::memchr(b' ', line) {
+        Some(method_end) => method_end,
+        None if Method::ALL
+            .iter()
+            .any(|method| method.as_str().as_bytes().starts_with(line)) =>
+        {
+            return Ok(())
+        }
+        None => return Err(Error::InvalidMethod),
+    };
+    let method = Method::from_token(&line[..method_end]).ok_or(Error::InvalidMethod)?;
+    let after_method = &line[method_end + 1..];
+    let full_path_end = match memchr::memchr(b' ', after_method) {
+        Some(full_path_end) => full_path_end,
+        None => return Ok(()),
+    };
+    split_request_target(method, &after_method[..full_path_end])?;
+    let version = &after_method[full_path_end + 1..];
+    let is_version_prefix = [HttpVersion::OneOne, HttpVersion::OneZero]
+        .iter()
+        .any(|known| known.as_bytes().starts_with(version));
+    if is_version_prefix {
+        Ok(())
+    } else {
+        Err(Error::UnsupportedVersion)
+    }
+}
+
+/// Splits the bytes after the request line into the header block and the body.
+///
+/// The header block keeps the `\r\n` that ends each header line, but not the blank line that
+/// separates it from the body.
+fn split_headers(after_request_line: &[u8]) -> Result<(&[u8], &[u8]), Error> {
+    if let Some(body) = after_request_line.strip_prefix(b"\r\n") {
+        return Ok((&after_request_line[..0], body));
+    }
+    let end =
+        memmem::find(after_request_line, b"\r\n\r\n").ok_or(Error::MissingHeaderTerminator)?;
+    Ok((
+        &after_request_line[..end + 2],
+        &after_request_line[end + 4..],
+    ))
+}
+
+/// Like `split_headers`, but the blank line and the header lines may end with a bare `\n`.
+fn split_headers_lenient(after_request_line: &[u8]) -> Result<(&[u8], &[u8]), Error> {
+    let mut start = 0;
+    loop {
+        let end = start
+            + memchr::memchr(b'\n', &after_request_line[start..])
+                .ok_or(Error::MissingHeaderTerminator)?;
+        let line = &after_request_line[start..end];
+        if line.is_empty() || line == b"\r" {
+            return Ok((&after_request_line[..start], &after_request_line[end + 1..]));
+        }
+        start = end + 1;
+    }
+}
@@ -0,0 +1,236 @@
+//! Compile time helpers used by `front_line::FrontLine` to build `Router::PATTERNS` and
+//! `Router::ROUTES` tables.
+//!
+//! A flattened router's patterns have to be prefixed with the parent's prefix. Since both are
+//! constants, the generated code concatenates them into a single byte buffer and then slices that
+//! buffer back into one `&'static str` per pattern, all during constant evaluation. Route tables
+//! are prefixed the same way, by splitting out their patterns and pairing them back up with the
+//! methods afterwards.
+//!
+//! The prefixes of flattened routers are compared during constant evaluation too, to reject two of
+//! them that would be tried under the same prefix.
+
+use crate::Method;
+
+/// The number of bytes needed to hold every pattern with `prefix` prepended.
+pub const fn prefixed_len(prefix: &str, patterns: &[&str]) -> usize {
+    let mut len = 0;
+    let mut i = 0;
+    while i < patterns.len() {
+        len += prefix.len() + patterns[i].len();
+        i += 1;
+    }
+    len
+}
+
+/// Concatenates every pattern, each with `prefix` prepended, into a single buffer.
+pub const fn prefixed_bytes<const LEN: usize>(prefix: &str, patterns: &[&str]) -> [u8; LEN] {
+    let mut bytes = [0u8; LEN];
+    let mut offset = 0;
+    let mut i = 0;
+    while i < patterns.len() {
+        offset = copy_into(&mut bytes, offset, prefix.as_bytes());
+        offset = copy_into(&mut bytes, offset, patterns[i].as_bytes());
+        i += 1;
+    }
+    bytes
+}
+
+/// Slices a buffer built by `prefixed_bytes` back into one string per pattern.
+pub const fn prefixed_table<const N: usize>(
+    bytes: &'static [u8],
+    prefix: &str,
+    patterns: &[&str],
+) -> [&'static str; N] {
+    let mut table = [""; N];
+    let mut rest = bytes;
+    let mut i = 0;
+    while i < N {
+        let (pattern, remaining) = rest.split_at(prefix.len() + patterns[i].len());
+        table[i] = match core::str::from_utf8(pattern) {
+            Ok(pattern) => pattern,
+            Err(_) => panic!("route patterns must be valid utf-8"),
+        };
+        rest = remaining;
+        i += 1;
+    }
+    table
+}
+
+/// The total number of patterns across every table.
+pub const fn joined_len(tables: &[&[&str]]) -> usize {
+    let mut len = 0;
+    let mut i = 0;
+    while i < tables.len() {
+        len += tables[i].len();
+        i += 1;
+    }
+    len
+}
+
+/// Concatenates every table, in order, into a single table.
+pub const fn joined_table<const N: usize>(tables: &[&[&'static str]]) -> [&'static str; N] {
+    let mut table = [""; N];
+    let mut offset = 0;
+    let mut i = 0;
+    while i < tables.len() {
+        let mut j = 0;
+        while j < tables[i].len() {
+            table[offset] = tables[i][j];
+            offset += 1;
+            j += 1;
+        }
+        i += 1;
+    }
+    table
+}
+
+/// The pattern of every route, in order.
+pub const fn route_patterns<const N: usize>(
+    routes: &[(Method, &'static str)],
+) -> [&'static str; N] {
+    let mut patterns = [""; N];
+    let mut i = 0;
+    while i < N {
+        patterns[i] = routes[i].1;
+        i += 1;
+    }
+    patterns
+}
+
+/// Pairs the method of every route with the corresponding pattern from `patterns`.
+pub const fn routes_with_patterns<const N: usize>(
+    routes: &[(Method, &'static str)],
+    patterns: &[&'static str],
+) -> [(Method, &'static str); N] {
+    let mut table = [(Method::Get, ""); N];
+    let mut i = 0;
+    while i < N {
+        table[i] = (routes[i].0, patterns[i]);
+        i += 1;
+    }
+    table
+}
+
+/// The total number of routes across every table.
+pub const fn joined_routes_len(tables: &[&[(Method, &str)]]) -> usize {
+    let mut len = 0;
+    let mut i = 0;
+    while i < tables.len() {
+        len += tables[i].len();
+        i += 1;
+    }
+    len
+}
+
+/// Concatenates every route table, in order, into a single table.
+pub const fn joined_routes<const N: usize>(
+    tables: &[&[(Method, &'static str)]],
+) -> [(Method, &'static str); N] {
+    let mut table = [(Method::Get, ""); N];
+    let mut offset = 0;
+    let mut i = 0;
+    while i < tables.len() {
+        let mut j = 0;
+        while j < tables[i].len() {
+            table[offset] = tables[i][j];
+            offset += 1;
+            j += 1;
+        }
+        i += 1;
+    }
+    table
+}
+
+/// Whether two flattened sub-routers are tried under the same non-empty prefix, given the mount
+/// path and `Router::PREFIX` of each, e.g. `("", "/app")` and `("/app", "")`.
+pub const fn same_prefix(a: (&str, &str), b: (&str, &str)) -> bool {
+    let (a_mount, a_prefix) = (a.0.as_bytes(), a.1.as_bytes());
+    let (b_mount, b_prefix) = (b.0.as_bytes(), b.1.as_bytes());
+    let len = a_mount.len() + a_prefix.len();
+    if len == 0 || len != b_mount.len() + b_prefix.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < len {
+        if byte_at(a_mount, a_prefix, i) != byte_at(b_mount, b_prefix, i) {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// The byte at `offset` of `first` followed by `second`.
+const fn byte_at(first: &[u8], second: &[u8], offset: usize) -> u8 {
+    match offset < first.len() {
+        true => first[offset],
+        false => second[offset - first.len()],
+    }
+}
+
+const fn copy_into<const LEN: usize>(bytes: &mut [u8; LEN], offset: usize, from: &[u8]) -> usize {
+    let mut i = 0;
+    while i < from.len() {
+        bytes[offset + i] = from[i];
+        i += 1;
+    }
+    offset + from.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PREFIX: &str = "/v1";
+    const PATTERNS: &[&str] = &["/users", "/users/{id}"];
+    const LEN: usize = prefixed_len(PREFIX, PATTERNS);
+    const BYTES: [u8; LEN] = prefixed_bytes(PREFIX, PATTERNS);
+    const TABLE: [&str; PATTERNS.len()] = prefixed_table(&BYTES, PREFIX, PATTERNS);
+    const TABLES: &[&[&str]] = &[&["/"], &TABLE, &[]];
+    const JOINED: [&str; joined_len(TABLES)] = joined_table(TABLES);
+
+    #[test]
+    fn test_prefixed_table() {
+        assert_eq!(TABLE, ["/v1/users", "/v1/users/{id}"]);
+    }
+
+    #[test]
+    fn test_joined_table() {
+        assert_eq!(JOINED, ["/", "/v1/users", "/v1/users/{id}"]);
+    }
+
+    const ROUTES: &[(Method, &str)] = &[(Method::Get, "/users"), (Method::Post, "/users/{id}")];
+    const ROUTE_PATTERNS: [&str; ROUTES.len()] = route_patterns(ROUTES);
+    const ROUTE_LEN: usize = prefixed_len(PREFIX, &ROUTE_PATTERNS);
+    const ROUTE_BYTES: [u8; ROUTE_LEN] = prefixed_bytes(PREFIX, &ROUTE_PATTERNS);
+    const PREFIXED_PATTERNS: [&str; ROUTES.len()] =
+        prefixed_table(&ROUTE_BYTES, PREFIX, &ROUTE_PATTERNS);
+    const PREFIXED_ROUTES: [(Method, &str); ROUTES.len()] =
+        routes_with_patterns(ROUTES, &PREFIXED_PATTERNS);
+    const ROUTE_TABLES: &[&[(Method, &str)]] = &[&[(Method::Head, "/")], &PREFIXED_ROUTES];
+    const JOINED_ROUTES: [(Method, &str); joined_routes_len(ROUTE_TABLES)] =
+        joined_routes(ROUTE_TABLES);
+
+    #[test]
+    fn test_joined_routes() {
+        assert_eq!(
+            JOINED_ROUTES,
+            [
+                (Method::Head, "/"),
+                (Method::Get, "/v1/users"),
+                (Method::Post, "/v1/users/{id}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_same_prefix() {
+        assert!(same_prefix(("", "/app"), ("", "/app")));
+        assert!(same_prefix(("", "/app"), ("/app", "")));
+        assert!(same_prefix(("/a", "/pp"), ("/a/p", "p")));
+        assert!(!same_prefix(("", "/app"), ("", "/api")));
+        assert!(!same_prefix(("", "/app"), ("", "/app/admin")));
+        assert!(!same_prefix(("", ""), ("", "")));
+    }
+}
@@ -0,0 +1,262 @@
+use std::borrow::Cow;
+
+/// Percent-decodes a captured path segment.
+///
+/// Decoding only allocates when the segment actually contains a `%` escape, otherwise the
+/// original slice is returned as `Cow::Borrowed`.
+///
+/// # Arguments
+///
+/// * `input` - The raw, possibly percent-encoded, path segment.
+///
+/// # Returns
+///
+/// Returns `Some(Cow<str>)` with the decoded segment. Returns `None` if a `%` isn't followed by
+/// two hex digits or if the decoded bytes aren't valid UTF-8.
+///
+/// # Examples
+///
+/// ```
+/// use front_line_router::percent_decode;
+///
+/// assert_eq!(percent_decode("john%20doe").as_deref(), Some("john doe"));
+/// assert_eq!(percent_decode("john%2"), None);
+/// ```
+pub fn percent_decode(input: &str) -> Option<Cow<'_, str>> {
+    let bytes = input.as_bytes();
+    let first_escape = match memchr::memchr(b'%', bytes) {
+        Some(first_escape) => first_escape,
+        None => return Some(Cow::Borrowed(input)),
+    };
+    let mut decoded = Vec::with_capacity(bytes.len());
+    decoded.extend_from_slice(&bytes[..first_escape]);
+    let mut offset = first_escape;
+    while offset < bytes.len() {
+        if bytes[offset] == b'%' {
+            let high = hex_value(*bytes.get(offset + 1)?)?;
+            let low = hex_value(*bytes.get(offset + 2)?)?;
+            decoded.push(high << 4 | low);
+            offset += 3;
+        } else {
+            decoded.push(bytes[offset]);
+            offset += 1;
+        }
+    }
+    String::from_utf8(decoded).ok().map(Cow::Owned)
+}
+
+/// Decodes a key or value from an `application/x-www-form-urlencoded` query string.
+///
+/// Unlike `percent_decode` this never fails: `+` decodes to a space, a `%` that isn't followed by
+/// two hex digits is kept as is, and decoded bytes that aren't valid UTF-8 are replaced with
+/// `U+FFFD`. Decoding only allocates when the input contains a `+` or `%`.
+pub(crate) fn form_decode(input: &str) -> Cow<'_, str> {
+    let bytes = input.as_bytes();
+    let first_escape = match memchr::memchr2(b'%', b'+', bytes) {
+        Some(first_escape) => first_escape,
+        None => return Cow::Borrowed(input),
+    };
+    let mut decoded = Vec::with_capacity(bytes.len());
+    decoded.extend_from_slice(&bytes[..first_escape]);
+    let mut offset = first_escape;
+    while offset < bytes.len() {
+        let escaped = match bytes[offset..] {
+            [b'+', ..] => Some((b' ', 1)),
+            [b'%', high, low, ..] => hex_value(high)
+                .zip(hex_value(low))
+                .map(|(high, low)| (high << 4 | low, 3)),
+            _ => None,
+        };
+        let (byte, len) = escaped.unwrap_or((bytes[offset], 1));
+        decoded.push(byte);
+        offset += len;
+    }
+    match String::from_utf8(decoded) {
+        Ok(decoded) => Cow::Owned(decoded),
+        Err(error) => Cow::Owned(String::from_utf8_lossy(error.as_bytes()).into_owned()),
+    }
+}
+
+/// Percent-encodes a value so it can be embedded in a single path segment.
+///
+/// Every byte other than the RFC 3986 unreserved characters (`A-Z`, `a-z`, `0-9`, `-`, `.`, `_`,
+/// and `~`) is escaped. Encoding only allocates when at least one byte needs escaping, otherwise
+/// the original slice is returned as `Cow::Borrowed`.
+///
+/// # Examples
+///
+/// ```
+/// use front_line_router::percent_encode;
+///
+/// assert_eq!(percent_encode("john doe"), "john%20doe");
+/// assert_eq!(percent_encode("a/b"), "a%2Fb");
+/// ```
+pub fn percent_encode(input: &str) -> Cow<'_, str> {
+    encode(input, is_unreserved)
+}
+
+/// Percent-encodes a value that spans several path segments, like a catch-all capture, so each
+/// `/` is kept as a segment separator and everything else is escaped as by `percent_encode`.
+///
+/// # Examples
+///
+/// ```
+/// use front_line_router::percent_encode_path;
+///
+/// assert_eq!(percent_encode_path("docs/read me.md"), "docs/read%20me.md");
+/// ```
+pub fn percent_encode_path(input: &str) -> Cow<'_, str> {
+    encode(input, |byte| is_unreserved(byte) || byte == b'/')
+}
+
+/// Percent-encodes a value so it can be embedded as a key or value of a query string.
+///
+/// Besides the unreserved characters, the `/`, `?`, `:`, `@`, `!`, `$`, `'`, `(`, `)`, `*`, `,`
+/// and `;` a query may hold are kept, while `&`, `=`, `#`, `+` and spaces are escaped, so the
+/// value can't split into other parameters or end the query, and reads the same whether it's
+/// decoded as a form or not.
+///
+/// # Examples
+///
+/// ```
+/// use front_line_router::percent_encode_query;
+///
+/// assert_eq!(percent_encode_query("a b&c=d"), "a%20b%26c%3Dd");
+/// assert_eq!(percent_encode_query("/docs?page"), "/docs?page");
+/// ```
+pub fn percent_encode_query(input: &str) -> Cow<'_, str> {
+    encode(input, |byte| {
+        is_unreserved(byte)
+            || matches!(
+                byte,
+                b'/' | b'?' | b':' | b'@' | b'!' | b'$' | b'\'' | b'(' | b')' | b'*' | b',' | b';'
+            )
+    })
+}
+
+/// Escapes every byte of `input` that `keep` rejects, borrowing `input` when none is.
+fn encode(input: &str, keep: impl Fn(u8) -> bool) -> Cow<'_, str> {
+    let bytes = input.as_bytes();
+    let first_escaped = match bytes.iter().position(|byte| !keep(*byte)) {
+        Some(first_escaped) => first_escaped,
+        None => return Cow::Borrowed(input),
+    };
+    let mut encoded = String::with_capacity(bytes.len() + 8);
+    encoded.push_str(&input[..first_escaped]);
+    for byte in &bytes[first_escaped..] {
+        if keep(*byte) {
+            encoded.push(*byte as char);
+        } else {
+            encoded.push('%');
+            encoded.push(HEX_DIGITS[(byte >> 4) as usize] as char);
+            encoded.push(HEX_DIGITS[(byte & 0xf) as usize] as char);
+        }
+    }
+    Cow::Owned(encoded)
+}
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~')
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        form_decode, percent_decode, percent_encode, percent_encode_path, percent_encode_query,
+    };
+    use rstest::rstest;
+    use std::borrow::Cow;
+
+    #[rstest]
+    #[case("plain", Some("plain"))]
+    #[case("", Some(""))]
+    #[case("john%20doe", Some("john doe"))]
+    #[case("%2Fetc%2fpasswd", Some("/etc/passwd"))]
+    #[case("caf%C3%A9", Some("café"))]
+    #[case("100%", None)]
+    #[case("100%2", None)]
+    #[case("%zz", None)]
+    #[case("%C3", None)]
+    fn test_percent_decode(#[case] input: &str, #[case] expected: Option<&str>) {
+        assert_eq!(percent_decode(input).as_deref(), expected);
+    }
+
+    #[test]
+    fn test_percent_decode_borrows_without_escapes() {
+        assert!(matches!(
+            percent_decode("plain"),
+            Some(Cow::Borrowed("plain"))
+        ));
+    }
+
+    #[rstest]
+    #[case("plain", "plain")]
+    #[case("", "")]
+    #[case("john+doe", "john doe")]
+    #[case("john%20doe", "john doe")]
+    #[case("1%2B1", "1+1")]
+    #[case("caf%C3%A9", "café")]
+    #[case("100%", "100%")]
+    #[case("100%2", "100%2")]
+    #[case("%zz+", "%zz ")]
+    #[case("%C3", "\u{FFFD}")]
+    fn test_form_decode(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(form_decode(input), expected);
+    }
+
+    #[test]
+    fn test_form_decode_borrows_without_escapes() {
+        assert!(matches!(form_decode("plain"), Cow::Borrowed("plain")));
+    }
+
+    #[rstest]
+    #[case("plain-text_1.0~", "plain-text_1.0~")]
+    #[case("", "")]
+    #[case("john doe", "john%20doe")]
+    #[case("/etc/passwd", "%2Fetc%2Fpasswd")]
+    #[case("café", "caf%C3%A9")]
+    #[case("100%", "100%25")]
+    fn test_percent_encode(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(percent_encode(input), expected);
+    }
+
+    #[rstest]
+    #[case("docs/readme.md", "docs/readme.md")]
+    #[case("a b/c?d#e", "a%20b/c%3Fd%23e")]
+    #[case("/", "/")]
+    fn test_percent_encode_path(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(percent_encode_path(input), expected);
+    }
+
+    #[rstest]
+    #[case("rust", "rust")]
+    #[case("a b&c=d", "a%20b%26c%3Dd")]
+    #[case("1+1#top", "1%2B1%23top")]
+    #[case("/path?x:y@z", "/path?x:y@z")]
+    #[case("100%", "100%25")]
+    fn test_percent_encode_query(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(percent_encode_query(input), expected);
+    }
+
+    #[rstest]
+    #[case("john doe")]
+    #[case("a/b?c=d&e")]
+    #[case("café ☕")]
+    fn test_percent_round_trip(#[case] input: &str) {
+        assert_eq!(
+            percent_decode(&percent_encode(input)).as_deref(),
+            Some(input)
+        );
+    }
+}
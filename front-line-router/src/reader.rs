@@ -0,0 +1,153 @@
+use crate::{Error, OwnedRouterResult, Router};
+use memchr::memmem;
+use std::io::{self, BufRead};
+
+/// The error returned by `resolve_reader`, when reading the request fails or it can't be routed.
+#[derive(thiserror::Error, Debug)]
+pub enum ReaderError {
+    /// Reading the request from the reader failed.
+    #[error("failed to read the request: {0}")]
+    Io(#[from] io::Error),
+
+    /// The request head was read but couldn't be parsed. A reader that ended before the blank
+    /// line after the headers is reported as `Error::MissingHeaderTerminator`.
+    #[error(transparent)]
+    Request(#[from] Error),
+}
+
+/// Read and route the head of a request from `reader`, leaving its body in the reader.
+///
+/// Only the request line, the headers and the blank line that ends them are read, into a buffer
+/// owned by the result, so a large upload can be streamed from the reader after routing on its
+/// head. The reader must be a `BufRead`, e.g. a `BufReader` around a socket, so no byte of the
+/// body is consumed. The returned result's `raw_body` is always empty.
+///
+/// Since the buffer doesn't outlive the call, the route can't borrow from it: `T` must be a
+/// router for any lifetime that doesn't borrow itself, like a derived enum that only captures
+/// owned values such as `String`s or numbers.
+///
+/// # Arguments
+///
+/// * `reader` - The reader positioned at the start of the request.
+/// * `max_head_len` - The maximum length of the request line and headers, in bytes.
+///
+/// # Returns
+///
+/// Returns the `OwnedRouterResult` of the request's head. Returns `ReaderError::Io` if reading
+/// fails, `Error::HeadTooLarge` if the head isn't terminated within `max_head_len` bytes, or any
+/// other `Error` if parsing or validation fails.
+pub fn resolve_reader<T, R>(
+    reader: &mut R,
+    max_head_len: usize,
+) -> Result<OwnedRouterResult<T>, ReaderError>
+where
+    T: for<'de> Router<'de> + 'static,
+    R: BufRead,
+{
+    let head = read_head(reader, max_head_len)?;
+    let result = T::resolve_with_limits(&head, max_head_len)?;
+    Ok(result.into_owned())
+}
+
+/// Reads up to and including the `\r\n\r\n` that ends the request head, and no further.
+fn read_head<R: BufRead>(reader: &mut R, max_head_len: usize) -> Result<Vec<u8>, ReaderError> {
+    let mut head = Vec::new();
+    loop {
+        let available = match reader.fill_buf() {
+            Ok(available) => available,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error.into()),
+        };
+        if available.is_empty() {
+            return Err(Error::MissingHeaderTerminator.into());
+        }
+        // the terminator may straddle the bytes read so far and the new ones
+        let read = head.len();
+        let start = read.saturating_sub(3);
+        head.extend_from_slice(available);
+        let available = available.len();
+        if let Some(offset) = memmem::find(&head[start..], b"\r\n\r\n") {
+            let end = start + offset + 4;
+            head.truncate(end);
+            reader.consume(end - read);
+            return match end > max_head_len {
+                true => Err(Error::HeadTooLarge.into()),
+                false => Ok(head),
+            };
+        }
+        reader.consume(available);
+        if head.len() > max_head_len {
+            return Err(Error::HeadTooLarge.into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+    use rstest::rstest;
+    use std::io::{BufReader, Read};
+
+    #[derive(PartialEq, Debug)]
+    enum UploadRoute {
+        Upload(String),
+    }
+
+    impl<'de> Router<'de> for UploadRoute {
+        fn handle_parsed(method: Method, remaining_path: &'de str) -> Option<Self> {
+            match (method, remaining_path.strip_prefix("/uploads/")) {
+                (Method::Post, Some(name)) => Some(UploadRoute::Upload(name.to_string())),
+                _ => None,
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(2)]
+    #[case(3)]
+    #[case(7)]
+    #[case(1024)]
+    fn test_resolve_reader_leaves_body(#[case] capacity: usize) {
+        let request = b"POST /uploads/a.bin HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello".as_slice();
+        let mut reader = BufReader::with_capacity(capacity, request);
+        let result = resolve_reader::<UploadRoute, _>(&mut reader, 1024).unwrap();
+        assert_eq!(result.route, Some(UploadRoute::Upload("a.bin".to_string())));
+        assert_eq!(result.raw_headers, b"Content-Length: 5\r\n");
+        assert!(result.raw_body.is_empty());
+        let mut body = String::new();
+        reader.read_to_string(&mut body).unwrap();
+        assert_eq!(body, "hello");
+    }
+
+    #[test]
+    fn test_resolve_reader_without_route() {
+        let mut reader = BufReader::new(b"GET /uploads/a.bin HTTP/1.1\r\n\r\n".as_slice());
+        let result = resolve_reader::<UploadRoute, _>(&mut reader, 1024).unwrap();
+        assert_eq!(result.route, None);
+        assert_eq!(result.method, Method::Get);
+    }
+
+    #[rstest]
+    #[case(
+        b"POST /uploads/a.bin HTTP/1.1\r\n",
+        1024,
+        Error::MissingHeaderTerminator
+    )]
+    #[case(b"", 1024, Error::MissingHeaderTerminator)]
+    #[case(b"POST /uploads/a.bin HTTP/1.1\r\n\r\n", 20, Error::HeadTooLarge)]
+    #[case(b"POST /uploads/a.bin HTTP/1.1\r\nX: y", 20, Error::HeadTooLarge)]
+    #[case(b"FETCH /uploads/a.bin HTTP/1.1\r\n\r\n", 1024, Error::InvalidMethod)]
+    fn test_resolve_reader_error(
+        #[case] request: &[u8],
+        #[case] max_head_len: usize,
+        #[case] expected: Error,
+    ) {
+        let mut reader = BufReader::with_capacity(4, request);
+        match resolve_reader::<UploadRoute, _>(&mut reader, max_head_len) {
+            Err(ReaderError::Request(error)) => assert_eq!(error, expected),
+            other => panic!("expected {expected:?}, got {other:?}"),
+        }
+    }
+}
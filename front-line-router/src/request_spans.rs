@@ -0,0 +1,118 @@
+use crate::buffered_result::range_of;
+use crate::parsed_request::ParsedRequest;
+use core::ops::Range;
+
+/// The byte ranges of the parts of an HTTP request within the buffer it was parsed from, created
+/// by `Router::resolve_spans`.
+///
+/// Each range indexes the original request, so `&request[spans.path]` gives back the raw bytes of
+/// the path, before any percent-decoding.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct RequestSpans {
+    /// The method token, e.g. `GET`.
+    pub method: Range<usize>,
+
+    /// The path of the request target, without its query.
+    ///
+    /// For an absolute-form target, like `http://example.com/users`, this excludes the scheme and
+    /// authority, and it's empty when the target has no path.
+    pub path: Range<usize>,
+
+    /// The query string, without the leading `?`. It's empty, and starts where the path ends,
+    /// when the target has no query.
+    pub query: Range<usize>,
+
+    /// The version token, e.g. `HTTP/1.1`.
+    pub version: Range<usize>,
+
+    /// The header block, see `RouterResult::headers`.
+    pub headers: Range<usize>,
+
+    /// The body, everything after the blank line that ends the headers.
+    pub body: Range<usize>,
+}
+
+impl RequestSpans {
+    /// Locates the parts of `parsed` within `request`, which it was parsed from with
+    /// `ParsedRequest::parse`, so the request line is known to be well-formed.
+    pub(crate) fn locate(request: &[u8], parsed: &ParsedRequest) -> Self {
+        let headers = range_of(request, parsed.headers);
+        let line_end = headers.start - 2;
+        let line = &request[..line_end];
+        let method_end = memchr::memchr(b' ', line).unwrap_or(0);
+        let version_start = memchr::memrchr(b' ', line).map_or(line_end, |space| space + 1);
+        let target = method_end + 1..version_start - 1;
+        let (path_end, query) = match memchr::memchr(b'?', &request[target.clone()]) {
+            Some(mark) => (target.start + mark, target.start + mark + 1..target.end),
+            None => (target.end, target.end..target.end),
+        };
+        let path_start = match parsed.authority {
+            Some(authority) => range_of(request, authority.as_bytes()).end,
+            None => target.start,
+        };
+        Self {
+            method: 0..method_end,
+            path: path_start..path_end,
+            query,
+            version: version_start..line_end,
+            body: range_of(request, parsed.body),
+            headers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(b"GET /users HTTP/1.1\r\n\r\n", "GET", "/users", "", "HTTP/1.1")]
+    #[case(b"GET /users?a=b HTTP/1.1\r\n\r\n", "GET", "/users", "a=b", "HTTP/1.1")]
+    #[case(b"GET /users? HTTP/1.0\r\n\r\n", "GET", "/users", "", "HTTP/1.0")]
+    #[case(
+        b"DELETE http://a.com/users?a=b HTTP/1.1\r\n\r\n",
+        "DELETE",
+        "/users",
+        "a=b",
+        "HTTP/1.1"
+    )]
+    #[case(b"GET http://a.com HTTP/1.1\r\n\r\n", "GET", "", "", "HTTP/1.1")]
+    #[case(b"OPTIONS * HTTP/1.1\r\n\r\n", "OPTIONS", "*", "", "HTTP/1.1")]
+    #[case(
+        b"CONNECT a.com:443 HTTP/1.1\r\n\r\n",
+        "CONNECT",
+        "a.com:443",
+        "",
+        "HTTP/1.1"
+    )]
+    fn test_locate_request_line(
+        #[case] request: &[u8],
+        #[case] method: &str,
+        #[case] path: &str,
+        #[case] query: &str,
+        #[case] version: &str,
+    ) {
+        let parsed = ParsedRequest::parse(request).unwrap();
+        let spans = RequestSpans::locate(request, &parsed);
+        assert_eq!(&request[spans.method], method.as_bytes());
+        assert_eq!(&request[spans.path], path.as_bytes());
+        assert_eq!(&request[spans.query], query.as_bytes());
+        assert_eq!(&request[spans.version], version.as_bytes());
+    }
+
+    #[test]
+    fn test_locate_headers_and_body() {
+        let request = b"POST /users HTTP/1.1\r\nHost: a\r\nContent-Length: 4\r\n\r\nbody";
+        let parsed = ParsedRequest::parse(request).unwrap();
+        let spans = RequestSpans::locate(request, &parsed);
+        assert_eq!(&request[spans.headers], b"Host: a\r\nContent-Length: 4\r\n");
+        assert_eq!(&request[spans.body], b"body");
+
+        let request = b"GET /users HTTP/1.1\r\n\r\n";
+        let parsed = ParsedRequest::parse(request).unwrap();
+        let spans = RequestSpans::locate(request, &parsed);
+        assert_eq!(spans.headers, 21..21);
+        assert_eq!(spans.body, 23..23);
+    }
+}
@@ -0,0 +1,19 @@
+use crate::Method;
+
+/// Describes how a request's path and method related to the declared routes.
+///
+/// This distinguishes the two reasons a route may fail to resolve, so a server can respond with
+/// `404 Not Found` or `405 Method Not Allowed` as appropriate.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub enum RouteStatus {
+    /// A route matched both the path and the method.
+    Matched,
+
+    /// A route matched the path, but not with the request's method.
+    ///
+    /// Holds every method the path does resolve for, suitable for an `Allow` header.
+    MethodNotAllowed(Vec<Method>),
+
+    /// No route matched the path with any method.
+    NotFound,
+}
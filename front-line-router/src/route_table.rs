@@ -0,0 +1,226 @@
+//! Matching against a route table, a `const` slice of `(Method, &[Segment])` rows such as the
+//! `ROUTE_TABLE` generated by `front_line::RouteTable`.
+//!
+//! Matching a table walks its rows at runtime instead of running a generated `handle_parsed`, which
+//! keeps the generated code down to the table itself, e.g. for flash constrained `no_std` targets.
+
+use crate::Method;
+
+/// A `/` delimited segment of a path in a route table.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Segment {
+    /// A segment that must equal the literal, e.g. `users`.
+    Literal(&'static str),
+    /// A non-empty segment captured under the name, e.g. `{id}`.
+    Variable(&'static str),
+    /// The remainder of the path, slashes included, captured under the name, e.g. `{*path}`.
+    CatchAll(&'static str),
+}
+
+/// A row of a route table: the method and the segments of a route's path.
+pub type TableRoute = (Method, &'static [Segment]);
+
+/// A match of a path against a route table, created by `match_table`.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct TableMatch<'p> {
+    /// The index of the matched row in the table.
+    pub index: usize,
+    segments: &'static [Segment],
+    path: &'p str,
+}
+
+impl<'p> TableMatch<'p> {
+    /// Iterate over the captures of the match as `(name, value)` pairs, in the order they appear
+    /// in the path. Values are borrowed from the path, without any decoding.
+    pub fn captures(&self) -> TableCaptures<'p> {
+        TableCaptures {
+            segments: self.segments.iter(),
+            remaining: Some(self.path),
+        }
+    }
+
+    /// The value captured under `name`, if the matched route has such a variable.
+    pub fn capture(&self, name: &str) -> Option<&'p str> {
+        self.captures()
+            .find(|(variable, _)| *variable == name)
+            .map(|(_, value)| value)
+    }
+}
+
+/// An iterator over the captures of a `TableMatch`, created by `TableMatch::captures`.
+#[derive(Clone, Debug)]
+pub struct TableCaptures<'p> {
+    segments: core::slice::Iter<'static, Segment>,
+    remaining: Option<&'p str>,
+}
+
+impl<'p> Iterator for TableCaptures<'p> {
+    type Item = (&'static str, &'p str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for segment in self.segments.by_ref() {
+            let remaining = self.remaining?;
+            if let Segment::CatchAll(name) = segment {
+                self.remaining = None;
+                return Some((name, remaining));
+            }
+            let (value, rest) = match remaining.split_once('/') {
+                Some((value, rest)) => (value, Some(rest)),
+                None => (remaining, None),
+            };
+            self.remaining = rest;
+            if let Segment::Variable(name) = segment {
+                return Some((name, value));
+            }
+        }
+        None
+    }
+}
+
+/// Finds the first row of `table` for `method` whose segments match `path`.
+///
+/// The path must start with a `/`, and a query, if any, is ignored. Segments are compared exactly,
+/// so unlike a generated router the match is case-sensitive and a trailing slash is a segment of
+/// its own.
+///
+/// # Examples
+///
+/// ```
+/// use front_line_router::route_table::{match_table, Segment, TableRoute};
+/// use front_line_router::Method;
+///
+/// const TABLE: &[TableRoute] = &[
+///     (Method::Get, &[Segment::Literal("users")]),
+///     (Method::Get, &[Segment::Literal("users"), Segment::Variable("id")]),
+/// ];
+///
+/// let matched = match_table(TABLE, Method::Get, "/users/42?full=1").unwrap();
+/// assert_eq!(matched.index, 1);
+/// assert_eq!(matched.capture("id"), Some("42"));
+/// assert!(match_table(TABLE, Method::Post, "/users").is_none());
+/// ```
+pub fn match_table<'p>(
+    table: &[TableRoute],
+    method: Method,
+    path: &'p str,
+) -> Option<TableMatch<'p>> {
+    let path = path.split_once('?').map_or(path, |(path, _)| path);
+    let path = path.strip_prefix('/')?;
+    table
+        .iter()
+        .enumerate()
+        .filter(|(_, (row_method, _))| *row_method == method)
+        .find(|(_, (_, segments))| matches(segments, path))
+        .map(|(index, (_, segments))| TableMatch {
+            index,
+            segments,
+            path,
+        })
+}
+
+/// Whether `path`, without its leading `/`, matches `segments`.
+fn matches(segments: &[Segment], path: &str) -> bool {
+    let mut remaining = Some(path);
+    for segment in segments {
+        let path = match remaining {
+            Some(path) => path,
+            None => return false,
+        };
+        if let Segment::CatchAll(_) = segment {
+            return true;
+        }
+        let (value, rest) = match path.split_once('/') {
+            Some((value, rest)) => (value, Some(rest)),
+            None => (path, None),
+        };
+        let is_match = match segment {
+            Segment::Literal(literal) => value == *literal,
+            _ => !value.is_empty(),
+        };
+        if !is_match {
+            return false;
+        }
+        remaining = rest;
+    }
+    remaining.is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const TABLE: &[TableRoute] = &[
+        (Method::Get, &[Segment::Literal("")]),
+        (Method::Get, &[Segment::Literal("users")]),
+        (
+            Method::Get,
+            &[Segment::Literal("users"), Segment::Literal("me")],
+        ),
+        (
+            Method::Get,
+            &[Segment::Literal("users"), Segment::Variable("id")],
+        ),
+        (
+            Method::Get,
+            &[
+                Segment::Literal("users"),
+                Segment::Variable("id"),
+                Segment::Literal("posts"),
+                Segment::Variable("post"),
+            ],
+        ),
+        (
+            Method::Delete,
+            &[Segment::Literal("users"), Segment::Variable("id")],
+        ),
+        (
+            Method::Get,
+            &[Segment::Literal("assets"), Segment::CatchAll("path")],
+        ),
+    ];
+
+    #[rstest]
+    #[case(Method::Get, "/", Some((0, vec![])))]
+    #[case(Method::Get, "/users", Some((1, vec![])))]
+    #[case(Method::Get, "/users?page=2", Some((1, vec![])))]
+    #[case(Method::Get, "/users/me", Some((2, vec![])))]
+    #[case(Method::Get, "/users/42", Some((3, vec![("id", "42")])))]
+    #[case(Method::Delete, "/users/42", Some((5, vec![("id", "42")])))]
+    #[case(
+        Method::Get,
+        "/users/42/posts/7",
+        Some((4, vec![("id", "42"), ("post", "7")]))
+    )]
+    #[case(
+        Method::Get,
+        "/assets/css/app.css",
+        Some((6, vec![("path", "css/app.css")]))
+    )]
+    #[case(Method::Get, "/assets/", Some((6, vec![("path", "")])))]
+    #[case(Method::Get, "/users/", None)]
+    #[case(Method::Get, "/Users", None)]
+    #[case(Method::Get, "/users/42/posts", None)]
+    #[case(Method::Get, "/users//posts/7", None)]
+    #[case(Method::Get, "/assets", None)]
+    #[case(Method::Get, "users", None)]
+    #[case(Method::Get, "", None)]
+    #[case(Method::Post, "/users", None)]
+    fn test_match_table(
+        #[case] method: Method,
+        #[case] path: &str,
+        #[case] expected: Option<(usize, Vec<(&str, &str)>)>,
+    ) {
+        let matched = match_table(TABLE, method, path)
+            .map(|matched| (matched.index, matched.captures().collect::<Vec<_>>()));
+        assert_eq!(matched, expected);
+    }
+
+    #[test]
+    fn test_capture() {
+        let matched = match_table(TABLE, Method::Get, "/users/42/posts/7").unwrap();
+        assert_eq!(matched.capture("post"), Some("7"));
+        assert_eq!(matched.capture("id"), Some("42"));
+        assert_eq!(matched.capture("users"), None);
+    }
+}
@@ -1,12 +1,60 @@
-use crate::http_version::HttpVersion;
 use crate::method::Method;
-use crate::RouterResult;
-use memchr::memmem;
+use crate::parsed_request::ParsedRequest;
+#[cfg(feature = "std")]
+use crate::route_status::RouteStatus;
+use crate::{AnyMethodResult, MethodSet, PartialResult, RequestSpans, RouterResult};
 
-#[derive(thiserror::Error, PartialEq, Debug)]
+/// The ways parsing an HTTP request can fail.
+///
+/// Each variant corresponds to a stage of parsing, so callers can pick an appropriate response
+/// status or log an actionable message. With the `std` feature enabled this implements
+/// `std::error::Error` and `Display`.
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum Error {
-    #[error("the http request line was not valid")]
-    InvalidRequestLine,
+    /// The request line or the header block wasn't terminated, which usually means the request
+    /// hasn't been fully read yet.
+    #[cfg_attr(
+        feature = "std",
+        error("the request line or header block is missing its terminator")
+    )]
+    MissingHeaderTerminator,
+
+    /// The request line doesn't start with a known HTTP method followed by a space.
+    #[cfg_attr(
+        feature = "std",
+        error("the request method is not a known http method")
+    )]
+    InvalidMethod,
+
+    /// The request target isn't followed by a space and an HTTP version.
+    #[cfg_attr(feature = "std", error("the request target is malformed"))]
+    MalformedRequestTarget,
+
+    /// The HTTP version isn't one front-line supports.
+    #[cfg_attr(feature = "std", error("the http version is not supported"))]
+    UnsupportedVersion,
+
+    /// The path or query string isn't valid UTF-8.
+    #[cfg_attr(feature = "std", error("the request path is not valid utf-8"))]
+    NonUtf8Path,
+
+    /// The request line and headers weren't terminated within the limit passed to
+    /// `resolve_with_limits`.
+    #[cfg_attr(
+        feature = "std",
+        error("the request line and headers exceed the maximum length")
+    )]
+    HeadTooLarge,
+
+    /// The request has more than one `Host` header, or one that is malformed, like a `Host` line
+    /// folded into another header or one with whitespace before its colon. Only
+    /// `resolve_with_host` checks for this, since it routes on the host.
+    #[cfg_attr(
+        feature = "std",
+        error("the request has more than one host header or a malformed one")
+    )]
+    InvalidHost,
 }
 
 /// A trait that encapsulates routing logic for an HTTP request.
@@ -19,6 +67,41 @@ pub enum Error {
 /// query components. If parsing is successful, it constructs a `RouterResult` that encapsulates
 /// these parsed components.
 pub trait Router<'de>: Sized {
+    /// The prefix the router was declared with, like `/api`, or an empty string when there is
+    /// none.
+    ///
+    /// With several prefixes this is the first one declared, which `ToPath` also renders paths
+    /// with. A prefix with variables is given as declared, e.g. `/tenants/{tenant}`. Hand written
+    /// routers default to an empty prefix.
+    const PREFIX: &'static str = "";
+
+    /// The hosts the router was declared for with `#[host(..)]`, like `api.example.com`.
+    ///
+    /// A router with hosts only matches requests routed with `resolve_with_host` whose host is
+    /// one of them. Hand written routers, and generated ones without `#[host(..)]`, default to an
+    /// empty list and match any host.
+    const HOSTS: &'static [&'static str] = &[];
+
+    /// Every method the router may resolve a route for, on any path.
+    ///
+    /// `allowed_method_set` only tries these methods. Routers generated by
+    /// `front_line::FrontLine` list the methods of their routes, flattened sub-routers and
+    /// fallback route, while hand written routers default to every method.
+    const METHODS: MethodSet = MethodSet::ALL;
+
+    /// Every route pattern the router declares, with any prefixes applied.
+    ///
+    /// `handle_parsed_with_pattern` refers to the matched pattern by its index in this table.
+    /// Hand written routers default to an empty table.
+    const PATTERNS: &'static [&'static str] = &[];
+
+    /// Every route the router declares, as a method and a pattern with any prefixes applied.
+    ///
+    /// A route declared for several methods is listed once per method. This is handy for
+    /// documentation, debug endpoints and auditing route tables. Hand written routers default to
+    /// an empty table.
+    const ROUTES: &'static [(Method, &'static str)] = &[];
+
     /// Handle the parsed method and path segment.
     ///
     /// Implementers can provide custom logic to identify routes based on the parsed method and
@@ -35,11 +118,211 @@ pub trait Router<'de>: Sized {
     /// returns `None`.
     fn handle_parsed(method: Method, remaining_path: &'de str) -> Option<Self>;
 
+    /// Handle the parsed method, path segment, and query string.
+    ///
+    /// The default implementation ignores the query and delegates to `handle_parsed`. Routers
+    /// that capture query parameters, like those generated by `front_line::FrontLine`, override
+    /// this to match against the query as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The parsed HTTP method (e.g., GET, POST).
+    /// * `remaining_path` - The parsed path segment from the HTTP request.
+    /// * `query` - The query string from the HTTP request, without the leading `?`.
+    ///
+    /// # Returns
+    ///
+    /// Returns an instance of the implementing type if a route is identified. Otherwise,
+    /// returns `None`.
+    fn handle_parsed_with_query(
+        method: Method,
+        remaining_path: &'de str,
+        query: &'de str,
+    ) -> Option<Self> {
+        let _ = query;
+        Self::handle_parsed(method, remaining_path)
+    }
+
+    /// Handle the parsed method, path segment, and query string, reporting the matched pattern.
+    ///
+    /// The default implementation delegates to `handle_parsed_with_query` and reports no
+    /// pattern. Routers generated by `front_line::FrontLine` override this to report the index
+    /// of the matched pattern in `PATTERNS`.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The parsed HTTP method (e.g., GET, POST).
+    /// * `remaining_path` - The parsed path segment from the HTTP request.
+    /// * `query` - The query string from the HTTP request, without the leading `?`.
+    ///
+    /// # Returns
+    ///
+    /// Returns an instance of the implementing type, along with the index of its pattern in
+    /// `PATTERNS` if known, if a route is identified. Otherwise, returns `None`.
+    fn handle_parsed_with_pattern(
+        method: Method,
+        remaining_path: &'de str,
+        query: &'de str,
+    ) -> Option<(Self, Option<usize>)> {
+        Self::handle_parsed_with_query(method, remaining_path, query).map(|route| (route, None))
+    }
+
+    /// Handle the parsed method, raw path bytes, and query string, reporting the matched pattern.
+    ///
+    /// This is what `resolve` calls, so the path doesn't have to be validated as UTF-8 up front.
+    /// The default implementation validates the path and delegates to
+    /// `handle_parsed_with_pattern`. Routers generated by `front_line::FrontLine` override this to
+    /// compare literal segments on the raw bytes and only validate the slices bound to captures.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The parsed HTTP method (e.g., GET, POST).
+    /// * `remaining_path` - The raw path segment from the HTTP request.
+    /// * `query` - The query string from the HTTP request, without the leading `?`.
+    ///
+    /// # Returns
+    ///
+    /// Returns an instance of the implementing type, along with the index of its pattern in
+    /// `PATTERNS` if known, if a route is identified. Otherwise, returns `None`.
+    fn handle_parsed_bytes(
+        method: Method,
+        remaining_path: &'de [u8],
+        query: &'de str,
+    ) -> Option<(Self, Option<usize>)> {
+        let remaining_path = core::str::from_utf8(remaining_path).ok()?;
+        Self::handle_parsed_with_pattern(method, remaining_path, query)
+    }
+
+    /// Like `handle_parsed_bytes`, but also shortens `deepest` to the shortest remaining path
+    /// that any route reached before failing to match.
+    ///
+    /// This is plumbing for `handle_parsed_partial`. The default implementation delegates to
+    /// `handle_parsed_bytes` and leaves `deepest` as is.
+    #[doc(hidden)]
+    fn handle_parsed_tracking(
+        method: Method,
+        remaining_path: &'de [u8],
+        query: &'de str,
+        deepest: &mut &'de [u8],
+    ) -> Option<(Self, Option<usize>)> {
+        let _ = deepest;
+        Self::handle_parsed_bytes(method, remaining_path, query)
+    }
+
+    /// Like `handle_parsed_tracking`, but also given the request's host, if known, so routers
+    /// declared for particular hosts can check it.
+    ///
+    /// This is plumbing for `resolve_with_host`. The default implementation ignores the host and
+    /// delegates to `handle_parsed_tracking`.
+    #[doc(hidden)]
+    fn handle_parsed_with_host(
+        method: Method,
+        host: Option<&'de str>,
+        remaining_path: &'de [u8],
+        query: &'de str,
+        deepest: &mut &'de [u8],
+    ) -> Option<(Self, Option<usize>)> {
+        let _ = host;
+        Self::handle_parsed_tracking(method, remaining_path, query, deepest)
+    }
+
+    /// Like `handle_parsed_with_host`, but pushes every route that matches onto `matches`, in the
+    /// order they're tried, instead of returning the first.
+    ///
+    /// This is plumbing for `resolve_all`. The default implementation pushes the route
+    /// `handle_parsed_with_host` returns, if any, while routers generated by
+    /// `front_line::FrontLine` try every route.
+    #[cfg(feature = "resolve-all")]
+    #[doc(hidden)]
+    fn handle_parsed_all(
+        method: Method,
+        host: Option<&'de str>,
+        remaining_path: &'de [u8],
+        query: &'de str,
+        matches: &mut Vec<(Self, Option<usize>)>,
+    ) {
+        let mut deepest = remaining_path;
+        matches.extend(Self::handle_parsed_with_host(
+            method,
+            host,
+            remaining_path,
+            query,
+            &mut deepest,
+        ));
+    }
+
+    /// Like `handle_parsed_with_host`, but inserts the method of every route that matches the
+    /// path into `allowed`, whatever the request's method.
+    ///
+    /// This is plumbing for `allowed_method_set`. The default implementation tries each method
+    /// in `METHODS` in turn, while routers generated by `front_line::FrontLine` walk the routes of
+    /// every method in a single pass.
+    #[doc(hidden)]
+    fn handle_parsed_methods(
+        host: Option<&'de str>,
+        remaining_path: &'de [u8],
+        query: &'de str,
+        allowed: &mut MethodSet,
+    ) {
+        for method in Self::METHODS.iter() {
+            let mut deepest = remaining_path;
+            if Self::handle_parsed_with_host(method, host, remaining_path, query, &mut deepest)
+                .is_some()
+            {
+                allowed.insert(method);
+            }
+        }
+    }
+
+    /// Handle the parsed method, path segment, and query string, reporting how far matching got
+    /// when no route matches.
+    ///
+    /// This is useful for dispatching the rest of the path to a sub-router by hand, or for
+    /// explaining a 404, e.g. "no route under /api/users". Routers generated by
+    /// `front_line::FrontLine` track the literals, captures and prefixes matched by every route
+    /// they try, while hand written routers report the whole path as unmatched.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The parsed HTTP method (e.g., GET, POST).
+    /// * `remaining_path` - The parsed path segment from the HTTP request.
+    /// * `query` - The query string from the HTTP request, without the leading `?`.
+    ///
+    /// # Returns
+    ///
+    /// Returns the identified route and an empty remaining path if a route is identified.
+    /// Otherwise, returns `None` and the shortest remaining path any route reached before it
+    /// failed to match.
+    fn handle_parsed_partial(
+        method: Method,
+        remaining_path: &'de str,
+        query: &'de str,
+    ) -> (Option<Self>, &'de str) {
+        let mut deepest = remaining_path.as_bytes();
+        let route =
+            Self::handle_parsed_tracking(method, remaining_path.as_bytes(), query, &mut deepest);
+        match route {
+            Some((route, _)) => (Some(route), ""),
+            None => {
+                let matched = remaining_path.len() - deepest.len();
+                (
+                    None,
+                    remaining_path.get(matched..).unwrap_or(remaining_path),
+                )
+            }
+        }
+    }
+
     /// Parse and route an HTTP request.
     ///
     /// This method provides the core logic to process an HTTP request byte slice, extract its
     /// components, and identify a route if possible.
     ///
+    /// The request target is split into its path and query at the first `?`, so later `?`s are
+    /// part of the query, while an encoded `%3F` stays part of the path. Clients never send a
+    /// `#fragment`, and `#` isn't special either: it's part of the query after a `?`, and
+    /// otherwise part of the path, which routes won't match unless they declare it.
+    ///
     /// # Arguments
     ///
     /// * `request` - The raw byte slice of the HTTP request.
@@ -49,34 +332,335 @@ pub trait Router<'de>: Sized {
     /// Returns a `Result` containing the `RouterResult` if routing is successful. If any parsing
     /// or validation errors occur, returns an `Error`.
     fn resolve(request: &'de [u8]) -> Result<RouterResult<'de, Self>, Error> {
-        let end = memmem::find(request, b"\r\n\r\n").ok_or(Error::InvalidRequestLine)?;
-        let request_line = &request[..end];
-        let (method, after_method) =
-            Method::parse(request_line).ok_or(Error::InvalidRequestLine)?;
-        let full_path_end = memchr::memchr(b' ', after_method).ok_or(Error::InvalidRequestLine)?;
-        let after_path = &after_method[full_path_end + 1..];
-        let version = HttpVersion::parse(after_path).ok_or(Error::InvalidRequestLine)?;
-        let full_path = &after_method[..full_path_end];
-        let query_start = memchr::memchr(b'?', full_path).unwrap_or(full_path.len());
-        let query_bytes = &full_path[full_path.len().min(query_start + 1)..];
-        let query = std::str::from_utf8(query_bytes).map_err(|_| Error::InvalidRequestLine)?;
-        let path_bytes = &full_path[..query_start];
-        let path = std::str::from_utf8(path_bytes).map_err(|_| Error::InvalidRequestLine)?;
-        let route = Self::handle_parsed(method, path);
-        let head_and_body = &request[end + 4..];
-        let result = RouterResult {
-            route,
-            query,
-            version,
-            head_and_body,
+        let parsed = ParsedRequest::parse(request)?;
+        let (route, pattern) = parsed.route::<Self>()?;
+        Ok(parsed.into_result(route, pattern))
+    }
+
+    /// Parse and route an HTTP request whose path may not be valid UTF-8.
+    ///
+    /// This behaves like `resolve`, but a path that isn't valid UTF-8 and matches no route
+    /// resolves with `route` set to `None`, instead of failing the whole request with
+    /// `Error::NonUtf8Path`, so it falls through to the usual not found handling. Either way, a
+    /// route whose captures accept any bytes, like `&[u8]` or `Latin1`, still matches such a
+    /// path. The query string must still be valid UTF-8.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The raw byte slice of the HTTP request.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the `RouterResult` if the request could be parsed. If any
+    /// other parsing or validation errors occur, returns an `Error`.
+    fn resolve_non_utf8(request: &'de [u8]) -> Result<RouterResult<'de, Self>, Error> {
+        let parsed = ParsedRequest::parse(request)?;
+        let (route, pattern) = match parsed.route::<Self>() {
+            Err(Error::NonUtf8Path) => (None, None),
+            result => result?,
+        };
+        Ok(parsed.into_result(route, pattern))
+    }
+
+    /// Parse and route an HTTP request, also locating each part of it within `request`.
+    ///
+    /// This behaves like `resolve`, and additionally returns the byte ranges of the method, path,
+    /// query, version, headers and body, so the raw bytes of each can be sliced back out of the
+    /// original buffer, e.g. to log the undecoded path, without scanning the request again.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The raw byte slice of the HTTP request.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the `RouterResult` and the `RequestSpans` of the request if
+    /// routing is successful. If any parsing or validation errors occur, returns an `Error`.
+    fn resolve_spans(request: &'de [u8]) -> Result<(RouterResult<'de, Self>, RequestSpans), Error> {
+        let parsed = ParsedRequest::parse(request)?;
+        let (route, pattern) = parsed.route::<Self>()?;
+        let spans = RequestSpans::locate(request, &parsed);
+        Ok((parsed.into_result(route, pattern), spans))
+    }
+
+    /// Parse an HTTP request and collect every route that matches it, not just the first.
+    ///
+    /// This is a diagnostic for finding routes that overlap at runtime, e.g. `/users/me` and
+    /// `/users/{name}`. Routes are listed in the order `resolve` tries them, so the first is the
+    /// one it returns, and a fallback route is only listed when no other route matches. It
+    /// allocates and tries every route, so it's only available with the `resolve-all` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The raw byte slice of the HTTP request.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing every route that matches, which is empty when none do. If
+    /// any parsing or validation errors occur, returns an `Error`.
+    #[cfg(feature = "resolve-all")]
+    fn resolve_all(request: &'de [u8]) -> Result<Vec<Self>, Error> {
+        let parsed = ParsedRequest::parse(request)?;
+        let mut matches = Vec::new();
+        if parsed.target_form.is_routable() {
+            Self::handle_parsed_all(parsed.method, None, parsed.path, parsed.query, &mut matches);
+        }
+        if matches.is_empty() {
+            parsed.path_str()?;
+        }
+        Ok(matches.into_iter().map(|(route, _)| route).collect())
+    }
+
+    /// Parse and route an HTTP request, bounding how far it scans for the end of the headers.
+    ///
+    /// This behaves like `resolve`, but only looks for the `\r\n\r\n` that ends the headers
+    /// within the first `max_head_len` bytes of the request, so a client can't force a scan of a
+    /// multi-megabyte request line or header block before it's rejected. The limit covers the
+    /// request line, the headers and the blank line that ends them, but not the body.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The raw byte slice of the HTTP request.
+    /// * `max_head_len` - The maximum length of the request line and headers, in bytes.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the `RouterResult` if routing is successful. Returns
+    /// `Error::HeadTooLarge` if the headers aren't terminated within `max_head_len` bytes, or
+    /// any other `Error` if parsing or validation fails.
+    fn resolve_with_limits(
+        request: &'de [u8],
+        max_head_len: usize,
+    ) -> Result<RouterResult<'de, Self>, Error> {
+        let parsed = ParsedRequest::parse_with_limit(request, max_head_len)?;
+        let (route, pattern) = parsed.route::<Self>()?;
+        Ok(parsed.into_result(route, pattern))
+    }
+
+    /// Parse and route an HTTP request, taking the host it was sent to into account.
+    ///
+    /// This behaves like `resolve`, but also reads the host from an absolute-form request target
+    /// or the `Host` header, see `RouterResult::host`, so routers declared with `#[host(..)]` only
+    /// match requests sent to one of their hosts. Routers with hosts never match through
+    /// `resolve` and the other methods, which don't know the host.
+    ///
+    /// As RFC 7230 requires, a request with more than one `Host` header, or a malformed one, is
+    /// rejected rather than routed on a host a proxy in front of the server may not have seen.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The raw byte slice of the HTTP request.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the `RouterResult` if routing is successful. If any parsing
+    /// or validation errors occur, returns an `Error`, which is `Error::InvalidHost` for repeated
+    /// or malformed `Host` headers.
+    fn resolve_with_host(request: &'de [u8]) -> Result<RouterResult<'de, Self>, Error> {
+        let parsed = ParsedRequest::parse(request)?;
+        let (route, pattern) = parsed.route_with_host::<Self>()?;
+        Ok(parsed.into_result(route, pattern))
+    }
+
+    /// Parse and route an HTTP request that may not have been fully read yet.
+    ///
+    /// This behaves like `resolve`, but when the `\r\n\r\n` that ends the headers hasn't
+    /// arrived yet it checks whether the bytes read so far could still begin a valid request,
+    /// rather than reporting `Error::MissingHeaderTerminator`. The method, request target and
+    /// version seen so far are validated, so a non-blocking server can tell whether to read more
+    /// bytes or close the connection.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The raw byte slice of the HTTP request read so far.
+    ///
+    /// # Returns
+    ///
+    /// Returns `PartialResult::Complete` with the `RouterResult` if the headers are terminated
+    /// and routing is successful, `PartialResult::Incomplete` if more bytes are needed, or
+    /// `PartialResult::Invalid` with an `Error` if the request is already malformed.
+    fn resolve_partial(request: &'de [u8]) -> PartialResult<'de, Self> {
+        match Self::resolve(request) {
+            Ok(result) => PartialResult::Complete(result),
+            Err(Error::MissingHeaderTerminator) => match ParsedRequest::check_incomplete(request) {
+                Ok(()) => PartialResult::Incomplete,
+                Err(error) => PartialResult::Invalid(error),
+            },
+            Err(error) => PartialResult::Invalid(error),
+        }
+    }
+
+    /// Parse and route an HTTP request, tolerating bare `\n` line endings and extra spaces.
+    ///
+    /// This behaves like `resolve`, but also accepts requests from clients that end lines with
+    /// `\n` instead of `\r\n`: the request line may end with either, and the headers may be
+    /// terminated by `\n\n` as well as `\r\n\r\n`. The method, request target and version may
+    /// also be separated by more than one space, with leading spaces before the method and
+    /// trailing spaces before the line ending ignored, and the method token may be in any case,
+    /// like `get`, as parsed by `Method::parse_ci`. Prefer `resolve` where strict conformance
+    /// matters.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The raw byte slice of the HTTP request.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the `RouterResult` if routing is successful. If any parsing
+    /// or validation errors occur, returns an `Error`.
+    fn resolve_lenient(request: &'de [u8]) -> Result<RouterResult<'de, Self>, Error> {
+        let parsed = ParsedRequest::parse_lenient(request)?;
+        let (route, pattern) = parsed.route::<Self>()?;
+        Ok(parsed.into_result(route, pattern))
+    }
+
+    /// Parse and route an HTTP request, tolerating a missing blank line after a request line
+    /// without headers.
+    ///
+    /// This behaves like `resolve`, but also accepts a request that ends right after the `\r\n`
+    /// of its request line, like `GET / HTTP/1.1\r\n` as sent by some minimal clients, as a
+    /// request without headers or a body. The request line must still be complete, with its
+    /// version and `\r\n`, and once any header bytes follow it the blank line is required
+    /// again. Since a read that stopped right after the request line looks the same, only use
+    /// this when `request` is known to hold the whole request, e.g. after the client has closed
+    /// its side of the connection.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The raw byte slice of the HTTP request.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the `RouterResult` if routing is successful. If any parsing
+    /// or validation errors occur, returns an `Error`.
+    fn resolve_unterminated(request: &'de [u8]) -> Result<RouterResult<'de, Self>, Error> {
+        let parsed = ParsedRequest::parse_unterminated(request)?;
+        let (route, pattern) = parsed.route::<Self>()?;
+        Ok(parsed.into_result(route, pattern))
+    }
+
+    /// Parse and route an HTTP request, accepting methods that `Method` doesn't model.
+    ///
+    /// This behaves like `resolve`, but a request line starting with any valid method token, such
+    /// as `PROPFIND`, is still parsed rather than rejected with `Error::InvalidMethod`, so a
+    /// server can respond with `501 Not Implemented` using the request's version.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The raw byte slice of the HTTP request.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing `AnyMethodResult::Known` with the `RouterResult` for known
+    /// methods, or `AnyMethodResult::Unknown` with the rest of the request for unknown ones. If
+    /// any other parsing or validation errors occur, returns an `Error`.
+    fn resolve_any_method(request: &'de [u8]) -> Result<AnyMethodResult<'de, Self>, Error> {
+        match Self::resolve(request) {
+            Err(Error::InvalidMethod) => {
+                ParsedRequest::parse_unknown_method(request).map(AnyMethodResult::Unknown)
+            }
+            result => result.map(AnyMethodResult::Known),
+        }
+    }
+
+    /// Route a method and request target that were already parsed elsewhere, e.g. by a server
+    /// framework, without a request to parse.
+    ///
+    /// The target is split into its path and query at the first `?`, and then matched like
+    /// `resolve` would match them.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The parsed HTTP method (e.g., GET, POST).
+    /// * `path` - The request target, e.g. `/search?q=rust`.
+    ///
+    /// # Returns
+    ///
+    /// Returns an instance of the implementing type if a route is identified. Otherwise,
+    /// returns `None`.
+    fn resolve_parts(method: Method, path: &'de str) -> Option<Self> {
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        Self::handle_parsed_bytes(method, path.as_bytes(), query).map(|(route, _)| route)
+    }
+
+    /// Collect every method that resolves a route for the given path and query, without
+    /// allocating.
+    ///
+    /// Routers generated by `front_line::FrontLine` match the path against the routes of every
+    /// method in a single pass, while hand written routers try each method in `METHODS` in turn.
+    ///
+    /// # Arguments
+    ///
+    /// * `remaining_path` - The parsed path segment from the HTTP request.
+    /// * `query` - The query string from the HTTP request, without the leading `?`.
+    ///
+    /// # Returns
+    ///
+    /// Returns the set of methods that resolve a route, which displays as an `Allow` header value.
+    fn allowed_method_set(remaining_path: &'de str, query: &'de str) -> MethodSet {
+        let mut allowed = MethodSet::EMPTY;
+        Self::handle_parsed_methods(None, remaining_path.as_bytes(), query, &mut allowed);
+        allowed
+    }
+
+    /// Collect every method that resolves a route for the given path and query.
+    ///
+    /// This is `allowed_method_set` collected into a `Vec`.
+    ///
+    /// # Arguments
+    ///
+    /// * `remaining_path` - The parsed path segment from the HTTP request.
+    /// * `query` - The query string from the HTTP request, without the leading `?`.
+    ///
+    /// # Returns
+    ///
+    /// Returns the methods that resolve a route, in `Method::ALL` order.
+    #[cfg(feature = "std")]
+    fn allowed_methods(remaining_path: &'de str, query: &'de str) -> Vec<Method> {
+        Self::allowed_method_set(remaining_path, query)
+            .iter()
+            .collect()
+    }
+
+    /// Parse and route an HTTP request, distinguishing unknown paths from disallowed methods.
+    ///
+    /// This behaves like `resolve`, but when no route matches it additionally checks whether the
+    /// path resolves for any other method.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The raw byte slice of the HTTP request.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the `RouterResult` and a `RouteStatus` describing why it did
+    /// or didn't match. If any parsing or validation errors occur, returns an `Error`.
+    #[cfg(feature = "std")]
+    fn resolve_with_status(
+        request: &'de [u8],
+    ) -> Result<(RouterResult<'de, Self>, RouteStatus), Error> {
+        let parsed = ParsedRequest::parse(request)?;
+        let (route, pattern) = parsed.route::<Self>()?;
+        let status = if route.is_some() {
+            RouteStatus::Matched
+        } else if !parsed.target_form.is_routable() {
+            RouteStatus::NotFound
+        } else {
+            let allowed = Self::allowed_methods(parsed.path_str()?, parsed.query);
+            if allowed.is_empty() {
+                RouteStatus::NotFound
+            } else {
+                RouteStatus::MethodNotAllowed(allowed)
+            }
         };
-        Ok(result)
+        Ok((parsed.into_result(route, pattern), status))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{HttpVersion, TargetForm, UnknownMethodRequest};
     use rstest::rstest;
 
     #[derive(PartialEq, Debug)]
@@ -88,6 +672,7 @@ mod tests {
         fn handle_parsed(method: Method, remaining_path: &'de str) -> Option<Self> {
             match (method, remaining_path) {
                 (Method::Get, "/test") => Some(TestRoute::Test),
+                (Method::Head, "/test") => Some(TestRoute::Test),
                 _ => None,
             }
         }
@@ -98,69 +683,196 @@ mod tests {
         b"GET /test HTTP/1.1\r\n\r\nSome data",
         Ok(RouterResult {
             route: Some(TestRoute::Test),
+            pattern: None,
+            method: Method::Get,
+            authority: None,
+            target_form: TargetForm::Origin,
+            raw_path: b"/test",
             query: "",
             version: HttpVersion::OneOne,
-            head_and_body: b"Some data",
+            raw_headers: b"",
+            raw_body: b"Some data",
+            lenient: false,
         })
     )]
     #[case(
         b"GET /test?query=value HTTP/1.1\r\n\r\n",
         Ok(RouterResult {
             route: Some(TestRoute::Test),
+            pattern: None,
+            method: Method::Get,
+            authority: None,
+            target_form: TargetForm::Origin,
+            raw_path: b"/test",
             query: "query=value",
             version: HttpVersion::OneOne,
-            head_and_body: b"",
+            raw_headers: b"",
+            raw_body: b"",
+            lenient: false,
         })
     )]
     #[case(
         b"GET /test HTTP/1.0\r\n\r\n",
         Ok(RouterResult {
             route: Some(TestRoute::Test),
+            pattern: None,
+            method: Method::Get,
+            authority: None,
+            target_form: TargetForm::Origin,
+            raw_path: b"/test",
             query: "",
             version: HttpVersion::OneZero,
-            head_and_body: b"",
+            raw_headers: b"",
+            raw_body: b"",
+            lenient: false,
         })
     )]
     #[case(
         b"POST /test HTTP/1.1\r\n\r\n",
         Ok(RouterResult {
             route: None,
+            pattern: None,
+            method: Method::Post,
+            authority: None,
+            target_form: TargetForm::Origin,
+            raw_path: b"/test",
             query: "",
             version: HttpVersion::OneOne,
-            head_and_body: b"",
+            raw_headers: b"",
+            raw_body: b"",
+            lenient: false,
         })
     )]
     #[case(
         b"GET /invalid HTTP/1.1\r\n\r\n",
         Ok(RouterResult {
             route: None,
+            pattern: None,
+            method: Method::Get,
+            authority: None,
+            target_form: TargetForm::Origin,
+            raw_path: b"/invalid",
             query: "",
             version: HttpVersion::OneOne,
-            head_and_body: b"",
+            raw_headers: b"",
+            raw_body: b"",
+            lenient: false,
         })
     )]
     #[case(
         b"GET /invalid?key=value HTTP/1.1\r\n\r\n",
         Ok(RouterResult {
             route: None,
+            pattern: None,
+            method: Method::Get,
+            authority: None,
+            target_form: TargetForm::Origin,
+            raw_path: b"/invalid",
             query: "key=value",
             version: HttpVersion::OneOne,
-            head_and_body: b"",
+            raw_headers: b"",
+            raw_body: b"",
+            lenient: false,
         })
     )]
     #[case(
         b"GET /invalid?key=value HTTP/1.1\r\n\r\nheader-section",
         Ok(RouterResult {
             route: None,
+            pattern: None,
+            method: Method::Get,
+            authority: None,
+            target_form: TargetForm::Origin,
+            raw_path: b"/invalid",
             query: "key=value",
             version: HttpVersion::OneOne,
-            head_and_body: b"header-section",
+            raw_headers: b"",
+            raw_body: b"header-section",
+            lenient: false,
+        })
+    )]
+    #[case(
+        b"GET /test HTTP/1.1\r\nHost: example.com\r\nAccept: */*\r\n\r\nSome data",
+        Ok(RouterResult {
+            route: Some(TestRoute::Test),
+            pattern: None,
+            method: Method::Get,
+            authority: None,
+            target_form: TargetForm::Origin,
+            raw_path: b"/test",
+            query: "",
+            version: HttpVersion::OneOne,
+            raw_headers: b"Host: example.com\r\nAccept: */*\r\n",
+            raw_body: b"Some data",
+            lenient: false,
+        })
+    )]
+    #[case(
+        b"GET /test HTTP/1.1\r\nHost: example.com\r\n\r\n",
+        Ok(RouterResult {
+            route: Some(TestRoute::Test),
+            pattern: None,
+            method: Method::Get,
+            authority: None,
+            target_form: TargetForm::Origin,
+            raw_path: b"/test",
+            query: "",
+            version: HttpVersion::OneOne,
+            raw_headers: b"Host: example.com\r\n",
+            raw_body: b"",
+            lenient: false,
+        })
+    )]
+    #[case(
+        b"GET  HTTP/1.1\r\n\r\n",
+        Ok(RouterResult {
+            route: None,
+            pattern: None,
+            method: Method::Get,
+            authority: None,
+            target_form: TargetForm::Empty,
+            raw_path: b"",
+            query: "",
+            version: HttpVersion::OneOne,
+            raw_headers: b"",
+            raw_body: b"",
+            lenient: false,
         })
     )]
-    #[case(b"GET /test HTT/1.1\r\n\r\n", Err(Error::InvalidRequestLine))]
-    #[case(b"GET /test", Err(Error::InvalidRequestLine))]
-    #[case(b"GET/test HTTP/1.1\r\n\r\n", Err(Error::InvalidRequestLine))]
-    #[case(b"GET /test HTTP/1.1\r\nSome data", Err(Error::InvalidRequestLine))]
+    #[case(
+        b"HEAD ?page=2 HTTP/1.0\r\n\r\n",
+        Ok(RouterResult {
+            route: None,
+            pattern: None,
+            method: Method::Head,
+            authority: None,
+            target_form: TargetForm::Empty,
+            raw_path: b"",
+            query: "page=2",
+            version: HttpVersion::OneZero,
+            raw_headers: b"",
+            raw_body: b"",
+            lenient: false,
+        })
+    )]
+    #[case(b"GET /test HTT/1.1\r\n\r\n", Err(Error::UnsupportedVersion))]
+    #[case(b"GET /test HTTP/2.0\r\n\r\n", Err(Error::UnsupportedVersion))]
+    #[case(b"GET /test", Err(Error::MissingHeaderTerminator))]
+    #[case(b"GET/test HTTP/1.1\r\n\r\n", Err(Error::InvalidMethod))]
+    #[case(b"FETCH /test HTTP/1.1\r\n\r\n", Err(Error::InvalidMethod))]
+    #[case(b"GET /test\r\n\r\n", Err(Error::MalformedRequestTarget))]
+    #[case(b"GET /test/for/something\r\n\r\n", Err(Error::MalformedRequestTarget))]
+    #[case(b"GET /t\xffst HTTP/1.1\r\n\r\n", Err(Error::NonUtf8Path))]
+    #[case(b"GET /test?\xff HTTP/1.1\r\n\r\n", Err(Error::NonUtf8Path))]
+    #[case(
+        b"GET /test HTTP/1.1\r\nSome data",
+        Err(Error::MissingHeaderTerminator)
+    )]
+    #[case(
+        b"GET /test HTTP/1.1\r\nHost: example.com\r\n",
+        Err(Error::MissingHeaderTerminator)
+    )]
+    #[case(b"GET /test HTT/1.1\r\nHost: a", Err(Error::UnsupportedVersion))]
     fn test_route(
         #[case] input: &[u8],
         #[case] expected_result: Result<RouterResult<'_, TestRoute>, Error>,
@@ -168,4 +880,420 @@ mod tests {
         let result = TestRoute::resolve(input);
         assert_eq!(result, expected_result);
     }
+
+    #[rstest]
+    #[case(b"GET /test HTTP/1.1\r\n\r\nSome data", b"", b"Some data")]
+    #[case(b"GET /test HTTP/1.1\n\nSome data", b"", b"Some data")]
+    #[case(b"GET /test HTTP/1.1\r\n\nSome data", b"", b"Some data")]
+    #[case(
+        b"GET /test HTTP/1.1\nHost: a\nAccept: */*\n\n",
+        b"Host: a\nAccept: */*\n",
+        b""
+    )]
+    #[case(
+        b"GET /test HTTP/1.1\nHost: a\r\n\r\nSome data",
+        b"Host: a\r\n",
+        b"Some data"
+    )]
+    #[case(
+        b"GET /test HTTP/1.1\r\nHost: a\n\r\nSome data",
+        b"Host: a\n",
+        b"Some data"
+    )]
+    fn test_route_lenient(
+        #[case] input: &[u8],
+        #[case] expected_headers: &[u8],
+        #[case] expected_body: &[u8],
+    ) {
+        let result = TestRoute::resolve_lenient(input).unwrap();
+        assert_eq!(result.route, Some(TestRoute::Test));
+        assert_eq!(result.version, HttpVersion::OneOne);
+        assert_eq!(result.raw_headers, expected_headers);
+        assert_eq!(result.raw_body, expected_body);
+        assert!(result.lenient);
+        assert!(result.headers().all(|(_, value)| !value.contains('\n')));
+    }
+
+    #[test]
+    fn test_route_lenient_host() {
+        let request = b"GET /test HTTP/1.1\nHost: example.com\nAccept: */*\n\n";
+        let result = TestRoute::resolve_lenient(request).unwrap();
+        assert_eq!(result.host(), Some("example.com"));
+        let request = b"GET /test HTTP/1.1\r\nHost: example.com\nAccept: */*\r\n\r\n";
+        let result = TestRoute::resolve(request).unwrap();
+        assert!(!result.lenient);
+        assert!(result.headers().all(|(name, _)| name != "Accept"));
+    }
+
+    #[rstest]
+    #[case(b"GET /test HTTP/1.1\n\n")]
+    #[case(b"GET /test HTTP/1.1\r\n\n")]
+    #[case(b"GET /test HTTP/1.1\nHost: a\n\n")]
+    fn test_route_strict_rejects_bare_lf(#[case] input: &[u8]) {
+        assert_eq!(
+            TestRoute::resolve(input),
+            Err(Error::MissingHeaderTerminator)
+        );
+    }
+
+    #[rstest]
+    #[case(b"GET /test HTTP/1.1\r\n\r\n", 22, Ok(b"".as_slice()))]
+    #[case(b"GET /test HTTP/1.1\r\n\r\nbody", 22, Ok(b"body".as_slice()))]
+    #[case(b"GET /test HTTP/1.1\r\nHost: a\r\n\r\nbody", 31, Ok(b"body".as_slice()))]
+    #[case(b"GET /test HTTP/1.1\r\n\r\n", 21, Err(Error::HeadTooLarge))]
+    #[case(b"GET /test HTTP/1.1\r\nHost: a\r\n\r\n", 30, Err(Error::HeadTooLarge))]
+    #[case(b"GET /test HTTP/1.1\r\n\r\n", 4, Err(Error::HeadTooLarge))]
+    #[case(
+        b"GET /test HTTP/1.1\r\nHost: a",
+        64,
+        Err(Error::MissingHeaderTerminator)
+    )]
+    #[case(b"GET /test HTT/1.1\r\n\r\n", 64, Err(Error::UnsupportedVersion))]
+    fn test_route_with_limits(
+        #[case] input: &[u8],
+        #[case] max_head_len: usize,
+        #[case] expected_body: Result<&[u8], Error>,
+    ) {
+        let result = TestRoute::resolve_with_limits(input, max_head_len);
+        assert_eq!(result.map(|result| result.raw_body), expected_body);
+    }
+
+    #[rstest]
+    #[case(b"GET /test HTTP/1.1\r\n\r\nbody", Ok(Some(b"body".as_slice())))]
+    #[case(b"GET /test HTTP/1.1\r\nHost: a\r\n\r\n", Ok(Some(b"".as_slice())))]
+    #[case(b"", Ok(None))]
+    #[case(b"G", Ok(None))]
+    #[case(b"OPTI", Ok(None))]
+    #[case(b"GET", Ok(None))]
+    #[case(b"GET ", Ok(None))]
+    #[case(b"GET /te", Ok(None))]
+    #[case(b"GET /test ", Ok(None))]
+    #[case(b"GET /test HTTP/1.", Ok(None))]
+    #[case(b"GET /test HTTP/1.1", Ok(None))]
+    #[case(b"GET /test HTTP/1.1\r", Ok(None))]
+    #[case(b"GET /test HTTP/1.1\r\n", Ok(None))]
+    #[case(b"GET /test HTTP/1.1\r\nHost: a\r\n", Ok(None))]
+    #[case(b"GET /test HTTP/1.1\r\nHost: a\r\n\r", Ok(None))]
+    #[case(b"X", Err(Error::InvalidMethod))]
+    #[case(b"get", Err(Error::InvalidMethod))]
+    #[case(b"GETS ", Err(Error::InvalidMethod))]
+    #[case(b"GET * ", Err(Error::MalformedRequestTarget))]
+    #[case(b"GET /test HTTP/2", Err(Error::UnsupportedVersion))]
+    #[case(b"GET /test HTTP/1.1 ", Err(Error::UnsupportedVersion))]
+    #[case(b"GET /test HTTP/1.1x\r", Err(Error::UnsupportedVersion))]
+    #[case(b"GET /test\r", Err(Error::MalformedRequestTarget))]
+    #[case(b"GET /test HTTP/3.0\r\nHost: a", Err(Error::UnsupportedVersion))]
+    fn test_route_partial(
+        #[case] input: &[u8],
+        #[case] expected_body: Result<Option<&[u8]>, Error>,
+    ) {
+        let result = match TestRoute::resolve_partial(input) {
+            PartialResult::Complete(result) => Ok(Some(result.raw_body)),
+            PartialResult::Incomplete => Ok(None),
+            PartialResult::Invalid(error) => Err(error),
+        };
+        assert_eq!(result, expected_body);
+    }
+
+    #[rstest]
+    #[case(b"GET  /test HTTP/1.1\r\n\r\n")]
+    #[case(b"GET /test   HTTP/1.1\r\n\r\n")]
+    #[case(b"  GET /test HTTP/1.1\r\n\r\n")]
+    #[case(b"GET /test HTTP/1.1  \r\n\r\n")]
+    #[case(b"  GET   /test   HTTP/1.1  \n\n")]
+    #[case(b"get /test HTTP/1.1\r\n\r\n")]
+    #[case(b"Get /test HTTP/1.1\n\n")]
+    #[case(b"  gEt   /test HTTP/1.1\r\n\r\n")]
+    fn test_route_lenient_whitespace(#[case] input: &[u8]) {
+        let result = TestRoute::resolve_lenient(input).unwrap();
+        assert_eq!(result.route, Some(TestRoute::Test));
+        assert_eq!(result.method, Method::Get);
+        assert_eq!(result.version, HttpVersion::OneOne);
+    }
+
+    #[rstest]
+    #[case(b"GET /test HTTP/1.1\r\n\r\n", Ok(Some(TestRoute::Test)))]
+    #[case(b"GET /t\xffst HTTP/1.1\r\n\r\n", Ok(None))]
+    #[case(b"GET /test?\xff HTTP/1.1\r\n\r\n", Err(Error::NonUtf8Path))]
+    #[case(b"GET /test HTTP/1.1\r\n", Err(Error::MissingHeaderTerminator))]
+    fn test_route_non_utf8(
+        #[case] input: &[u8],
+        #[case] expected: Result<Option<TestRoute>, Error>,
+    ) {
+        let result = TestRoute::resolve_non_utf8(input).map(|result| result.route);
+        assert_eq!(result, expected);
+    }
+
+    #[rstest]
+    #[case(b"GET /test HTTP/1.1\r\n", Ok((Some(TestRoute::Test), b"".as_slice())))]
+    #[case(b"GET /test?a=1 HTTP/1.0\r\n", Ok((Some(TestRoute::Test), b"".as_slice())))]
+    #[case(b"POST /test HTTP/1.1\r\n", Ok((None, b"".as_slice())))]
+    #[case(
+        b"GET /test HTTP/1.1\r\n\r\nbody",
+        Ok((Some(TestRoute::Test), b"body".as_slice()))
+    )]
+    #[case(
+        b"GET /test HTTP/1.1\r\nHost: a\r\n\r\n",
+        Ok((Some(TestRoute::Test), b"".as_slice()))
+    )]
+    #[case(b"GET /test HTTP/1.1", Err(Error::MissingHeaderTerminator))]
+    #[case(b"GET /test HTTP/1.1\r", Err(Error::MissingHeaderTerminator))]
+    #[case(b"GET /test HTTP/1.\r\n", Err(Error::UnsupportedVersion))]
+    #[case(b"GET /test\r\n", Err(Error::MalformedRequestTarget))]
+    #[case(
+        b"GET /test HTTP/1.1\r\nHost: a\r\n",
+        Err(Error::MissingHeaderTerminator)
+    )]
+    #[case(b"GET /test HTTP/1.1\r\nHost: a", Err(Error::MissingHeaderTerminator))]
+    #[case(b"GET /test HTTP/1.1\r\n\r", Err(Error::MissingHeaderTerminator))]
+    fn test_route_unterminated(
+        #[case] input: &[u8],
+        #[case] expected: Result<(Option<TestRoute>, &[u8]), Error>,
+    ) {
+        let result =
+            TestRoute::resolve_unterminated(input).map(|result| (result.route, result.raw_body));
+        assert_eq!(result, expected);
+        if input.ends_with(b"HTTP/1.1\r\n") || input.ends_with(b"HTTP/1.0\r\n") {
+            assert_eq!(
+                TestRoute::resolve(input),
+                Err(Error::MissingHeaderTerminator)
+            );
+        }
+    }
+
+    #[rstest]
+    #[case(b"GET /test HTTP/1.1\r\n\r\n", Some(TestRoute::Test), "")]
+    #[case(b"GET /test? HTTP/1.1\r\n\r\n", Some(TestRoute::Test), "")]
+    #[case(
+        b"GET /test?a=1?b=2 HTTP/1.1\r\n\r\n",
+        Some(TestRoute::Test),
+        "a=1?b=2"
+    )]
+    #[case(b"GET /test?? HTTP/1.1\r\n\r\n", Some(TestRoute::Test), "?")]
+    #[case(b"GET /test%3Fa=1 HTTP/1.1\r\n\r\n", None, "")]
+    #[case(b"GET /test#frag HTTP/1.1\r\n\r\n", None, "")]
+    #[case(
+        b"GET /test?a=1#frag HTTP/1.1\r\n\r\n",
+        Some(TestRoute::Test),
+        "a=1#frag"
+    )]
+    #[case(b"GET /test?#?a HTTP/1.1\r\n\r\n", Some(TestRoute::Test), "#?a")]
+    fn test_route_query_delimiters(
+        #[case] input: &[u8],
+        #[case] expected_route: Option<TestRoute>,
+        #[case] expected_query: &str,
+    ) {
+        let result = TestRoute::resolve(input).unwrap();
+        assert_eq!(result.route, expected_route);
+        assert_eq!(result.query, expected_query);
+    }
+
+    #[rstest]
+    #[case(Method::Get, "/test", Some(TestRoute::Test))]
+    #[case(Method::Head, "/test?query=value", Some(TestRoute::Test))]
+    #[case(Method::Get, "/test?", Some(TestRoute::Test))]
+    #[case(Method::Post, "/test", None)]
+    #[case(Method::Get, "/test/", None)]
+    #[case(Method::Get, "", None)]
+    fn test_resolve_parts(
+        #[case] method: Method,
+        #[case] path: &str,
+        #[case] expected: Option<TestRoute>,
+    ) {
+        assert_eq!(TestRoute::resolve_parts(method, path), expected);
+    }
+
+    #[rstest]
+    #[case(b"GET  /test HTTP/1.1\r\n\r\n", Error::UnsupportedVersion)]
+    #[case(b"GET /test  HTTP/1.1\r\n\r\n", Error::UnsupportedVersion)]
+    #[case(b" GET /test HTTP/1.1\r\n\r\n", Error::InvalidMethod)]
+    #[case(b"GET /test HTTP/1.1 \r\n\r\n", Error::UnsupportedVersion)]
+    fn test_route_strict_rejects_extra_spaces(#[case] input: &[u8], #[case] expected: Error) {
+        assert_eq!(TestRoute::resolve(input), Err(expected));
+    }
+
+    #[rstest]
+    #[case(
+        b"PROPFIND /test?depth=1 HTTP/1.0\r\nHost: a\r\n\r\nbody",
+        Ok(AnyMethodResult::Unknown(UnknownMethodRequest {
+            method: "PROPFIND",
+            authority: None,
+            query: "depth=1",
+            version: HttpVersion::OneZero,
+            raw_headers: b"Host: a\r\n",
+            raw_body: b"body",
+        }))
+    )]
+    #[case(
+        b"M-SEARCH http://host/test HTTP/1.1\r\n\r\n",
+        Ok(AnyMethodResult::Unknown(UnknownMethodRequest {
+            method: "M-SEARCH",
+            authority: Some("host"),
+            query: "",
+            version: HttpVersion::OneOne,
+            raw_headers: b"",
+            raw_body: b"",
+        }))
+    )]
+    #[case(
+        b"POST /test HTTP/1.0\r\n\r\n",
+        Ok(AnyMethodResult::Known(RouterResult {
+            route: None,
+            pattern: None,
+            method: Method::Post,
+            authority: None,
+            target_form: TargetForm::Origin,
+            raw_path: b"/test",
+            query: "",
+            version: HttpVersion::OneZero,
+            raw_headers: b"",
+            raw_body: b"",
+            lenient: false,
+        }))
+    )]
+    #[case(
+        b"GET /missing?q=1 HTTP/1.0\r\n\r\n",
+        Ok(AnyMethodResult::Known(RouterResult {
+            route: None,
+            pattern: None,
+            method: Method::Get,
+            authority: None,
+            target_form: TargetForm::Origin,
+            raw_path: b"/missing",
+            query: "q=1",
+            version: HttpVersion::OneZero,
+            raw_headers: b"",
+            raw_body: b"",
+            lenient: false,
+        }))
+    )]
+    #[case(b"PROPFIND /test HTTP/1.1", Err(Error::MissingHeaderTerminator))]
+    #[case(b"PROPFIND /test HTTP/2.0\r\n\r\n", Err(Error::UnsupportedVersion))]
+    #[case(b"PROPFIND /test\r\n\r\n", Err(Error::MalformedRequestTarget))]
+    #[case(b"PROPFIND * HTTP/1.1\r\n\r\n", Err(Error::MalformedRequestTarget))]
+    #[case(b"PROPFIND /test HTTP/1.1\r\n", Err(Error::MissingHeaderTerminator))]
+    #[case(b"PROP(FIND) /test HTTP/1.1\r\n\r\n", Err(Error::InvalidMethod))]
+    #[case(b" /test HTTP/1.1\r\n\r\n", Err(Error::InvalidMethod))]
+    #[case(b"PROPFIND\r\n\r\n", Err(Error::InvalidMethod))]
+    fn test_route_any_method(
+        #[case] input: &[u8],
+        #[case] expected: Result<AnyMethodResult<'_, TestRoute>, Error>,
+    ) {
+        assert_eq!(TestRoute::resolve_any_method(input), expected);
+    }
+
+    #[rstest]
+    #[case(b"GET /test HTTP/1.1", Error::MissingHeaderTerminator)]
+    #[case(b"GET /test HTTP/1.1\nHost: a\n", Error::MissingHeaderTerminator)]
+    #[case(b"GET /test HTT/1.1\n\n", Error::UnsupportedVersion)]
+    #[case(b"   \n\n", Error::InvalidMethod)]
+    #[case(b"FETCH  /test HTTP/1.1\n\n", Error::InvalidMethod)]
+    #[case(b"GET  \n\n", Error::MalformedRequestTarget)]
+    #[case(b"GET   /test  \n\n", Error::MalformedRequestTarget)]
+    fn test_route_lenient_error(#[case] input: &[u8], #[case] expected: Error) {
+        assert_eq!(TestRoute::resolve_lenient(input), Err(expected));
+    }
+
+    #[rstest]
+    #[case(b"GET /test HTTP/1.1\r\n\r\n", Some(TestRoute::Test), None, "")]
+    #[case(
+        b"GET http://host/test HTTP/1.1\r\n\r\n",
+        Some(TestRoute::Test),
+        Some("host"),
+        ""
+    )]
+    #[case(
+        b"GET https://host:8080/test?q HTTP/1.1\r\n\r\n",
+        Some(TestRoute::Test),
+        Some("host:8080"),
+        "q"
+    )]
+    #[case(b"GET http://host HTTP/1.1\r\n\r\n", None, Some("host"), "")]
+    #[case(b"GET http://host?q HTTP/1.1\r\n\r\n", None, Some("host"), "q")]
+    #[case(b"GET 1http://host/test HTTP/1.1\r\n\r\n", None, None, "")]
+    #[case(b"GET test HTTP/1.1\r\n\r\n", None, None, "")]
+    fn test_route_absolute_form(
+        #[case] input: &[u8],
+        #[case] expected_route: Option<TestRoute>,
+        #[case] expected_authority: Option<&str>,
+        #[case] expected_query: &str,
+    ) {
+        let result = TestRoute::resolve(input).unwrap();
+        assert_eq!(result.route, expected_route);
+        assert_eq!(result.authority, expected_authority);
+        assert_eq!(result.query, expected_query);
+    }
+
+    #[test]
+    fn test_route_asterisk_form() {
+        let result = TestRoute::resolve(b"OPTIONS * HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(result.route, None);
+        assert_eq!(result.version, HttpVersion::OneOne);
+    }
+
+    #[rstest]
+    #[case(b"GET * HTTP/1.1\r\n\r\n")]
+    #[case(b"POST * HTTP/1.1\r\n\r\n")]
+    #[case(b"GET http:///test HTTP/1.1\r\n\r\n")]
+    #[case(b"GET http://\xff/test HTTP/1.1\r\n\r\n")]
+    fn test_route_absolute_form_error(#[case] input: &[u8]) {
+        assert_eq!(
+            TestRoute::resolve(input),
+            Err(Error::MalformedRequestTarget)
+        );
+    }
+
+    #[rstest]
+    #[case(
+        b"GET /test HTTP/1.1\r\n\r\n",
+        Some(TestRoute::Test),
+        RouteStatus::Matched
+    )]
+    #[case(
+        b"POST /test HTTP/1.1\r\n\r\n",
+        None,
+        RouteStatus::MethodNotAllowed(vec![Method::Get, Method::Head])
+    )]
+    #[case(b"GET /invalid HTTP/1.1\r\n\r\n", None, RouteStatus::NotFound)]
+    fn test_route_with_status(
+        #[case] input: &[u8],
+        #[case] expected_route: Option<TestRoute>,
+        #[case] expected_status: RouteStatus,
+    ) {
+        let (result, status) = TestRoute::resolve_with_status(input).unwrap();
+        assert_eq!(result.route, expected_route);
+        assert_eq!(status, expected_status);
+    }
+
+    #[test]
+    fn test_route_with_status_error() {
+        assert_eq!(
+            TestRoute::resolve_with_status(b"GET /test HTT/1.1\r\n\r\n"),
+            Err(Error::UnsupportedVersion)
+        );
+    }
+
+    #[rstest]
+    #[case("/test", (Some(TestRoute::Test), ""))]
+    #[case("/test/more", (None, "/test/more"))]
+    fn test_handle_parsed_partial(#[case] path: &str, #[case] expected: (Option<TestRoute>, &str)) {
+        assert_eq!(
+            TestRoute::handle_parsed_partial(Method::Get, path, ""),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(b"/test", Some((TestRoute::Test, None)))]
+    #[case(b"/invalid", None)]
+    #[case(b"/t\xffst", None)]
+    fn test_handle_parsed_bytes(
+        #[case] path: &[u8],
+        #[case] expected: Option<(TestRoute, Option<usize>)>,
+    ) {
+        assert_eq!(
+            TestRoute::handle_parsed_bytes(Method::Get, path, ""),
+            expected
+        );
+    }
 }
@@ -1,9 +1,14 @@
-use crate::HttpVersion;
+use crate::host::split_port;
+#[cfg(feature = "std")]
+use crate::percent_encoding::form_decode;
+use crate::{Error, HttpVersion, Method, TargetForm};
+#[cfg(feature = "std")]
+use std::borrow::Cow;
 
 /// Represents the result of routing an HTTP request.
 ///
 /// This structure captures essential parts of an HTTP request like the route, query parameters,
-/// the HTTP version, and the remaining head and body sections.
+/// the HTTP version, and the header and body sections.
 ///
 /// The generic type `T` allows flexibility in how routes are represented. It could be a simple
 /// enum, a string, or any other type that best captures the essence of routes for a specific
@@ -15,6 +20,39 @@ pub struct RouterResult<'a, T> {
     /// This could be `None` if no matching route was found.
     pub route: Option<T>,
 
+    /// The declared pattern of the matched route, e.g. `/api/users/{id}`.
+    ///
+    /// Generated routers report the pattern with any prefixes, including those of flattened
+    /// sub-routes, applied. This is `None` when no route matched or the router doesn't declare
+    /// patterns.
+    pub pattern: Option<&'static str>,
+
+    /// The method of the HTTP request.
+    ///
+    /// This is the method the client sent, which isn't always the method the route was declared
+    /// for, e.g. a `HEAD` request answered by a `GET` route with `#[head_implies_get]`.
+    pub method: Method,
+
+    /// The authority of an absolute-form request target, e.g. `example.com:8080` for
+    /// `GET http://example.com:8080/users HTTP/1.1`, as sent to proxies.
+    ///
+    /// This is `None` for the usual origin-form targets, like `/users`.
+    pub authority: Option<&'a str>,
+
+    /// The form of the request target, e.g. `TargetForm::Absolute` for
+    /// `GET http://example.com/users HTTP/1.1`.
+    ///
+    /// This lets a proxy tell the forms apart, or a server reject an authority-form target that
+    /// isn't sent with `CONNECT`.
+    pub target_form: TargetForm,
+
+    /// The raw path of the request target, before any percent-decoding and without the query,
+    /// e.g. `/users/john%20doe` for `GET /users/john%20doe?page=2 HTTP/1.1`.
+    ///
+    /// This is the path routes were matched against, which may not be valid UTF-8 when the
+    /// request was resolved with `Router::resolve_non_utf8`.
+    pub raw_path: &'a [u8],
+
     /// The query string from the HTTP request.
     ///
     /// Represents the part after the `?` in the URL.
@@ -23,6 +61,970 @@ pub struct RouterResult<'a, T> {
     /// The version of the HTTP protocol used in the request.
     pub version: HttpVersion,
 
-    /// The remaining parts of the HTTP request, typically the headers and the body.
-    pub head_and_body: &'a [u8],
+    /// The raw header block of the HTTP request, which `headers()` iterates over.
+    ///
+    /// Every header line keeps its terminating `\r\n`, but the blank line that ends the block is
+    /// excluded. This is empty when the request has no headers.
+    pub raw_headers: &'a [u8],
+
+    /// The raw body of the HTTP request, everything after the blank line that ends the headers.
+    ///
+    /// Unlike `body()`, this isn't trimmed to the `Content-Length`, so it may run into a pipelined
+    /// request that follows.
+    pub raw_body: &'a [u8],
+
+    /// Whether the request was parsed by `Router::resolve_lenient`.
+    ///
+    /// Only then may its header lines end with a bare `\n`, or fold a value across several
+    /// lines, which `headers()` otherwise doesn't accept.
+    pub lenient: bool,
+}
+
+impl<'a, T> RouterResult<'a, T> {
+    /// Iterate over the headers in the `raw_headers` block.
+    ///
+    /// Each header is yielded as a `(name, value)` pair borrowed from the request, with surrounding
+    /// whitespace trimmed from the value. Lines end with `\r\n`, and iteration stops at a blank
+    /// line, if there is one. Lines that aren't UTF-8 or that have no colon are skipped, and so are
+    /// lines whose name is empty or contains whitespace, like `Host : example.com` or a line
+    /// continuing a folded value, which only a lenient result unfolds.
+    ///
+    /// When the result is `lenient`, lines may also end with a bare `\n`, and values folded
+    /// across several lines are yielded as a single value that still contains the line breaks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use front_line_router::{HttpVersion, Method, RouterResult, TargetForm};
+    ///
+    /// let result: RouterResult<()> = RouterResult {
+    ///     route: None,
+    ///     pattern: None,
+    ///     method: Method::Get,
+    ///     authority: None,
+    ///     target_form: TargetForm::Origin,
+    ///     raw_path: b"/",
+    ///     query: "",
+    ///     version: HttpVersion::OneOne,
+    ///     raw_headers: b"Host: example.com\r\nContent-Length: 2\r\n",
+    ///     raw_body: b"hi",
+    ///     lenient: false,
+    /// };
+    /// let headers: Vec<_> = result.headers().collect();
+    /// assert_eq!(headers, [("Host", "example.com"), ("Content-Length", "2")]);
+    /// ```
+    pub fn headers(&self) -> Headers<'a> {
+        Headers::new(self.raw_headers, self.lenient)
+    }
+
+    /// The host the request was sent to, e.g. `api.example.com:8080`.
+    ///
+    /// This is the authority of an absolute-form request target, without any user info, or else
+    /// the value of the `Host` header, matched case-insensitively. The port is kept as sent.
+    /// Returns `None` when neither is present, the `Host` header is empty, or the request has
+    /// more than one `Host` header or a malformed one, which `Router::resolve_with_host` rejects.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use front_line_router::{HttpVersion, Method, RouterResult, TargetForm};
+    ///
+    /// let result: RouterResult<()> = RouterResult {
+    ///     route: None,
+    ///     pattern: None,
+    ///     method: Method::Get,
+    ///     authority: None,
+    ///     target_form: TargetForm::Origin,
+    ///     raw_path: b"/",
+    ///     query: "",
+    ///     version: HttpVersion::OneOne,
+    ///     raw_headers: b"Accept: */*\r\nhost: api.example.com\r\n",
+    ///     raw_body: b"",
+    ///     lenient: false,
+    /// };
+    /// assert_eq!(result.host(), Some("api.example.com"));
+    /// ```
+    pub fn host(&self) -> Option<&'a str> {
+        request_host(self.authority, self.headers()).ok().flatten()
+    }
+
+    /// The name of the host the request was sent to, without its port, e.g. `api.example.com`
+    /// for `api.example.com:8080`.
+    ///
+    /// An IPv6 literal keeps its brackets, along with any zone ID inside them, so `[::1]:8080`
+    /// has the name `[::1]`. Returns `None` when `host` does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use front_line_router::{HttpVersion, Method, RouterResult, TargetForm};
+    ///
+    /// let result: RouterResult<()> = RouterResult {
+    ///     route: None,
+    ///     pattern: None,
+    ///     method: Method::Get,
+    ///     authority: None,
+    ///     target_form: TargetForm::Origin,
+    ///     raw_path: b"/",
+    ///     query: "",
+    ///     version: HttpVersion::OneOne,
+    ///     raw_headers: b"Host: [::1]:8080\r\n",
+    ///     raw_body: b"",
+    ///     lenient: false,
+    /// };
+    /// assert_eq!(result.host_name(), Some("[::1]"));
+    /// assert_eq!(result.port(), Some(8080));
+    /// ```
+    pub fn host_name(&self) -> Option<&'a str> {
+        self.host().map(|host| split_port(host).0)
+    }
+
+    /// The port of the host the request was sent to, e.g. `8443` for `example.com:8443`.
+    ///
+    /// Returns `None` when the host has no port, or its port isn't a number from 0 to 65535.
+    pub fn port(&self) -> Option<u16> {
+        let (_, port) = split_port(self.host()?);
+        port.filter(|port| port.bytes().all(|byte| byte.is_ascii_digit()))?
+            .parse()
+            .ok()
+    }
+
+    /// Iterate over the decoded key/value pairs of the `query` string.
+    ///
+    /// The query is split into pairs on `&` and each pair into its key and value on the first
+    /// `=`, as in an `application/x-www-form-urlencoded` form. Keys and values have `+` decoded to
+    /// a space and `%XX` escapes decoded, and are only allocated when they contain either. A key
+    /// without an `=` has an empty value, empty pairs, e.g. from a trailing `&`, are skipped, and
+    /// repeated keys are yielded once per occurrence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use front_line_router::{HttpVersion, Method, RouterResult, TargetForm};
+    ///
+    /// let result: RouterResult<()> = RouterResult {
+    ///     route: None,
+    ///     pattern: None,
+    ///     method: Method::Get,
+    ///     authority: None,
+    ///     target_form: TargetForm::Origin,
+    ///     raw_path: b"/",
+    ///     query: "q=rust+router&tag=a%26b&debug",
+    ///     version: HttpVersion::OneOne,
+    ///     raw_headers: b"",
+    ///     raw_body: b"",
+    ///     lenient: false,
+    /// };
+    /// let mut pairs = result.query_pairs();
+    /// assert_eq!(pairs.next(), Some(("q".into(), "rust router".into())));
+    /// assert_eq!(pairs.next(), Some(("tag".into(), "a&b".into())));
+    /// assert_eq!(pairs.next(), Some(("debug".into(), "".into())));
+    /// assert_eq!(pairs.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn query_pairs(&self) -> QueryPairs<'a> {
+        QueryPairs {
+            remaining: self.query,
+        }
+    }
+
+    /// Iterate over the decoded key/value pairs of an `application/x-www-form-urlencoded` body.
+    ///
+    /// The pairs are decoded like those of `query_pairs`, from the body delimited by its
+    /// `Content-Length`, see `body()`. Returns `None` unless the first `Content-Type` header is
+    /// `application/x-www-form-urlencoded`, with or without parameters like a `charset`, or
+    /// when the body is chunked or isn't UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use front_line_router::{HttpVersion, Method, RouterResult, TargetForm};
+    ///
+    /// let result: RouterResult<()> = RouterResult {
+    ///     route: None,
+    ///     pattern: None,
+    ///     method: Method::Post,
+    ///     authority: None,
+    ///     target_form: TargetForm::Origin,
+    ///     raw_path: b"/",
+    ///     query: "",
+    ///     version: HttpVersion::OneOne,
+    ///     raw_headers: b"Content-Type: application/x-www-form-urlencoded\r\nContent-Length: 17\r\n",
+    ///     raw_body: b"name=Ada+L&age=36",
+    ///     lenient: false,
+    /// };
+    /// let pairs: Vec<_> = result.form_pairs().unwrap().collect();
+    /// assert_eq!(pairs, [("name".into(), "Ada L".into()), ("age".into(), "36".into())]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn form_pairs(&self) -> Option<QueryPairs<'a>> {
+        let (_, content_type) = self
+            .headers()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))?;
+        let media_type = content_type.split(';').next().unwrap_or_default().trim();
+        if !media_type.eq_ignore_ascii_case("application/x-www-form-urlencoded")
+            || self.is_chunked()
+        {
+            return None;
+        }
+        let body = core::str::from_utf8(self.body()?).ok()?;
+        Some(QueryPairs { remaining: body })
+    }
+
+    /// The value of the `Content-Length` header, if present and valid.
+    ///
+    /// Header names are matched case-insensitively, and the first `Content-Length` header wins.
+    pub fn content_length(&self) -> Option<usize> {
+        let (_, value) = self
+            .headers()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))?;
+        value.parse().ok()
+    }
+
+    /// Whether the request declares a chunked `Transfer-Encoding`.
+    pub fn is_chunked(&self) -> bool {
+        self.headers()
+            .filter(|(name, _)| name.eq_ignore_ascii_case("transfer-encoding"))
+            .flat_map(|(_, value)| value.split(','))
+            .any(|coding| coding.trim().eq_ignore_ascii_case("chunked"))
+    }
+
+    /// The body of the request, delimited by its headers.
+    ///
+    /// With a `Content-Length` this is exactly that many bytes, or the bytes available so far if
+    /// fewer have been read, e.g. when only the first packet of a request has arrived. A chunked
+    /// request's body is returned as is, still chunk encoded, since its length is encoded in the
+    /// chunks themselves. Returns `None` when the request is neither chunked nor has a valid
+    /// `Content-Length`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use front_line_router::{HttpVersion, Method, RouterResult, TargetForm};
+    ///
+    /// let result: RouterResult<()> = RouterResult {
+    ///     route: None,
+    ///     pattern: None,
+    ///     method: Method::Get,
+    ///     authority: None,
+    ///     target_form: TargetForm::Origin,
+    ///     raw_path: b"/",
+    ///     query: "",
+    ///     version: HttpVersion::OneOne,
+    ///     raw_headers: b"Content-Length: 5\r\n",
+    ///     raw_body: b"helloGET / HTTP/1.1\r\n\r\n",
+    ///     lenient: false,
+    /// };
+    /// assert_eq!(result.body(), Some(&b"hello"[..]));
+    /// ```
+    pub fn body(&self) -> Option<&'a [u8]> {
+        if self.is_chunked() {
+            return Some(self.raw_body);
+        }
+        let length = self.content_length()?;
+        Some(&self.raw_body[..length.min(self.raw_body.len())])
+    }
+
+    /// Converts the route with `f`, keeping the rest of the result.
+    ///
+    /// This is mostly useful to turn a route that borrows from the request into an owned one
+    /// before calling `into_owned`.
+    pub fn map_route<U>(self, f: impl FnOnce(T) -> U) -> RouterResult<'a, U> {
+        RouterResult {
+            route: self.route.map(f),
+            pattern: self.pattern,
+            method: self.method,
+            authority: self.authority,
+            target_form: self.target_form,
+            raw_path: self.raw_path,
+            query: self.query,
+            version: self.version,
+            raw_headers: self.raw_headers,
+            raw_body: self.raw_body,
+            lenient: self.lenient,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: 'static> RouterResult<'_, T> {
+    /// Copies the parts borrowed from the request into an `OwnedRouterResult`, so the result can
+    /// outlive the buffer the request was read into, e.g. to hand it to another thread.
+    ///
+    /// The route must already be owned. A route that borrows from the request can be converted
+    /// first with `map_route`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use front_line_router::{HttpVersion, Method, OwnedRouterResult, RouterResult, TargetForm};
+    ///
+    /// let owned: OwnedRouterResult<String> = {
+    ///     let buffer = b"page=2".to_vec();
+    ///     let result = RouterResult {
+    ///         route: Some(std::str::from_utf8(&buffer[..4]).unwrap()),
+    ///         pattern: Some("/{name}"),
+    ///         method: Method::Get,
+    ///         authority: None,
+    ///         target_form: TargetForm::Origin,
+    ///         raw_path: b"/",
+    ///         query: std::str::from_utf8(&buffer).unwrap(),
+    ///         version: HttpVersion::OneOne,
+    ///         raw_headers: b"",
+    ///         raw_body: b"",
+    ///         lenient: false,
+    ///     };
+    ///     result.map_route(String::from).into_owned()
+    /// };
+    /// assert_eq!(owned.route.as_deref(), Some("page"));
+    /// assert_eq!(owned.query, "page=2");
+    /// ```
+    pub fn into_owned(self) -> OwnedRouterResult<T> {
+        OwnedRouterResult {
+            route: self.route,
+            pattern: self.pattern,
+            method: self.method,
+            authority: self.authority.map(String::from),
+            target_form: self.target_form,
+            raw_path: self.raw_path.to_vec(),
+            query: self.query.to_string(),
+            version: self.version,
+            raw_headers: self.raw_headers.to_vec(),
+            raw_body: self.raw_body.to_vec(),
+            lenient: self.lenient,
+        }
+    }
+}
+
+/// A `RouterResult` that owns the parts it borrowed from the request, created by
+/// `RouterResult::into_owned`.
+#[cfg(feature = "std")]
+#[derive(PartialEq, Debug, Clone)]
+pub struct OwnedRouterResult<T> {
+    /// The identified route from the HTTP request, see `RouterResult::route`.
+    pub route: Option<T>,
+
+    /// The declared pattern of the matched route, see `RouterResult::pattern`.
+    pub pattern: Option<&'static str>,
+
+    /// The method of the HTTP request.
+    pub method: Method,
+
+    /// The authority of an absolute-form request target, see `RouterResult::authority`.
+    pub authority: Option<String>,
+
+    /// The form of the request target, see `RouterResult::target_form`.
+    pub target_form: TargetForm,
+
+    /// The raw path of the request target, see `RouterResult::raw_path`.
+    pub raw_path: Vec<u8>,
+
+    /// The query string from the HTTP request.
+    pub query: String,
+
+    /// The version of the HTTP protocol used in the request.
+    pub version: HttpVersion,
+
+    /// The raw header block of the HTTP request, see `RouterResult::raw_headers`.
+    pub raw_headers: Vec<u8>,
+
+    /// The raw body of the HTTP request, see `RouterResult::raw_body`.
+    pub raw_body: Vec<u8>,
+
+    /// Whether the request was parsed by `Router::resolve_lenient`, see `RouterResult::lenient`.
+    pub lenient: bool,
+}
+
+#[cfg(feature = "std")]
+impl<T> OwnedRouterResult<T> {
+    /// Borrows the result as a `RouterResult`, to use its helpers like `headers()`,
+    /// `query_pairs()` or `body()`.
+    pub fn as_router_result(&self) -> RouterResult<'_, &T> {
+        RouterResult {
+            route: self.route.as_ref(),
+            pattern: self.pattern,
+            method: self.method,
+            authority: self.authority.as_deref(),
+            target_form: self.target_form,
+            raw_path: &self.raw_path,
+            query: &self.query,
+            version: self.version,
+            raw_headers: &self.raw_headers,
+            raw_body: &self.raw_body,
+            lenient: self.lenient,
+        }
+    }
+}
+
+/// An iterator over the headers of a request, created by `RouterResult::headers`.
+#[derive(Clone, Debug)]
+pub struct Headers<'a> {
+    remaining: &'a [u8],
+    lenient: bool,
+}
+
+impl<'a> Headers<'a> {
+    pub(crate) fn new(block: &'a [u8], lenient: bool) -> Self {
+        Self {
+            remaining: block,
+            lenient,
+        }
+    }
+
+    /// The length of the first line of `bytes`, and of the line ending after it, which is
+    /// `\r\n`, or also a bare `\n` when lenient. A last line without one has no line ending.
+    fn split_line(&self, bytes: &[u8]) -> (usize, usize) {
+        if !self.lenient {
+            return match memchr::memmem::find(bytes, b"\r\n") {
+                Some(end) => (end, 2),
+                None => (bytes.len(), 0),
+            };
+        }
+        match memchr::memchr(b'\n', bytes) {
+            Some(end) if end > 0 && bytes[end - 1] == b'\r' => (end - 1, 2),
+            Some(end) => (end, 1),
+            None => (bytes.len(), 0),
+        }
+    }
+}
+
+impl<'a> Headers<'a> {
+    /// The next line of the block as sent, without its line ending or unfolding it into the line
+    /// before, or `None` once the blank line that ends the block is reached.
+    fn next_line(&mut self) -> Option<&'a [u8]> {
+        let (len, ending) = self.split_line(self.remaining);
+        if len == 0 {
+            self.remaining = &[];
+            return None;
+        }
+        let line = &self.remaining[..len];
+        self.remaining = &self.remaining[len + ending..];
+        Some(line)
+    }
+}
+
+impl<'a> Iterator for Headers<'a> {
+    type Item = (&'a str, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.remaining.is_empty() {
+                return None;
+            }
+            let (mut len, mut ending) = self.split_line(self.remaining);
+            if len == 0 {
+                self.remaining = &[];
+                return None;
+            }
+            // obsolete line folding, where a line starting with whitespace continues the value
+            while self.lenient
+                && ending > 0
+                && matches!(self.remaining.get(len + ending), Some(b' ' | b'\t'))
+            {
+                let (next_len, next_ending) = self.split_line(&self.remaining[len + ending..]);
+                len += ending + next_len;
+                ending = next_ending;
+            }
+            let line = &self.remaining[..len];
+            self.remaining = &self.remaining[len + ending..];
+            let header = core::str::from_utf8(line)
+                .ok()
+                .and_then(|line| line.split_once(':'));
+            match header {
+                // the name is a token, so whitespace around it is malformed rather than padding
+                Some((name, _)) if name.is_empty() || name.contains(char::is_whitespace) => {}
+                Some((name, value)) => return Some((name, value.trim())),
+                None => {}
+            }
+        }
+    }
+}
+
+/// An iterator over the decoded query pairs of a request, created by `RouterResult::query_pairs`.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct QueryPairs<'a> {
+    remaining: &'a str,
+}
+
+#[cfg(feature = "std")]
+impl<'a> Iterator for QueryPairs<'a> {
+    type Item = (Cow<'a, str>, Cow<'a, str>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.remaining.is_empty() {
+                return None;
+            }
+            let (pair, remaining) = self
+                .remaining
+                .split_once('&')
+                .unwrap_or((self.remaining, ""));
+            self.remaining = remaining;
+            if pair.is_empty() {
+                continue;
+            }
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            return Some((form_decode(key), form_decode(value)));
+        }
+    }
+}
+
+/// The host a request was sent to, from the `authority` of its request target or else the `Host`
+/// header in its header block.
+///
+/// The `Host` header is parsed strictly even when the authority is used, since a proxy in front
+/// may read an ambiguous header block differently: more than one `Host` header, or one that is
+/// malformed, like `Host : example.com`, a `Host` line folded into the header before it or a
+/// value with whitespace inside, fails with `Error::InvalidHost`.
+pub(crate) fn request_host<'a>(
+    authority: Option<&'a str>,
+    mut headers: Headers<'a>,
+) -> Result<Option<&'a str>, Error> {
+    let mut host = None;
+    while let Some(line) = headers.next_line() {
+        let Some(colon) = memchr::memchr(b':', line) else {
+            continue;
+        };
+        let (name, value) = (&line[..colon], &line[colon + 1..]);
+        if !name.trim_ascii().eq_ignore_ascii_case(b"host") {
+            continue;
+        }
+        let value = core::str::from_utf8(value)
+            .map_err(|_| Error::InvalidHost)?
+            .trim_matches([' ', '\t']);
+        let malformed = !name.eq_ignore_ascii_case(b"host")
+            || value
+                .bytes()
+                .any(|byte| byte.is_ascii_whitespace() || byte.is_ascii_control());
+        if malformed || host.is_some() {
+            return Err(Error::InvalidHost);
+        }
+        host = Some(value);
+    }
+    Ok(match authority {
+        Some(authority) => Some(
+            authority
+                .rsplit_once('@')
+                .map_or(authority, |(_, host)| host),
+        ),
+        None => host.filter(|value| !value.is_empty()),
+    })
+}
+
+/// The result of `Router::resolve_any_method`, which also parses requests whose method `Method`
+/// doesn't model.
+#[derive(PartialEq, Debug)]
+pub enum AnyMethodResult<'a, T> {
+    /// The request's method is one `Method` models, and it was routed as usual.
+    Known(RouterResult<'a, T>),
+
+    /// The request's method isn't one `Method` models, so no route could match. A server would
+    /// usually respond with `501 Not Implemented`.
+    Unknown(UnknownMethodRequest<'a>),
+}
+
+/// A request whose method isn't one `Method` models, e.g. `PROPFIND`.
+///
+/// This holds the same parts of the request as `RouterResult`, but with the method as it was
+/// sent instead of a route.
+#[derive(PartialEq, Debug)]
+pub struct UnknownMethodRequest<'a> {
+    /// The method token of the HTTP request.
+    pub method: &'a str,
+
+    /// The authority of an absolute-form request target, see `RouterResult::authority`.
+    pub authority: Option<&'a str>,
+
+    /// The query string from the HTTP request.
+    pub query: &'a str,
+
+    /// The version of the HTTP protocol used in the request.
+    pub version: HttpVersion,
+
+    /// The raw header block of the HTTP request, see `RouterResult::raw_headers`.
+    pub raw_headers: &'a [u8],
+
+    /// The raw body of the HTTP request, see `RouterResult::raw_body`.
+    pub raw_body: &'a [u8],
+}
+
+/// The result of `Router::resolve_partial`, which tells a request that hasn't been fully read yet
+/// apart from a malformed one.
+#[derive(PartialEq, Debug)]
+pub enum PartialResult<'a, T> {
+    /// The request line and headers were terminated, and the request was routed as usual.
+    Complete(RouterResult<'a, T>),
+
+    /// The headers aren't terminated yet, but nothing read so far rules out a valid request, so
+    /// a server should read more bytes and try again.
+    Incomplete,
+
+    /// The request is malformed, so reading more bytes won't help.
+    Invalid(Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(b"", false, &[])]
+    #[case(b"\r\n", false, &[])]
+    #[case(b"Host: example.com\r\n\r\n", false, &[("Host", "example.com")])]
+    #[case(b"Host: example.com", false, &[("Host", "example.com")])]
+    #[case(
+        b"Host: example.com\r\nAccept: */*\r\n\r\nbody",
+        false,
+        &[("Host", "example.com"), ("Accept", "*/*")]
+    )]
+    #[case(b"Host:example.com\r\n\r\n", false, &[("Host", "example.com")])]
+    #[case(b"Host: \t example.com \t\r\n\r\n", false, &[("Host", "example.com")])]
+    #[case(b"X-Empty:\r\nX-Blank:   \r\n\r\n", false, &[("X-Empty", ""), ("X-Blank", "")])]
+    #[case(
+        b"X-Folded: first\r\n  second\r\n\tthird\r\nHost: example.com\r\n\r\n",
+        false,
+        &[("X-Folded", "first"), ("Host", "example.com")]
+    )]
+    #[case(
+        b"X-Folded: first\r\n  second\r\n\tthird\r\nHost: example.com\r\n\r\n",
+        true,
+        &[("X-Folded", "first\r\n  second\r\n\tthird"), ("Host", "example.com")]
+    )]
+    #[case(
+        b"X-Note: hi\r\n Host: admin.example.com\r\n\r\n",
+        false,
+        &[("X-Note", "hi")]
+    )]
+    #[case(b" Host: x\r\nAccept: */*\r\n\r\n", false, &[("Accept", "*/*")])]
+    #[case(b"Host : x\r\nAccept: */*\r\n\r\n", false, &[("Accept", "*/*")])]
+    #[case(b"Host : x\r\n\r\n", true, &[])]
+    #[case(b": x\r\nAccept: */*\r\n\r\n", false, &[("Accept", "*/*")])]
+    #[case(b"X-Url: http://example.com\r\n\r\n", false, &[("X-Url", "http://example.com")])]
+    #[case(b"no colon\r\nHost: example.com\r\n\r\n", false, &[("Host", "example.com")])]
+    #[case(b"X-Bad: \xff\r\nHost: example.com\r\n\r\n", false, &[("Host", "example.com")])]
+    #[case(
+        b"Host: example.com\r\n\r\nX-Body: ignored\r\n",
+        false,
+        &[("Host", "example.com")]
+    )]
+    #[case(
+        b"Host: example.com\nX-Injected: 1\r\nAccept: */*\r\n\r\n",
+        false,
+        &[("Host", "example.com\nX-Injected: 1"), ("Accept", "*/*")]
+    )]
+    #[case(
+        b"Host: example.com\nAccept: */*\n\nX-Body: ignored\n",
+        true,
+        &[("Host", "example.com"), ("Accept", "*/*")]
+    )]
+    #[case(b"X-Folded: first\n second\n\n", true, &[("X-Folded", "first\n second")])]
+    #[case(
+        b"Host: example.com\r\nAccept: */*\n\r\n",
+        true,
+        &[("Host", "example.com"), ("Accept", "*/*")]
+    )]
+    fn test_headers(
+        #[case] headers: &[u8],
+        #[case] lenient: bool,
+        #[case] expected: &[(&str, &str)],
+    ) {
+        let result: RouterResult<()> = RouterResult {
+            route: None,
+            pattern: None,
+            method: Method::Get,
+            authority: None,
+            target_form: TargetForm::Origin,
+            raw_path: b"/",
+            query: "",
+            version: HttpVersion::OneOne,
+            raw_headers: headers,
+            raw_body: b"",
+            lenient,
+        };
+        let headers: Vec<_> = result.headers().collect();
+        assert_eq!(headers, expected);
+    }
+
+    #[rstest]
+    #[case("", &[])]
+    #[case("q=rust", &[("q", "rust")])]
+    #[case("q=rust+router&page=2", &[("q", "rust router"), ("page", "2")])]
+    #[case("tag=a%26b&name=caf%C3%A9", &[("tag", "a&b"), ("name", "café")])]
+    #[case("a%3Db=c%3Dd=e", &[("a=b", "c=d=e")])]
+    #[case("debug&q=", &[("debug", ""), ("q", "")])]
+    #[case("tag=a&tag=b", &[("tag", "a"), ("tag", "b")])]
+    #[case("q=rust&", &[("q", "rust")])]
+    #[case("&&q=rust&&", &[("q", "rust")])]
+    #[case("=value", &[("", "value")])]
+    #[case("q=100%", &[("q", "100%")])]
+    fn test_query_pairs(#[case] query: &str, #[case] expected: &[(&str, &str)]) {
+        let result: RouterResult<()> = RouterResult {
+            route: None,
+            pattern: None,
+            method: Method::Get,
+            authority: None,
+            target_form: TargetForm::Origin,
+            raw_path: b"/",
+            query,
+            version: HttpVersion::OneOne,
+            raw_headers: b"",
+            raw_body: b"",
+            lenient: false,
+        };
+        let pairs: Vec<_> = result.query_pairs().collect();
+        let pairs: Vec<_> = pairs
+            .iter()
+            .map(|(key, value)| (key.as_ref(), value.as_ref()))
+            .collect();
+        assert_eq!(pairs, expected);
+    }
+
+    #[test]
+    fn test_query_pairs_borrow_without_escapes() {
+        let result: RouterResult<()> = RouterResult {
+            route: None,
+            pattern: None,
+            method: Method::Get,
+            authority: None,
+            target_form: TargetForm::Origin,
+            raw_path: b"/",
+            query: "q=rust&page=2",
+            version: HttpVersion::OneOne,
+            raw_headers: b"",
+            raw_body: b"",
+            lenient: false,
+        };
+        assert!(result
+            .query_pairs()
+            .all(|(key, value)| matches!((key, value), (Cow::Borrowed(_), Cow::Borrowed(_)))));
+    }
+
+    #[rstest]
+    #[case(b"", None)]
+    #[case(b"Content-Length: 5\r\n", Some(5))]
+    #[case(b"content-length:0\r\n", Some(0))]
+    #[case(b"Host: example.com\r\nCONTENT-LENGTH: 12\r\n", Some(12))]
+    #[case(b"Content-Length: 5\r\nContent-Length: 7\r\n", Some(5))]
+    #[case(b"Content-Length: five\r\n", None)]
+    #[case(b"Content-Length: -5\r\n", None)]
+    #[case(b"Content-Length:\r\n", None)]
+    fn test_content_length(#[case] headers: &[u8], #[case] expected: Option<usize>) {
+        let result: RouterResult<()> = RouterResult {
+            route: None,
+            pattern: None,
+            method: Method::Get,
+            authority: None,
+            target_form: TargetForm::Origin,
+            raw_path: b"/",
+            query: "",
+            version: HttpVersion::OneOne,
+            raw_headers: headers,
+            raw_body: b"",
+            lenient: false,
+        };
+        assert_eq!(result.content_length(), expected);
+    }
+
+    #[test]
+    fn test_into_owned() {
+        let request = b"Host: example.com\r\nContent-Length: 2\r\nhi".to_vec();
+        let owned = {
+            let result = RouterResult {
+                route: Some(core::str::from_utf8(&request[6..17]).unwrap()),
+                pattern: Some("/{name}"),
+                method: Method::Post,
+                authority: Some(core::str::from_utf8(&request[6..17]).unwrap()),
+                target_form: TargetForm::Absolute,
+                raw_path: b"/",
+                query: core::str::from_utf8(&request[..4]).unwrap(),
+                version: HttpVersion::OneZero,
+                raw_headers: &request[..38],
+                raw_body: &request[38..],
+                lenient: false,
+            };
+            result.map_route(String::from).into_owned()
+        };
+        drop(request);
+        assert_eq!(owned.route.as_deref(), Some("example.com"));
+        assert_eq!(owned.authority.as_deref(), Some("example.com"));
+        assert_eq!(owned.query, "Host");
+        assert_eq!(owned.raw_body, b"hi");
+        let borrowed = owned.as_router_result();
+        assert_eq!(borrowed.route, Some(&String::from("example.com")));
+        assert_eq!(borrowed.pattern, Some("/{name}"));
+        assert_eq!(borrowed.method, Method::Post);
+        assert_eq!(borrowed.version, HttpVersion::OneZero);
+        assert_eq!(borrowed.content_length(), Some(2));
+        assert_eq!(borrowed.body(), Some(&b"hi"[..]));
+    }
+
+    #[rstest]
+    #[case(
+        b"Content-Type: application/x-www-form-urlencoded\r\nContent-Length: 11\r\n",
+        b"a=1&b=x+y&c",
+        Some(vec![("a", "1"), ("b", "x y"), ("c", "")])
+    )]
+    #[case(
+        b"content-type: Application/X-WWW-Form-Urlencoded; charset=utf-8\r\ncontent-length: 3\r\n",
+        b"a=1&b=2",
+        Some(vec![("a", "1")])
+    )]
+    #[case(
+        b"Content-Type: application/x-www-form-urlencoded\r\nContent-Length: 6\r\n",
+        b"a=1&b=2",
+        Some(vec![("a", "1"), ("b", "")])
+    )]
+    #[case(
+        b"Content-Type: application/x-www-form-urlencoded\r\nContent-Length: 3\r\n",
+        b"a=1\xFF\xFF",
+        Some(vec![("a", "1")])
+    )]
+    #[case(
+        b"Content-Type: application/x-www-form-urlencoded\r\nContent-Length: 0\r\n",
+        b"",
+        Some(vec![])
+    )]
+    #[case(
+        b"Content-Type: application/json\r\nContent-Length: 3\r\n",
+        b"a=1",
+        None
+    )]
+    #[case(b"Content-Length: 3\r\n", b"a=1", None)]
+    #[case(b"Content-Type: application/x-www-form-urlencoded\r\n", b"a=1", None)]
+    #[case(
+        b"Content-Type: application/x-www-form-urlencoded\r\nTransfer-Encoding: chunked\r\n",
+        b"3\r\na=1\r\n0\r\n\r\n",
+        None
+    )]
+    #[case(
+        b"Content-Type: application/x-www-form-urlencoded\r\nContent-Length: 3\r\n",
+        b"a=\xFF",
+        None
+    )]
+    fn test_form_pairs(
+        #[case] headers: &[u8],
+        #[case] body: &[u8],
+        #[case] expected: Option<Vec<(&str, &str)>>,
+    ) {
+        let result: RouterResult<()> = RouterResult {
+            route: None,
+            pattern: None,
+            method: Method::Post,
+            authority: None,
+            target_form: TargetForm::Origin,
+            raw_path: b"/",
+            query: "",
+            version: HttpVersion::OneOne,
+            raw_headers: headers,
+            raw_body: body,
+            lenient: false,
+        };
+        let pairs = result.form_pairs().map(|pairs| pairs.collect::<Vec<_>>());
+        let expected = expected.map(|expected| {
+            expected
+                .into_iter()
+                .map(|(key, value)| (Cow::from(key), Cow::from(value)))
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(pairs, expected);
+    }
+
+    #[rstest]
+    #[case(None, b"", None)]
+    #[case(None, b"Host: example.com\r\n", Some("example.com"))]
+    #[case(None, b"HOST:example.com:8080\r\n", Some("example.com:8080"))]
+    #[case(None, b"Accept: */*\r\nHost: a.com\r\nHost: b.com\r\n", None)]
+    #[case(None, b"X-Note: hi\r\n Host: a.com\r\n", None)]
+    #[case(None, b"Host : a.com\r\n", None)]
+    #[case(None, b"Host: a.com b.com\r\n", None)]
+    #[case(Some("proxy.com"), b"Host: a.com\r\nHost: b.com\r\n", None)]
+    #[case(None, b"Host:\r\n", None)]
+    #[case(None, b"X-Host: example.com\r\n", None)]
+    #[case(Some("proxy.com"), b"Host: example.com\r\n", Some("proxy.com"))]
+    #[case(Some("user@proxy.com:81"), b"", Some("proxy.com:81"))]
+    fn test_host(
+        #[case] authority: Option<&str>,
+        #[case] headers: &[u8],
+        #[case] expected: Option<&str>,
+    ) {
+        let result: RouterResult<()> = RouterResult {
+            route: None,
+            pattern: None,
+            method: Method::Get,
+            authority,
+            target_form: TargetForm::Origin,
+            raw_path: b"/",
+            query: "",
+            version: HttpVersion::OneOne,
+            raw_headers: headers,
+            raw_body: b"",
+            lenient: false,
+        };
+        assert_eq!(result.host(), expected);
+    }
+
+    #[rstest]
+    #[case(b"Host: example.com\r\n", Some("example.com"), None)]
+    #[case(b"Host: example.com:8443\r\n", Some("example.com"), Some(8443))]
+    #[case(b"Host: [::1]\r\n", Some("[::1]"), None)]
+    #[case(b"Host: [::1]:8080\r\n", Some("[::1]"), Some(8080))]
+    #[case(b"Host: [fe80::1%25eth0]:443\r\n", Some("[fe80::1%25eth0]"), Some(443))]
+    #[case(b"Host: example.com:\r\n", Some("example.com"), None)]
+    #[case(b"Host: example.com:+80\r\n", Some("example.com"), None)]
+    #[case(b"Host: example.com:65536\r\n", Some("example.com"), None)]
+    #[case(b"", None, None)]
+    fn test_host_name_and_port(
+        #[case] headers: &[u8],
+        #[case] expected_name: Option<&str>,
+        #[case] expected_port: Option<u16>,
+    ) {
+        let result: RouterResult<()> = RouterResult {
+            route: None,
+            pattern: None,
+            method: Method::Get,
+            authority: None,
+            target_form: TargetForm::Origin,
+            raw_path: b"/",
+            query: "",
+            version: HttpVersion::OneOne,
+            raw_headers: headers,
+            raw_body: b"",
+            lenient: false,
+        };
+        assert_eq!(result.host_name(), expected_name);
+        assert_eq!(result.port(), expected_port);
+    }
+
+    #[rstest]
+    #[case(b"", b"hello", None)]
+    #[case(b"Content-Length: 5\r\n", b"hello", Some(&b"hello"[..]))]
+    #[case(b"Content-Length: 5\r\n", b"hello world", Some(&b"hello"[..]))]
+    #[case(b"Content-Length: 11\r\n", b"hello", Some(&b"hello"[..]))]
+    #[case(b"Content-Length: 0\r\n", b"hello", Some(&b""[..]))]
+    #[case(b"Content-Length: nope\r\n", b"hello", None)]
+    #[case(b"Transfer-Encoding: chunked\r\n", b"5\r\nhello", Some(&b"5\r\nhello"[..]))]
+    #[case(
+        b"Transfer-Encoding: gzip, Chunked\r\nContent-Length: 1\r\n",
+        b"5\r\nhello",
+        Some(&b"5\r\nhello"[..])
+    )]
+    #[case(b"Transfer-Encoding: gzip\r\n", b"hello", None)]
+    fn test_body(#[case] headers: &[u8], #[case] body: &[u8], #[case] expected: Option<&[u8]>) {
+        let result: RouterResult<()> = RouterResult {
+            route: None,
+            pattern: None,
+            method: Method::Get,
+            authority: None,
+            target_form: TargetForm::Origin,
+            raw_path: b"/",
+            query: "",
+            version: HttpVersion::OneOne,
+            raw_headers: headers,
+            raw_body: body,
+            lenient: false,
+        };
+        assert_eq!(result.body(), expected);
+    }
 }
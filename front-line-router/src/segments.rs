@@ -0,0 +1,119 @@
+/// Splits a path into its `/` delimited segments, for hand written `Router::handle_parsed`
+/// implementations.
+///
+/// The leading `/` is skipped and a single trailing `/` is ignored, so `/users/42/` yields
+/// `users` and `42`, while the root path `/` yields nothing. Empty segments between two slashes
+/// are kept, so `/users//42` yields `users`, an empty segment and `42`. Compare the path against
+/// its trimmed form first to treat trailing slashes strictly.
+///
+/// # Examples
+///
+/// ```
+/// use front_line_router::{segments, Method, Router};
+///
+/// enum Routes<'a> {
+///     GetUser(u32),
+///     GetFile(&'a str),
+/// }
+///
+/// impl<'de> Router<'de> for Routes<'de> {
+///     fn handle_parsed(method: Method, path: &'de str) -> Option<Self> {
+///         let mut segments = segments(path);
+///         match (method, segments.next()?) {
+///             (Method::Get, "users") => {
+///                 let id = segments.next()?.parse().ok()?;
+///                 segments.next().is_none().then_some(Routes::GetUser(id))
+///             }
+///             (Method::Get, "files") => Some(Routes::GetFile(segments.as_str())),
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// let route = Routes::resolve(b"GET /users/42 HTTP/1.1\r\n\r\n").unwrap().route;
+/// assert!(matches!(route, Some(Routes::GetUser(42))));
+/// let route = Routes::resolve(b"GET /files/css/app.css HTTP/1.1\r\n\r\n").unwrap().route;
+/// assert!(matches!(route, Some(Routes::GetFile("css/app.css"))));
+/// ```
+pub fn segments(path: &str) -> Segments<'_> {
+    let path = path.strip_prefix('/').unwrap_or(path);
+    Segments {
+        remaining: path.strip_suffix('/').unwrap_or(path),
+        done: path.is_empty(),
+    }
+}
+
+/// An iterator over the segments of a path, created by `segments`.
+#[derive(Clone, Debug)]
+pub struct Segments<'a> {
+    remaining: &'a str,
+    done: bool,
+}
+
+impl<'a> Segments<'a> {
+    /// The segments that haven't been yielded yet, joined by their slashes, e.g. to capture the
+    /// rest of the path like a `{*name}` catch-all.
+    pub fn as_str(&self) -> &'a str {
+        match self.done {
+            true => "",
+            false => self.remaining,
+        }
+    }
+}
+
+impl<'a> Iterator for Segments<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match memchr::memchr(b'/', self.remaining.as_bytes()) {
+            Some(end) => {
+                let segment = &self.remaining[..end];
+                self.remaining = &self.remaining[end + 1..];
+                Some(segment)
+            }
+            None => {
+                self.done = true;
+                Some(self.remaining)
+            }
+        }
+    }
+}
+
+impl core::iter::FusedIterator for Segments<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("/", vec![])]
+    #[case("", vec![])]
+    #[case("/users", vec!["users"])]
+    #[case("/users/", vec!["users"])]
+    #[case("/users/42", vec!["users", "42"])]
+    #[case("/users/42/", vec!["users", "42"])]
+    #[case("/users//42", vec!["users", "", "42"])]
+    #[case("//", vec![""])]
+    #[case("/users//", vec!["users", ""])]
+    #[case("users/42", vec!["users", "42"])]
+    #[case("/caf%C3%A9/ü", vec!["caf%C3%A9", "ü"])]
+    fn test_segments(#[case] path: &str, #[case] expected: Vec<&str>) {
+        assert_eq!(segments(path).collect::<Vec<_>>(), expected);
+    }
+
+    #[rstest]
+    #[case("/files/css/app.css", 1, "css/app.css")]
+    #[case("/files/css/app.css/", 1, "css/app.css")]
+    #[case("/files", 1, "")]
+    #[case("/files", 0, "files")]
+    #[case("/", 0, "")]
+    fn test_segments_as_str(#[case] path: &str, #[case] skip: usize, #[case] expected: &str) {
+        let mut segments = segments(path);
+        segments.by_ref().take(skip).for_each(drop);
+        assert_eq!(segments.as_str(), expected);
+    }
+}
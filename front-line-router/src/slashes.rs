@@ -0,0 +1,74 @@
+//! Runtime helpers used by `front_line::FrontLine` to match paths with consecutive slashes merged,
+//! per `#[merge_slashes]`.
+//!
+//! Rather than copying the path into a buffer with the slashes merged, each `/` of a declared
+//! literal matches a whole run of slashes in the path, so captures still borrow from the request.
+
+/// The number of bytes of `path` that `literal` spans when each `/` in `literal` matches a run of
+/// consecutive slashes, or `None` when `path` doesn't start with `literal`.
+///
+/// A run of slashes is consumed whole, so after a literal ending in `/`, like `/users/`, `path`
+/// never starts with another slash.
+pub fn literal_len(path: &[u8], literal: &[u8], case_insensitive: bool) -> Option<usize> {
+    let mut offset = 0;
+    for expected in literal {
+        let byte = path.get(offset)?;
+        let matches = if case_insensitive {
+            byte.eq_ignore_ascii_case(expected)
+        } else {
+            byte == expected
+        };
+        if !matches {
+            return None;
+        }
+        offset += 1;
+        if *expected == b'/' {
+            while path.get(offset) == Some(&b'/') {
+                offset += 1;
+            }
+        }
+    }
+    Some(offset)
+}
+
+/// Whether `rest`, what's left of a path once a route has matched, is a run of slashes that
+/// merges into a single trailing slash, or is empty.
+pub fn is_trailing_slash(rest: &[u8]) -> bool {
+    rest.iter().all(|byte| *byte == b'/')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(b"/users", b"/users", false, Some(6))]
+    #[case(b"//users", b"/users", false, Some(7))]
+    #[case(b"/users//42", b"/users/", false, Some(8))]
+    #[case(b"/users/42", b"/users/", false, Some(7))]
+    #[case(b"/api///v1//users", b"/api/v1/users", false, Some(16))]
+    #[case(b"/USERS", b"/users", false, None)]
+    #[case(b"/USERS", b"/users", true, Some(6))]
+    #[case(b"/user", b"/users", false, None)]
+    #[case(b"users", b"/users", false, None)]
+    #[case(b"/users", b"", false, Some(0))]
+    fn test_literal_len(
+        #[case] path: &[u8],
+        #[case] literal: &[u8],
+        #[case] case_insensitive: bool,
+        #[case] expected: Option<usize>,
+    ) {
+        assert_eq!(literal_len(path, literal, case_insensitive), expected);
+    }
+
+    #[rstest]
+    #[case(b"", true)]
+    #[case(b"/", true)]
+    #[case(b"///", true)]
+    #[case(b"/a", false)]
+    #[case(b"a/", false)]
+    fn test_is_trailing_slash(#[case] rest: &[u8], #[case] expected: bool) {
+        assert_eq!(is_trailing_slash(rest), expected);
+    }
+}
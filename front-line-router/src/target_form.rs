@@ -0,0 +1,68 @@
+/// The form of a request target, as defined by RFC 7230, section 5.3.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum TargetForm {
+    /// An absolute path with an optional query, like `/users?page=2`, as sent to origin servers.
+    Origin,
+
+    /// An absolute URI, like `http://example.com/users`, as sent to proxies.
+    Absolute,
+
+    /// The authority of the server to connect to, like `example.com:443`, as sent with `CONNECT`.
+    ///
+    /// Any non-empty target that isn't one of the other forms is classified as authority-form,
+    /// without checking that it's a valid `host:port`.
+    Authority,
+
+    /// The `*` of a server-wide `OPTIONS * HTTP/1.1` request.
+    Asterisk,
+
+    /// A target without a path, like the empty target of `GET  HTTP/1.1` or the query-only
+    /// target of `HEAD ?page=2 HTTP/1.0`, which isn't any of the forms RFC 7230 allows.
+    Empty,
+}
+
+impl TargetForm {
+    /// Classify a request target from its parts, as split by the request line parser.
+    ///
+    /// # Arguments
+    ///
+    /// * `authority` - The authority of an absolute-form target, if it has one.
+    /// * `path` - The path of the target, without its query.
+    pub(crate) fn classify(authority: Option<&str>, path: &[u8]) -> Self {
+        match (authority, path) {
+            (Some(_), _) => TargetForm::Absolute,
+            (None, [b'/', ..]) => TargetForm::Origin,
+            (None, b"*") => TargetForm::Asterisk,
+            (None, []) => TargetForm::Empty,
+            (None, _) => TargetForm::Authority,
+        }
+    }
+
+    /// Whether requests with a target of this form are matched against routes. Authority-form and
+    /// empty targets have no path to match, so they never match a route, not even a fallback.
+    pub(crate) fn is_routable(self) -> bool {
+        !matches!(self, TargetForm::Authority | TargetForm::Empty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TargetForm;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(None, b"/", TargetForm::Origin)]
+    #[case(None, b"/users", TargetForm::Origin)]
+    #[case(Some("example.com"), b"/users", TargetForm::Absolute)]
+    #[case(None, b"*", TargetForm::Asterisk)]
+    #[case(None, b"/*", TargetForm::Origin)]
+    #[case(None, b"example.com:443", TargetForm::Authority)]
+    #[case(None, b"", TargetForm::Empty)]
+    fn test_classify(
+        #[case] authority: Option<&str>,
+        #[case] path: &[u8],
+        #[case] expected: TargetForm,
+    ) {
+        assert_eq!(TargetForm::classify(authority, path), expected);
+    }
+}
@@ -0,0 +1,26 @@
+/// A trait to reconstruct the concrete path of a route, the inverse of routing.
+///
+/// This is typically implemented with the `front_line::ToPath` derive, which substitutes each
+/// captured field back into the route's declared path, including the enum's prefix and any
+/// flattened sub-routes.
+///
+/// # Examples
+///
+/// ```
+/// use front_line_router::ToPath;
+///
+/// struct UserRoute(u32);
+///
+/// impl ToPath for UserRoute {
+///     fn to_path(&self) -> String {
+///         format!("/users/{}", self.0)
+///     }
+/// }
+///
+/// assert_eq!(UserRoute(42).to_path(), "/users/42");
+/// ```
+pub trait ToPath {
+    /// Builds the path, and query string if the route declares query parameters, that resolves
+    /// to this route.
+    fn to_path(&self) -> String;
+}
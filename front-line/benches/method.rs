@@ -0,0 +1,106 @@
+//! Compares `Method::parse`, which matches the first word of the request line at once, with the
+//! byte-slice comparisons it replaced, on an unpredictable mix of methods so branch prediction
+//! doesn't flatter either parser.
+//!
+//! Run with `cargo bench -p front-line --bench method`.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use front_line::Method;
+use std::hint::black_box;
+
+/// The byte-slice comparisons `Method::parse` used before it matched whole words, kept as a
+/// baseline.
+fn parse_method_by_comparison(request_line: &[u8]) -> Option<(Method, &[u8])> {
+    if request_line.len() < 14 {
+        return None;
+    }
+    if &request_line[..4] == b"GET " {
+        return Some((Method::Get, &request_line[4..]));
+    }
+    if &request_line[..4] == b"PUT " {
+        return Some((Method::Put, &request_line[4..]));
+    }
+    if request_line.len() < 15 {
+        return None;
+    }
+    if &request_line[..5] == b"POST " {
+        return Some((Method::Post, &request_line[5..]));
+    }
+    if &request_line[..5] == b"HEAD " {
+        return Some((Method::Head, &request_line[5..]));
+    }
+    if request_line.len() < 16 {
+        return None;
+    }
+    if &request_line[..6] == b"TRACE " {
+        return Some((Method::Trace, &request_line[6..]));
+    }
+    if &request_line[..6] == b"PATCH " {
+        return Some((Method::Patch, &request_line[6..]));
+    }
+    if request_line.len() < 17 {
+        return None;
+    }
+    if &request_line[..7] == b"DELETE " {
+        return Some((Method::Delete, &request_line[7..]));
+    }
+    if request_line.len() < 18 {
+        return None;
+    }
+    if &request_line[..8] == b"OPTIONS " {
+        return Some((Method::Options, &request_line[8..]));
+    }
+    if &request_line[..8] == b"CONNECT " {
+        return Some((Method::Connect, &request_line[8..]));
+    }
+    None
+}
+
+/// A pseudo-random mix of request lines for every method, plus an unknown one.
+fn request_lines() -> Vec<Vec<u8>> {
+    let tokens: Vec<_> = Method::ALL
+        .iter()
+        .map(|method| method.as_str())
+        .chain(["PURGE"])
+        .collect();
+    let mut seed = 0x2545_f491_u32;
+    (0..1024)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            let token = tokens[seed as usize % tokens.len()];
+            format!("{token} /api/v1/users HTTP/1.1").into_bytes()
+        })
+        .collect()
+}
+
+fn method(c: &mut Criterion) {
+    let request_lines = request_lines();
+    for request_line in request_lines.iter() {
+        assert_eq!(
+            parse_method_by_comparison(request_line),
+            Method::parse(request_line)
+        );
+    }
+    let mut group = c.benchmark_group("method");
+    group.throughput(Throughput::Elements(request_lines.len() as u64));
+    group.bench_function("words", |b| {
+        b.iter(|| {
+            for request_line in request_lines.iter() {
+                black_box(Method::parse(black_box(request_line)));
+            }
+        })
+    });
+    group.bench_function("slices", |b| {
+        b.iter(|| {
+            for request_line in request_lines.iter() {
+                black_box(parse_method_by_comparison(black_box(request_line)));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, method);
+criterion_main!(benches);
@@ -0,0 +1,139 @@
+//! Measures `resolve` throughput, request parsing included, on a representative router that mixes
+//! static routes, captures and flattened sub-routers nested three levels deep. Each case resolves
+//! a complete request, so the numbers cover both the request line parser and the generated
+//! matcher, and criterion reports them per request as well as in requests per second. The long
+//! path cases resolve a 3.5KB catch-all path and a numeric capture, to cover how much of the path
+//! has to be validated. The capture benches compare parsing an integer capture straight from its
+//! digits, as `FromRoute::parse_path_bytes` does, with validating it as UTF-8 and then parsing
+//! the `str`.
+//!
+//! Run with `cargo bench -p front-line --bench resolve`.
+
+use criterion::measurement::WallTime;
+use criterion::{criterion_group, criterion_main, BenchmarkGroup, Criterion, Throughput};
+use front_line::{FromRoute, FrontLine, Router};
+use std::hint::black_box;
+
+#[derive(FrontLine)]
+#[prefix("/posts")]
+#[allow(dead_code)]
+enum PostRoutes<'a> {
+    #[get("/{post}")]
+    GetPost { post: u32 },
+    #[put("/{post}")]
+    UpdatePost { post: u32 },
+    #[get("/{post}/comments")]
+    GetComments { post: u32 },
+    #[get("/{post}/comments/{id}")]
+    GetComment { post: u32, id: u32 },
+    #[get("/{post}/comments/{id}/reactions/{reaction}")]
+    GetReaction {
+        post: u32,
+        id: u32,
+        reaction: &'a str,
+    },
+}
+
+#[derive(FrontLine)]
+#[prefix("/blog")]
+#[allow(dead_code)]
+enum BlogRoutes<'a> {
+    #[get("/")]
+    GetBlog,
+    #[get("/authors/{author}")]
+    GetAuthor { author: &'a str },
+    #[flatten]
+    Posts(PostRoutes<'a>),
+}
+
+#[derive(FrontLine)]
+#[allow(dead_code)]
+enum SiteRoutes<'a> {
+    #[get("/")]
+    GetIndex,
+    #[get("/about")]
+    GetAbout,
+    #[get("/health")]
+    GetHealth,
+    #[get("/users/{id}")]
+    GetUser { id: u32 },
+    #[post("/users")]
+    CreateUser,
+    #[get("/users/{id}/settings")]
+    GetUserSettings { id: u32 },
+    #[get("/search?{q}")]
+    Search { q: &'a str },
+    #[get("/static/{*path}")]
+    GetStatic { path: &'a str },
+    #[flatten]
+    Blog(BlogRoutes<'a>),
+}
+
+fn bench_resolve(
+    group: &mut BenchmarkGroup<WallTime>,
+    name: &str,
+    request: &[u8],
+    expect_match: bool,
+) {
+    let resolved = SiteRoutes::resolve(request).map(|result| result.route.is_some());
+    assert_eq!(resolved, Ok(expect_match), "{name}");
+    group.bench_function(name, |b| {
+        b.iter(|| SiteRoutes::resolve(black_box(request)).ok())
+    });
+}
+
+fn resolve(c: &mut Criterion) {
+    let mut group = c.benchmark_group("resolve");
+    group.throughput(Throughput::Elements(1));
+    bench_resolve(
+        &mut group,
+        "static hit",
+        b"GET /about HTTP/1.1\r\nHost: example.com\r\n\r\n",
+        true,
+    );
+    bench_resolve(
+        &mut group,
+        "single capture",
+        b"GET /users/1234 HTTP/1.1\r\nHost: example.com\r\n\r\n",
+        true,
+    );
+    bench_resolve(
+        &mut group,
+        "deep flattened",
+        b"GET /blog/posts/42/comments/7/reactions/heart HTTP/1.1\r\nHost: example.com\r\n\r\n",
+        true,
+    );
+    bench_resolve(
+        &mut group,
+        "not found",
+        b"GET /blog/drafts/42 HTTP/1.1\r\nHost: example.com\r\n\r\n",
+        false,
+    );
+    let long_path = "/static/".to_string() + &"assets/".repeat(512);
+    let long_request = format!("GET {long_path} HTTP/1.1\r\n\r\n");
+    bench_resolve(&mut group, "long catch-all", long_request.as_bytes(), true);
+    let long_id = format!("GET /users/{}/settings HTTP/1.1\r\n\r\n", "7".repeat(9));
+    bench_resolve(&mut group, "long capture", long_id.as_bytes(), true);
+    group.finish();
+}
+
+fn capture(c: &mut Criterion) {
+    let mut group = c.benchmark_group("capture");
+    for digits in ["7", "1234", "4294967295"] {
+        let capture = digits.as_bytes();
+        group.bench_function(format!("digits/{digits}"), |b| {
+            b.iter(|| u32::parse_path_bytes(black_box(capture)))
+        });
+        group.bench_function(format!("utf8 then str/{digits}"), |b| {
+            b.iter(|| {
+                core::str::from_utf8(black_box(capture))
+                    .ok()
+                    .and_then(|capture| capture.parse::<u32>().ok())
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, resolve, capture);
+criterion_main!(benches);
@@ -0,0 +1,157 @@
+//! Measures route matching on a router with many routes that share long literal prefixes, which is
+//! where the generated prefix tree pays off over trying every route in turn. Exact routes like
+//! `/api/v1/users` share a branch with the routes under them, so reaching `/api/v1/secrets`
+//! compares each resource's segment once rather than once per route. Request parsing is
+//! left out of the matcher benches so the numbers reflect the generated matcher alone, while
+//! `benches/resolve.rs` covers whole requests.
+//!
+//! Run with `cargo bench -p front-line --bench routing`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use front_line::{FrontLine, Method, Router};
+use std::hint::black_box;
+
+#[derive(FrontLine)]
+#[allow(dead_code)]
+enum ApiRoutes<'a> {
+    #[get("/api/v1/users")]
+    GetUsers,
+    #[get("/api/v1/users/{id}")]
+    GetUser { id: u32 },
+    #[get("/api/v1/users/{id}/history")]
+    GetUserHistory { id: u32 },
+    #[get("/api/v1/teams")]
+    GetTeams,
+    #[get("/api/v1/teams/{id}")]
+    GetTeam { id: u32 },
+    #[get("/api/v1/teams/{id}/history")]
+    GetTeamHistory { id: u32 },
+    #[get("/api/v1/projects")]
+    GetProjects,
+    #[get("/api/v1/projects/{id}")]
+    GetProject { id: u32 },
+    #[get("/api/v1/projects/{id}/history")]
+    GetProjectHistory { id: u32 },
+    #[get("/api/v1/issues")]
+    GetIssues,
+    #[get("/api/v1/issues/{id}")]
+    GetIssue { id: u32 },
+    #[get("/api/v1/issues/{id}/history")]
+    GetIssueHistory { id: u32 },
+    #[get("/api/v1/releases")]
+    GetReleases,
+    #[get("/api/v1/releases/{id}")]
+    GetRelease { id: u32 },
+    #[get("/api/v1/releases/{id}/history")]
+    GetReleaseHistory { id: u32 },
+    #[get("/api/v1/comments")]
+    GetComments,
+    #[get("/api/v1/comments/{id}")]
+    GetComment { id: u32 },
+    #[get("/api/v1/comments/{id}/history")]
+    GetCommentHistory { id: u32 },
+    #[get("/api/v1/labels")]
+    GetLabels,
+    #[get("/api/v1/labels/{id}")]
+    GetLabel { id: u32 },
+    #[get("/api/v1/labels/{id}/history")]
+    GetLabelHistory { id: u32 },
+    #[get("/api/v1/milestones")]
+    GetMilestones,
+    #[get("/api/v1/milestones/{id}")]
+    GetMilestone { id: u32 },
+    #[get("/api/v1/milestones/{id}/history")]
+    GetMilestoneHistory { id: u32 },
+    #[get("/api/v1/hooks")]
+    GetHooks,
+    #[get("/api/v1/hooks/{id}")]
+    GetHook { id: u32 },
+    #[get("/api/v1/hooks/{id}/history")]
+    GetHookHistory { id: u32 },
+    #[get("/api/v1/keys")]
+    GetKeys,
+    #[get("/api/v1/keys/{id}")]
+    GetKey { id: u32 },
+    #[get("/api/v1/keys/{id}/history")]
+    GetKeyHistory { id: u32 },
+    #[get("/api/v1/gists")]
+    GetGists,
+    #[get("/api/v1/gists/{id}")]
+    GetGist { id: u32 },
+    #[get("/api/v1/gists/{id}/history")]
+    GetGistHistory { id: u32 },
+    #[get("/api/v1/repos")]
+    GetRepos,
+    #[get("/api/v1/repos/{id}")]
+    GetRepo { id: u32 },
+    #[get("/api/v1/repos/{id}/history")]
+    GetRepoHistory { id: u32 },
+    #[get("/api/v1/stars")]
+    GetStars,
+    #[get("/api/v1/stars/{id}")]
+    GetStar { id: u32 },
+    #[get("/api/v1/stars/{id}/history")]
+    GetStarHistory { id: u32 },
+    #[get("/api/v1/events")]
+    GetEvents,
+    #[get("/api/v1/events/{id}")]
+    GetEvent { id: u32 },
+    #[get("/api/v1/events/{id}/history")]
+    GetEventHistory { id: u32 },
+    #[get("/api/v1/builds")]
+    GetBuilds,
+    #[get("/api/v1/builds/{id}")]
+    GetBuild { id: u32 },
+    #[get("/api/v1/builds/{id}/history")]
+    GetBuildHistory { id: u32 },
+    #[get("/api/v1/secrets")]
+    GetSecrets,
+    #[get("/api/v1/secrets/{id}")]
+    GetSecret { id: u32 },
+    #[get("/api/v1/secrets/{id}/history")]
+    GetSecretHistory { id: u32 },
+    #[get("/api/v2/users")]
+    GetUsersV2,
+    #[get("/api/v2/users/{id}")]
+    GetUserV2 { id: u32 },
+    #[get("/api/v2/teams")]
+    GetTeamsV2,
+    #[get("/api/v2/teams/{id}")]
+    GetTeamV2 { id: u32 },
+    #[get("/api/v2/search/{term}")]
+    SearchV2 { term: &'a str },
+    #[get("/admin/settings")]
+    GetSettings,
+    #[get("/admin/audit/{id}")]
+    GetAuditEntry { id: u32 },
+    #[get("/static/{*path}")]
+    GetStatic { path: &'a str },
+    #[get("/health")]
+    GetHealth,
+}
+
+fn routing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("routing");
+    let cases = [
+        ("first route", "/api/v1/users", true),
+        ("middle route", "/api/v1/keys/7/history", true),
+        ("exact route", "/api/v1/secrets", true),
+        ("late route", "/api/v2/search/front-line", true),
+        ("last route", "/health", true),
+        ("not found", "/api/v3/users", false),
+    ];
+    for (name, path, expect_match) in cases {
+        assert_eq!(
+            ApiRoutes::handle_parsed(Method::Get, path).is_some(),
+            expect_match,
+            "{name}"
+        );
+        group.bench_function(name, |b| {
+            b.iter(|| ApiRoutes::handle_parsed(Method::Get, black_box(path)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, routing);
+criterion_main!(benches);
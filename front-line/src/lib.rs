@@ -15,7 +15,7 @@
 //! ## Basic Usage:
 //!
 //! ```rust
-//! use front_line::{FrontLine, HttpVersion, RouterResult, Router};
+//! use front_line::{FrontLine, HttpVersion, Method, RouterResult, Router, TargetForm};
 //!
 //! #[derive(FrontLine)]
 //! enum MarketingRoutes {
@@ -57,7 +57,7 @@
 //! }
 //!
 //! // Construct an example http request, this would normally just be read off of a socket.
-//! let request = b"GET /api/users/42?a=b HTTP/1.1\r\n\r\nContent-Length: 12\r\n\r\nHello World!";
+//! let request = b"GET /api/users/42?a=b HTTP/1.1\r\nContent-Length: 12\r\n\r\nHello World!";
 //!
 //! // Parse and and resolve the route
 //! let route = AllRoutes::resolve(request);
@@ -65,13 +65,1016 @@
 //! // For demonstration purposes, assert the resolved route is what we expect
 //! assert!(matches!(route, Ok(RouterResult {
 //!   route: Some(AllRoutes::Api(ApiRoutes::GetUser { id: 42 })),
+//!   pattern: Some("/api/users/{id}"),
+//!   method: Method::Get,
+//!   authority: None,
+//!   target_form: TargetForm::Origin,
+//!   raw_path: b"/api/users/42",
 //!   query: "a=b",
 //!   version: HttpVersion::OneOne,
-//!   head_and_body: b"Content-Length: 12\r\n\r\nHello World!",
+//!   raw_headers: b"Content-Length: 12\r\n",
+//!   raw_body: b"Hello World!",
+//!   lenient: false,
 //! })));
 //!```
 //!
+//! ## Multiple Methods:
+//!
+//! A variant may carry several method attributes, e.g. `#[get("/resource")] #[head("/resource")]`,
+//! and resolves for any of them. `#[methods(get, head, "/resource")]` is shorthand for the same
+//! thing that avoids repeating the path. `#[any("/health")]` declares the path for every method,
+//! e.g. for health checks or proxies, so its path allows every method too.
+//!
+//! The paths don't have to match either, so a variant can also answer to an alias of its path
+//! with the same method, e.g. `#[get("/users/{id}")] #[get("/u/{id}")] GetUser { id: u32 }`. Each
+//! path must capture every field, `RouterResult::pattern` reports the path that matched, and
+//! `ToPath` renders the first path declared.
+//!
+//! ## HEAD Requests:
+//!
+//! `#[head_implies_get]` on the enum makes `HEAD` requests fall back to `GET` routes, including a
+//! `GET` fallback route, when no route matches them as `HEAD`. `RouterResult::method` still holds
+//! `Method::Head`, so the handler knows to leave out the body.
+//!
+//! ## Positional Captures:
+//!
+//! Tuple-style variants bind captures by position, left to right, so `#[get("/users/{id}")]` can
+//! be declared on `GetUser(u32)`. The number of fields must match the number of path variables.
+//!
+//! ## Struct Routes:
+//!
+//! A single endpoint doesn't need a one-variant enum: `FrontLine` and `ToPath` can also be derived
+//! for a unit, tuple or named field struct carrying the route attributes, like
+//! `#[get("/things/{id}")] struct GetThing { id: u32 }`. The struct can then be flattened into an
+//! enum like any other router.
+//!
+//! ## Generic Routes:
+//!
+//! Route enums may have type and const parameters, with bounds and where clauses, which carry
+//! over to the generated impls. A capture whose type mentions a type parameter, like
+//! `GetItem { id: T }`, is bound by `FromRoute` automatically, so the enum doesn't need to name
+//! the lifetime the router decodes with. Flattened types can't depend on a type parameter, since
+//! the `PATTERNS` and `ROUTES` tables they contribute to are computed at compile time.
+//!
+//! The enum only routes for parameters that parse, so a capture type without `FromRoute` fails to
+//! compile where the router is used:
+//!
+//!```compile_fail
+//! use front_line::{FrontLine, Router};
+//!
+//! #[derive(FrontLine)]
+//! enum ItemRoutes<T> {
+//!     #[get("/items/{id}")]
+//!     GetItem { id: T },
+//! }
+//!
+//! struct ItemId(u32);
+//!
+//! let route = ItemRoutes::<ItemId>::resolve(b"GET /items/42 HTTP/1.1\r\n\r\n");
+//!```
+//!
+//! And flattening a type that depends on a type parameter fails to compile on the variant:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum ItemRoutes<T> {
+//!     #[get("/items/{id}")]
+//!     GetItem { id: T },
+//! }
+//!
+//! #[derive(FrontLine)]
+//! enum AllRoutes<T> {
+//!     #[flatten]
+//!     Items(ItemRoutes<T>),
+//! }
+//!```
+//!
+//! ## Owned Sub-routers:
+//!
+//! A flattened or mounted router doesn't have to borrow from the request. An enum without a
+//! lifetime, e.g. one capturing `String`s, routes for any request lifetime, so it can sit next to
+//! borrowing routers under one `AllRoutes<'a>` and its routes can outlive the request buffer.
+//! `RouterResult::into_owned()` copies the rest of such a result, its query, headers and body,
+//! into an `OwnedRouterResult`, e.g. to hand it to another thread once the buffer is recycled.
+//! Routes that borrow can be converted to owned ones first with `RouterResult::map_route()`.
+//!
+//! A route that borrows from the request, on the other hand, can't outlive the buffer:
+//!
+//!```compile_fail
+//! use front_line::{FrontLine, Router};
+//!
+//! #[derive(FrontLine)]
+//! enum ThingRoutes<'a> {
+//!     #[get("/things/{name}")]
+//!     GetThing { name: &'a str },
+//! }
+//!
+//! let request = b"GET /things/42 HTTP/1.1\r\n\r\n".to_vec();
+//! let route = ThingRoutes::resolve(&request).unwrap().route;
+//! drop(request);
+//! assert!(route.is_some());
+//!```
+//!
+//! ## Multiple Prefixes:
+//!
+//! `#[prefix("/api", "/api/v1")]` mounts every route under each of the prefixes. They are tried
+//! most specific first, so `/api/v1/users` is matched with the `/api/v1` prefix stripped before
+//! falling back to `/api`. `ToPath` always uses the first declared prefix.
+//!
+//! ## Variant Prefixes:
+//!
+//! A variant may carry its own `#[prefix("/accounts")]`, which is prepended to each of its paths
+//! after the enum's prefix, so routes sharing a long path don't have to repeat it or be split into
+//! a flattened enum. Under `#[prefix("/api/v1")]`, `#[prefix("/accounts")] #[get("/{id}")]`
+//! matches `/api/v1/accounts/42`, and `#[get("")]` matches `/api/v1/accounts` itself. Unlike the
+//! enum's prefix, a variant's prefix is part of its patterns and may capture variables.
+//!
+//! ## Variant Groups:
+//!
+//! `#[group("/v2")]` on a variant prefixes it and every variant after it, until the next
+//! `#[group(..)]`, so a run of variants sharing a path fragment doesn't need a flattened enum.
+//! Groups stack between the enum's prefix and a variant's own `#[prefix]`, and mounted or
+//! flattened variants in a group are mounted under it. `#[group("")]` ends a group without
+//! starting another.
+//!
+//! ## Relative Paths:
+//!
+//! Paths, prefixes and mounts may leave out their leading `/`, and prefixes and mounts may end
+//! with one, as they're joined with a single `/` in between. Under `#[prefix("api/")]`,
+//! `#[get("users/{id}")]` and `#[get("/users/{id}")]` both match `/api/users/42`, but never
+//! `/api//users/42` unless slashes are merged.
+//!
+//! ## Prefix Roots:
+//!
+//! Under `#[prefix("/api")]`, a root route declared as `#[get("/")]` matches both `/api/` and
+//! `/api`, while other routes, like a `/{*path}` catch-all, still need the `/`. Routes under a
+//! prefix may also be declared as `#[get("")]`, which matches `/api` and, unless trailing slashes
+//! are strict, `/api/`, and which `ToPath` renders as `/api`. With `#[trailing_slash(strict)]`,
+//! `/api/` only matches `"/"` routes and `/api` only matches `""` routes.
+//!
+//! ## Mounted Routers:
+//!
+//! `#[mount("/admin")] Admin(AdminRoutes<'a>)` delegates every path under `/admin` to a sub-router
+//! with `/admin` stripped, so `AdminRoutes` declares its routes as `/` and `/users` without a
+//! prefix of its own. The mount path must be followed by a `/` or the end of the path, and the bare
+//! mount path is passed along as `/` unless trailing slashes are strict. Any `Router` can be
+//! mounted, including hand written ones implemented as `impl<'de: 'a, 'a> Router<'de>`, and the
+//! mount path is applied to their `PATTERNS`, `ROUTES` and `ToPath` output.
+//!
+//! A variant is either flattened or mounted, and its sub-router's routes can't take a variant
+//! `#[prefix]`, so combining them fails to compile:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum AdminRoutes {
+//!     #[get("/users")]
+//!     ListUsers,
+//! }
+//!
+//! #[derive(FrontLine)]
+//! enum AllRoutes {
+//!     #[flatten]
+//!     #[mount("/admin")]
+//!     Admin(AdminRoutes),
+//! }
+//!```
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum AdminRoutes {
+//!     #[get("/users")]
+//!     ListUsers,
+//! }
+//!
+//! #[derive(FrontLine)]
+//! enum AllRoutes {
+//!     #[mount("/admin")]
+//!     #[prefix("/v1")]
+//!     Admin(AdminRoutes),
+//! }
+//!```
+//!
+//! The mount path is stripped before delegating, so it can't capture variables either:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum AdminRoutes {
+//!     #[get("/users")]
+//!     ListUsers,
+//! }
+//!
+//! #[derive(FrontLine)]
+//! enum AllRoutes {
+//!     #[mount("/admin/{tenant}")]
+//!     Admin(AdminRoutes),
+//! }
+//!```
+//!
+//! Two sub-routers mounted at the same path, with the same `Router::PREFIX`, fail to compile with
+//! an error naming the mount path, since the first would shadow the routes of the second:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum AdminRoutes {
+//!     #[get("/users")]
+//!     ListUsers,
+//! }
+//!
+//! #[derive(FrontLine)]
+//! enum ReportRoutes {
+//!     #[get("/reports")]
+//!     ListReports,
+//! }
+//!
+//! #[derive(FrontLine)]
+//! enum AllRoutes {
+//!     #[mount("/admin")]
+//!     Admin(AdminRoutes),
+//!     #[mount("/admin")]
+//!     Reports(ReportRoutes),
+//! }
+//!```
+//!
+//! ## Prefix Variables:
+//!
+//! A prefix may capture variables, e.g. `#[prefix("/tenants/{tenant}")]`. The prefix is then part
+//! of every route's path, so every variant must have a `tenant` field alongside its own captures,
+//! and `PATTERNS` and `ROUTES` list paths like `/tenants/{tenant}/users`. A prefix with variables
+//! must be the only prefix, and can't be combined with `#[flatten]` or `#[mount]` variants. A
+//! variable name may appear only once in a route's path, prefix and query included, so a route
+//! can't reuse `tenant` for one of its own captures. Prefixes, variant prefixes and groups can't
+//! have optional variables or query parameters:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! #[prefix("/{tenant?}")]
+//! enum Routes<'a> {
+//!     #[get("/users")]
+//!     ListUsers { tenant: Option<&'a str> },
+//! }
+//!```
+//!
+//! ## Variable Constraints:
+//!
+//! A variable may restrict the bytes it captures with `{name:pattern}`, so that other variants get
+//! a chance to match. `\d+` (or `[0-9]+`) only matches digits and `[a-zA-Z0-9]+` only matches
+//! ASCII letters and digits, e.g. with `#[get(r"/files/{id:\d+}")]` and `#[get("/files/{name}")]`,
+//! `/files/latest` falls through to the `name` variant.
+//!
+//! ## Non-empty Captures:
+//!
+//! A `&str` capture accepts an empty segment, so `/users//roles/admin` binds `name` to `""` for
+//! `/users/{name}/roles/{role}`. Adding `#[non_empty]` to a field, or to a variant to apply it to
+//! every field, makes an empty capture skip the route instead, and adding it to the enum applies
+//! it to every capture of every route.
+//!
+//! ## Parse Errors:
+//!
+//! A capture that fails to parse normally skips its route. A field of type
+//! `Result<T, ParseError>` instead always matches and holds the error from
+//! `FromRoute::try_parse_path_variable`, e.g. `GetOrder { id: Result<u32, ParseError> }` matches
+//! `/orders/abc` with an `Err`, so the handler can respond with a 400 explaining what was expected.
+//! Override `try_parse_path_variable` to give a more helpful reason than the type name.
+//!
+//! ## Capture Types:
+//!
+//! Every captured field must implement `FromRoute`, or `FromRouteRest` for a `{**name}`
+//! catch-all, unless it has a `#[parse_with]` attribute. A field that doesn't fails to compile
+//! with an error on the field's type, naming the trait it's missing:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! struct UserId(u32);
+//!
+//! #[derive(FrontLine)]
+//! enum Routes {
+//!     #[get("/users/{id}")]
+//!     GetUser { id: UserId },
+//! }
+//!```
+//!
+//! ## Delimited Captures:
+//!
+//! A single segment can hold several comma separated values, captured as an array or a tuple of
+//! up to four elements, e.g. `coords: [f64; 3]` for `/points/1,2.5,-3` or `range: (u32, u32)` for
+//! `/ranges/10,20`. Each element is parsed with its own `FromRoute`, and the route is skipped when
+//! the number of elements doesn't match or any element fails to parse.
+//!
+//! ## Custom Parsers:
+//!
+//! A field marked `#[parse_with(path::to::fn)]` is parsed by calling that function, with the
+//! signature `fn(&str) -> Option<T>`, instead of `FromRoute::parse_path_variable`. This allows
+//! capturing foreign types, like `chrono::NaiveDate`, without a newtype. Returning `None` skips
+//! the route like any other capture that fails to parse, and `#[decode]` fields are decoded
+//! before they're passed to the function.
+//!
+//! ## Renamed Captures:
+//!
+//! A field marked `#[from("name")]` captures the path or query variable `{name}` instead of the
+//! variable named after the field, so `#[get("/users/{id}")]` can bind a `user_id` field. This
+//! also captures variables whose names are Rust keywords, like `?{type}`. Two fields of a variant
+//! can't capture the same variable.
+//!
+//! ## Method Fields:
+//!
+//! A field marked `#[method]` is bound to the request's `Method` instead of a path variable, so
+//! an `#[any("/proxy/{*path}")]` variant like `Proxy { #[method] method: Method, path: &'a str }`
+//! knows which method it was resolved for. A variant can have one such field, and `ToPath`
+//! ignores it.
+//!
+//! ## Mixed Segments:
+//!
+//! A variable may share its segment with literal text, e.g. `/files/{name}.json` or
+//! `/v{version}/users`. A literal after the variable bounds its capture to the first occurrence of
+//! that literal within the segment, so `/files/a.json` binds `name` to `a` while `/files/a.txt`
+//! doesn't match. Two variables must be separated by a literal, as in `/{year}-{month}`, or by a
+//! single delimiter byte such as the `;` of a matrix-style `/items/{id};{rev}`.
+//!
+//! ## Catch-all Captures:
+//!
+//! A variable written as `{*name}` captures the remainder of the path, slashes included, e.g.
+//! `#[get("/assets/{*path}")]` binds `path` to `css/app/main.css` for `/assets/css/app/main.css`.
+//! A catch-all must be the final part of the path.
+//!
+//! A catch-all written as `{**name}` parses its field with `FromRouteRest` instead of `FromRoute`,
+//! so a type can consume several segments at once, e.g. `values: Vec<u32>` from `1/2/3` for
+//! `#[get("/sums/{**values}")]`. `FromRouteRest` is implemented for `&str`, `String`, `Segments`
+//! and `Vec<T>` of any `FromRoute` type. Every path of a variant must capture the field that way.
+//!
+//! ## Literal Braces:
+//!
+//! Braces are escaped by doubling them, as in `format!`, so `#[get("/templates/{{name}}")]` matches
+//! a request for the raw path `/templates/{name}` instead of capturing a variable. Escaped braces may
+//! sit next to variables, e.g. `/{{{key}}}` captures `key` from `/{abc}`. `ToPath` renders the
+//! single braces, while `Router::PATTERNS` keeps the path as declared. Any other brace, like an
+//! empty `{}`, an unterminated `{id` or a lone `}`, fails to compile:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum Routes {
+//!     #[get("/templates/}")]
+//!     GetTemplate,
+//! }
+//!```
+//!
+//! ## Optional Segments:
+//!
+//! Trailing variables written as `{name?}` may be left out of the path, e.g.
+//! `#[get("/posts/{year}/{month?}/{day?}")]` matches `/posts/2024`, `/posts/2024/5` and
+//! `/posts/2024/5/17`. Their fields must be `Option`s, which are `None` when the segment is absent
+//! or empty, while a present segment has to parse as the wrapped type for the route to match.
+//! Optional variables must be whole segments at the end of the path, though a query may follow
+//! them, and `ToPath` renders segments up to the first `None`. OpenAPI lists each length of the
+//! path as its own route.
+//!
+//! ## Trailing Slashes:
+//!
+//! By default a route matches with or without a trailing slash, so `#[get("/users")]` matches both
+//! `/users` and `/users/`. Adding `#[trailing_slash(strict)]` to the enum requires the request path
+//! to match the declared path exactly, including the presence or absence of the trailing slash.
+//!
+//! ## Case Insensitivity:
+//!
+//! Adding `#[case_insensitive]` to the enum makes the prefix and literal path segments match
+//! regardless of ASCII case, so `/API/Users` resolves the same as `/api/users`. Captured variables
+//! are passed to `FromRoute` with their original casing.
+//!
+//! ## Duplicate Slashes:
+//!
+//! Adding `#[merge_slashes]` to the enum makes each `/` of the prefix, mounts and literal path
+//! segments match a run of consecutive slashes, so `/api//users/42` resolves the same as
+//! `/api/users/42`. Nothing is copied: captures still borrow from the request, and a catch-all
+//! keeps the slashes it captures as sent, so `/files//a//b` captures `a//b`. With lenient trailing
+//! slashes, a run of trailing slashes counts as one, so `/users/42//` matches `/users/{id}`. With
+//! `#[trailing_slash(strict)]` it only matches a path declared with a trailing slash, like
+//! `/users/{id}/`. Flattened and mounted routers match with their own setting. It's opt-in, since
+//! merging slashes can hide a misbehaving client or a path that a proxy in front of the router
+//! treats differently.
+//!
+//! ## Percent Decoding:
+//!
+//! Captures are zero-copy slices of the raw request path, so `/users/john%20doe` captures
+//! `john%20doe`. Adding `#[decode]` to a field, or to a variant to apply it to every field,
+//! percent-decodes the capture before it's parsed. Decoding may allocate, so decoded fields must
+//! be owned types like `String` or `u32`. Invalid escapes cause the route not to match.
+//!
+//! ## Non-UTF-8 Paths:
+//!
+//! Captures are only validated as UTF-8 by the fields that parse them, through
+//! `FromRoute::parse_path_bytes`. A `&[u8]` field captures any bytes, and a `Latin1` field
+//! borrows a capture that's valid UTF-8 and decodes any other capture as latin-1, for legacy
+//! clients. `Router::resolve` fails with `Error::NonUtf8Path` when a path that isn't valid UTF-8
+//! matches no route, while `Router::resolve_non_utf8` resolves it with no route, so it falls
+//! through to the usual not found handling.
+//!
+//! ## Query Parameters:
+//!
+//! Query parameters can be captured by listing them after a `?` in the route, e.g.
+//! `#[get("/search?{q}&{page}")]` binds the `q` and `page` fields from `/search?page=2&q=foo`.
+//! Parameters may appear in any order, the first occurrence of a repeated parameter wins, and the
+//! route doesn't match if a declared parameter is missing or fails to parse, except that an
+//! `Option` field is bound to `None` when its parameter is missing. To read the query
+//! without declaring it, `RouterResult::query_pairs()` iterates over its key/value pairs with `+`
+//! and `%XX` escapes decoded, only allocating for keys and values that contain them. The query
+//! starts after the first `?` of the request target, so it may contain more `?`s, and a stray
+//! `#fragment` after it is kept as part of the query rather than stripped.
+//!
+//! ## Not Found vs Method Not Allowed:
+//!
+//! `Router::resolve_with_status` resolves like `Router::resolve`, and also returns a `RouteStatus`
+//! telling a path no route knows about (`404 Not Found`) apart from a path that only resolves for
+//! other methods (`405 Method Not Allowed`, along with the methods for the `Allow` header).
+//! `Router::allowed_method_set` returns those methods as a `MethodSet` bitset without allocating,
+//! which displays as an `Allow` header value like `GET, POST`. It only tries the methods in
+//! `Router::METHODS`, the methods the router declares any route for.
+//!
+//! ## Unknown Methods:
+//!
+//! `Router::resolve` rejects methods that `Method` doesn't model with `Error::InvalidMethod`.
+//! `Router::resolve_any_method` instead returns `AnyMethodResult::Unknown` with the method token,
+//! query, version, headers and body of such requests, so a server can respond with
+//! `501 Not Implemented`, while known methods are routed as usual in `AnyMethodResult::Known`.
+//!
+//! ## Fallback Routes:
+//!
+//! A variant marked `#[fallback]` resolves whenever no other route matches, e.g. to render a
+//! custom 404 page, instead of `route` being `None`. It's either a unit variant or captures the full
+//! path in a single field, like `NotFound { path: &'a str }`. It matches any method unless it's
+//! also given bare method attributes, like `#[get]`, and an enum can only have one.
+//!
+//! ## Partial Matches:
+//!
+//! `Router::handle_parsed_partial` reports how far matching got when no route matches, as the
+//! shortest remaining path any route reached, which is handy for dispatching the rest of the path
+//! by hand or for a 404 like "no route under /api/users".
+//!
+//! ## Route Patterns:
+//!
+//! `RouterResult::pattern` holds the declared pattern of the matched route, e.g.
+//! `/api/users/{id}`, which is useful for grouping logs and metrics by route rather than by
+//! concrete path. Flattened routes report the pattern with every enclosing prefix applied. The
+//! full table is available as `Router::PATTERNS`.
+//!
+//! ## Route Introspection:
+//!
+//! `Router::ROUTES` lists every declared route as a `(Method, pattern)` pair, with prefixes and
+//! flattened sub-routers expanded, for generating documentation or serving a `/routes` debug
+//! endpoint. `Router::PREFIX` holds the first prefix the router was declared with, or an empty
+//! string, so link builders and tests don't have to repeat it.
+//!
+//! ## Route Specificity:
+//!
+//! Routes of the same method are tried from the most to the least specific, segment by segment,
+//! regardless of the order they're declared in. A literal segment like `me` ranks first, then a
+//! segment mixing literals and variables like `{name}.json`, then a constrained variable like
+//! `{id:\d+}`, then a plain variable like `{id}`, and a `{*catch_all}` ranks last. So
+//! `#[get("/users/me")]` matches `/users/me` even when declared after `#[get("/users/{id}")]`.
+//! Equally specific routes are tried in declaration order, and flattened or mounted routers are
+//! tried after every route of the enum itself.
+//!
+//! Routes of different methods never compete, since the generated code matches on the method first.
+//! Its arms are always emitted in the same order, whatever order routes are declared in: `GET`
+//! first, as the most common method, then `POST`, `PUT`, `DELETE`, `HEAD`, `OPTIONS`, `CONNECT`,
+//! `TRACE` and `PATCH`.
+//!
+//! ## Route Conflicts:
+//!
+//! Declaring the same method and path on two variants is a compile error, since the first variant
+//! would always win. Variable names don't matter, so `/users/{id}` conflicts with `/users/{name}`:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum Routes {
+//!     #[get("/users/{id}")]
+//!     GetUserById { id: u32 },
+//!     #[get("/users/{name}")]
+//!     GetUserByName { name: String },
+//! }
+//!```
+//!
+//! Query parameters match in any order, so their order doesn't matter either:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum Routes {
+//!     #[get("/search?{q}&{page}")]
+//!     Search { q: String, page: u32 },
+//!     #[get("/search?{page}&{q}")]
+//!     Find { q: String, page: u32 },
+//! }
+//!```
+//!
+//! Nor does a trailing slash, unless trailing slashes are `#[trailing_slash(strict)]`:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum Routes {
+//!     #[get("/users")]
+//!     ListUsers,
+//!     #[get("/users/")]
+//!     ListUsersSlash,
+//! }
+//!```
+//!
+//! Flattened and mounted routers are checked the same way by prefix: two of them tried under the
+//! same non-empty prefix, their mount path followed by their `Router::PREFIX`, fail to compile,
+//! since the first would shadow the routes of the second. Routers without a prefix, or with
+//! different ones like `/app` and `/app/admin`, aren't compared.
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! #[prefix("/app")]
+//! enum DashboardRoutes {
+//!     #[get("/")]
+//!     Home,
+//! }
+//!
+//! #[derive(FrontLine)]
+//! #[prefix("/app")]
+//! enum SettingsRoutes {
+//!     #[get("/settings")]
+//!     Settings,
+//! }
+//!
+//! #[derive(FrontLine)]
+//! enum AllRoutes {
+//!     #[flatten]
+//!     Dashboard(DashboardRoutes),
+//!     #[flatten]
+//!     Settings(SettingsRoutes),
+//! }
+//!```
+//!
+//! ## Derive Errors:
+//!
+//! Invalid attributes or variants fail to compile with an error pointing at the attribute, path or
+//! variant at fault, rather than a panic in the derive.
+//!
+//! A derive on a union, which can't be routed:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! union Routes {
+//!     id: u32,
+//! }
+//!```
+//!
+//! Deriving `ToPath` when a variant has no route:
+//!
+//!```compile_fail
+//! use front_line::ToPath;
+//!
+//! #[derive(ToPath)]
+//! enum Routes {
+//!     #[get("/users")]
+//!     ListUsers,
+//!     Unrouted,
+//! }
+//!```
+//!
+//! A flag attribute like `#[merge_slashes]`, `#[case_insensitive]`, `#[head_implies_get]` or
+//! `#[non_empty]` given arguments:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! #[merge_slashes(false)]
+//! enum Routes {
+//!     #[get("/users")]
+//!     ListUsers,
+//! }
+//!```
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! #[case_insensitive = "no"]
+//! enum Routes {
+//!     #[get("/users")]
+//!     ListUsers,
+//! }
+//!```
+//!
+//! A `#[prefix]` that isn't a string:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! #[prefix(42)]
+//! enum Routes {
+//!     #[get("/users")]
+//!     ListUsers,
+//! }
+//!```
+//!
+//! A `#[trailing_slash]` other than `strict` or `lenient`:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! #[trailing_slash(loose)]
+//! enum Routes {
+//!     #[get("/users")]
+//!     ListUsers,
+//! }
+//!```
+//!
+//! A path that isn't a string:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum Routes {
+//!     #[get(42)]
+//!     ListUsers,
+//! }
+//!```
+//!
+//! An unknown method in `#[methods]`:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum Routes {
+//!     #[methods(get, fetch, "/users")]
+//!     ListUsers,
+//! }
+//!```
+//!
+//! A `#[methods]` whose path isn't a string:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum Routes {
+//!     #[methods(get, post, /users)]
+//!     ListUsers,
+//! }
+//!```
+//!
+//! A `#[methods]` without any method:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum Routes {
+//!     #[methods("/users")]
+//!     ListUsers,
+//! }
+//!```
+//!
+//! A catch-all that isn't the last part of the path:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum Routes<'a> {
+//!     #[get("/files/{*path}/raw")]
+//!     GetRawFile { path: &'a str },
+//! }
+//!```
+//!
+//! A variable that couldn't name a field, like `{user-id}`, `{1x}` or a nameless `{*}`:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum Routes {
+//!     #[get("/users/{user-id}")]
+//!     GetUser { user_id: u32 },
+//! }
+//!```
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum Routes<'a> {
+//!     #[get("/files/{*}")]
+//!     GetFile(&'a str),
+//! }
+//!```
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum Routes {
+//!     #[get("/search?{1x}")]
+//!     Search(u32),
+//! }
+//!```
+//!
+//! A query parameter that isn't a `{name}` variable:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum Routes<'a> {
+//!     #[get("/search?q")]
+//!     Search { q: &'a str },
+//! }
+//!```
+//!
+//! More fields than path variables:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum Routes {
+//!     #[get("/users/{id}")]
+//!     GetUser { id: u32, page: u32 },
+//! }
+//!```
+//!
+//! Braces on a variant without path variables:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum Routes {
+//!     #[get("/users")]
+//!     ListUsers {},
+//! }
+//!```
+//!
+//! Fields named differently from the path variables:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum Routes {
+//!     #[get("/users/{id}")]
+//!     GetUser { user_id: u32 },
+//! }
+//!```
+//!
+//! A `#[flatten]` variant with more than one field:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum ApiRoutes {
+//!     #[get("/users")]
+//!     ListUsers,
+//! }
+//!
+//! #[derive(FrontLine)]
+//! enum Routes {
+//!     #[flatten]
+//!     Api(ApiRoutes, u32),
+//! }
+//!```
+//!
+//! A `#[flatten]` variant with a named field:
+//!
+//!```compile_fail
+//! use front_line::FrontLine;
+//!
+//! #[derive(FrontLine)]
+//! enum ApiRoutes {
+//!     #[get("/users")]
+//!     ListUsers,
+//! }
+//!
+//! #[derive(FrontLine)]
+//! enum Routes {
+//!     #[flatten]
+//!     Api { routes: ApiRoutes },
+//! }
+//!```
+//!
+//! ## Lenient Parsing:
+//!
+//! `Router::resolve` requires `\r\n` line endings and exactly one space between the method,
+//! request target and version. `Router::resolve_lenient` also accepts requests from clients that
+//! end lines with a bare `\n`, including a `\n\n` header terminator, that separate the tokens of
+//! the request line with runs of spaces, including leading and trailing ones, or that send the
+//! method in lowercase or mixed case, like `get`. Method tokens are case-sensitive, so
+//! `Method::parse` and `resolve` keep rejecting those, while `Method::parse_ci` accepts them.
+//! Its results are marked `lenient`, and only then does `RouterResult::headers()` split header
+//! lines on a bare `\n` or join values folded across lines.
+//!
+//! ## Headerless Requests:
+//!
+//! Some minimal clients end a request without headers right after its request line, e.g.
+//! `GET / HTTP/1.1\r\n`, leaving out the blank line `resolve` requires.
+//! `Router::resolve_unterminated` accepts those as requests without headers or a body, as long as
+//! the request line is complete. A read that stopped after the request line looks the same, so
+//! only use it once the whole request has been read.
+//!
+//! ## Head Size Limits:
+//!
+//! `Router::resolve` scans the whole request for the end of the headers.
+//! `Router::resolve_with_limits(request, max_head_len)` only scans the first `max_head_len` bytes,
+//! and fails with `Error::HeadTooLarge` when the request line and headers don't fit, so oversized
+//! requests are rejected without scanning them.
+//!
+//! ## Partial Requests:
+//!
+//! `Router::resolve` reports `Error::MissingHeaderTerminator` for a request that hasn't been fully
+//! read, just like it reports errors for malformed ones. `Router::resolve_partial` returns
+//! `PartialResult::Incomplete` instead when the bytes read so far could still begin a valid
+//! request, `PartialResult::Invalid` when they can't, and `PartialResult::Complete` otherwise, so
+//! a non-blocking server knows whether to keep reading or close the connection.
+//!
+//! ## Virtual Hosts:
+//!
+//! `#[host("api.example.com")]` on an enum restricts it to requests sent to one of the listed
+//! hosts, so sites served from one process can be flattened into a single router.
+//! `Router::resolve_with_host` reads the host from an absolute-form target or the `Host` header,
+//! ignoring the port and case, and exposes it as `RouterResult::host`, or split into
+//! `RouterResult::host_name` and `RouterResult::port`, which keep an IPv6 literal like
+//! `[::1]:8080` in one piece. Routers with hosts never match through `Router::resolve`, which
+//! doesn't look at the host. A request with more than one `Host` header, or a malformed one, like
+//! `Host : api.example.com` or a `Host` line folded into another header, fails with
+//! `Error::InvalidHost` rather than being routed on a host a proxy may not have seen.
+//!
+//! ## Absolute-form Targets:
+//!
+//! Requests sent to a proxy, like `GET http://example.com/users HTTP/1.1`, are routed on their
+//! path, `/users`, and `RouterResult::authority` holds the `example.com` they were addressed to.
+//! It's `None` for the usual origin-form targets.
+//!
+//! ## `OPTIONS *`:
+//!
+//! A server-wide `OPTIONS * HTTP/1.1` request matches a `#[options("*")]` route, which is never
+//! prefixed. The `*` target is rejected with `Error::MalformedRequestTarget` for any other method.
+//!
+//! ## Target Forms:
+//!
+//! `RouterResult::target_form` tells which of the request-target forms of RFC 7230 was received:
+//! `TargetForm::Origin` for `/users`, `Absolute` for `http://example.com/users`, `Asterisk` for
+//! `*`, `Empty` for a target without a path, like the empty target of `GET  HTTP/1.1` or the
+//! `?page=2` of `HEAD ?page=2 HTTP/1.0`, and `Authority` for anything else, like the
+//! `example.com:443` of a `CONNECT` request. Authority-form and empty targets never match a route,
+//! not even a `#[fallback]`, so a proxy handling `CONNECT` should check for them, and that they
+//! come with `CONNECT`, itself. Such requests still parse, with their version and headers.
+//!
+//! ## Headers:
+//!
+//! `RouterResult::raw_headers` holds the request's raw header block and `RouterResult::raw_body`
+//! everything after the blank line that ends it. `RouterResult::headers()` iterates over the header
+//! block as `(name, value)` pairs borrowed straight from the request.
+//! `RouterResult::body()` trims the body to its `Content-Length`, or to the bytes read so far when
+//! the body is incomplete. For an `application/x-www-form-urlencoded` body,
+//! `RouterResult::form_pairs()` decodes its key/value pairs like `query_pairs()`, and returns `None`
+//! for any other `Content-Type`.
+//!
+//! ## Streaming Bodies:
+//!
+//! `resolve_reader::<Routes, _>(&mut reader, max_head_len)` reads a request from a `BufRead`, e.g.
+//! a `BufReader` around a socket, only up to the blank line that ends its headers, and returns an
+//! `OwnedRouterResult`, leaving the body in the reader to be streamed. The head buffer doesn't
+//! outlive the call, so the router's captures must be owned, e.g. `String`s instead of `&str`s.
+//!
+//! ## Buffer Types:
+//!
+//! `resolve_in::<Routes, _>(buffer)` routes a request held in any `B: AsRef<[u8]>`, like an
+//! `Arc<[u8]>` or a `bytes::Bytes`, and returns a `BufferedRouterResult` that keeps the buffer
+//! instead of borrowing from it. It holds the byte ranges of the request's parts, so the body can
+//! be sliced out of a refcounted buffer without copying, e.g. `bytes.slice(result.body_range())`,
+//! and `as_router_result()` borrows it back as a `RouterResult`. Like `resolve_reader`, the
+//! router's captures must be owned.
+//!
+//! ## Request Spans:
+//!
+//! `Routes::resolve_spans(request)` resolves like `Routes::resolve`, and also returns the
+//! `RequestSpans` of the request: the byte ranges of its method, path, query, version, headers and
+//! body within `request`. Code layered on top of the router can slice the raw bytes back out of
+//! its buffer, e.g. to log the path before percent-decoding, without scanning the request again.
+//!
+//! ## Reverse Routing:
+//!
+//! Deriving `ToPath` alongside `FrontLine` generates a `to_path` method that rebuilds the concrete
+//! path for a route value, e.g. `ApiRoutes::GetUser { id: 42 }.to_path()` returns `/api/users/42`.
+//! Captured fields must implement `Display`, and variants with several routes use the first one
+//! declared. Every value is percent-encoded for where it lands, a segment, a catch-all or a query
+//! value, so a `#[decode]` field containing e.g. `/`, `?`, `&` or `#` routes back to the same
+//! variant. A variable followed by a literal within its segment also has the literal's first
+//! character escaped, so `F { a: "x.y", b: "z" }` on `/f/{a}.{b}` renders `/f/x%2Ey.z`. A query
+//! parameter whose `Option` field is `None` is left out of the query.
+//!
+//! ## Dynamic Routes:
+//!
+//! Routes that are only known at runtime, e.g. ones registered by plugins, can be collected with
+//! `RouterBuilder::new().add(Method::Get, "/plugins/{id}", handler)?` and built into a
+//! `DynRouter`. Patterns use a subset of the derive's syntax, without constraints, optional
+//! variables or query parameters, which `RouterBuilder::add` rejects. `DynRouter::handle` returns
+//! the matched handler along with its captures keyed by variable name, and routes are tried in
+//! the order they were added.
+//!
+//! ## Combined Routers:
+//!
+//! Two routers derived separately, e.g. in different crates, can be tried in turn at runtime with
+//! `Or<A, B>`, which is itself a router whose route is `Or::Left` when `A` matched and `Or::Right`
+//! when only `B` did. Unlike `#[flatten]`, it needs no combined enum, but it doesn't report the
+//! matched pattern.
+//!
+//! ## Pre-parsed Requests:
+//!
+//! When a server framework has already parsed the request line, `Router::resolve_parts` matches
+//! its method and request target directly, e.g. `ApiRoutes::resolve_parts(Method::Get,
+//! "/search?q=rust")`, splitting off the query at the first `?` instead of re-parsing a request.
+//!
+//! ## Hand Written Routers:
+//!
+//! Implementing `Router::handle_parsed` by hand is easier with `segments(path)`, which yields each
+//! `/` delimited segment of the path without allocating. It skips the leading slash, ignores a
+//! single trailing slash, and keeps empty segments, while `Segments::as_str` returns the rest of
+//! the path for catch-all style captures.
+//!
+//! ## `no_std` Support:
+//!
+//! Routing only needs `core`, so disabling the default `std` feature makes the crate `no_std`.
+//! Without `std`, the allocating helpers (`String` captures, `#[decode]`, `ToPath`, `DynRouter`,
+//! `RouterResult::query_pairs`, `RouterResult::form_pairs`, `Router::resolve_with_status`,
+//! `resolve_reader` and `Latin1`) are unavailable and `Error` doesn't implement `Display`.
+//!
+//! ## Route Tables:
+//!
+//! Deriving `RouteTable` instead of `FrontLine` generates no matching code at all, only a
+//! `ROUTE_TABLE` constant with a `(Method, &[Segment])` row per route and the name of each row's
+//! variant in `ROUTE_TABLE_VARIANTS`. `route_table::match_table` walks the table at runtime and
+//! returns the index of the first matching row along with its captures, which keeps flash
+//! constrained `no_std` targets small. Tables only hold whole literal, variable and catch-all
+//! segments, and match them exactly, without the case or trailing slash handling of a router.
+//!
+//! ## `http` Interop:
+//!
+//! The optional `http` feature converts between `Method` and `http::Method`, with
+//! `From<Method> for http::Method` and `TryFrom<&http::Method> for Method`. The latter fails with
+//! `UnsupportedMethod` for extension methods front-line doesn't model. `HttpVersion` converts into
+//! `http::Version` the same way.
+//!
+//! `RouterResult::to_http_parts` builds the `http::request::Parts` of a routed request, with its
+//! method, version, headers and a URI of the path and query, so it can be handed to code built on
+//! `http`, like a hyper service, without an adapter. The URI is built from `RouterResult::raw_path`,
+//! the path as it was received, before percent-decoding. `to_http_request` does the same, but
+//! returns an `http::Request` with the request's `body()` as its body.
+//!
+//! ## OpenAPI:
+//!
+//! The optional `openapi` feature also derives `OpenApi`, whose `openapi_routes()` lists every
+//! route like `ROUTES` does, but with an OpenAPI path template like `/users/{id}` and the path and
+//! query parameters each route captures. Parameter schemas come from the field types: integers map
+//! to `integer`, floats to `number`, `bool` to `boolean` and anything else to `string`, while an
+//! `Option` query parameter isn't required. `openapi_paths()` renders the routes as an OpenAPI
+//! paths object in a `serde_json::Value`.
+//!
+//! ## Overlapping Routes:
+//!
+//! The optional `resolve-all` feature adds `resolve_all`, which returns every route that matches
+//! a request in the order `resolve` tries them, rather than only the first. With `/users/me` and
+//! `/users/{name}` declared, `GET /users/me` resolves to both, `/users/me` first. This is useful
+//! for debugging or for layering routers, like an auth layer that inspects every matching route.
+//! The `#[fallback]` variant is only returned when no other route matches, and with
+//! `#[head_implies_get]` the `GET` routes are only tried for a `HEAD` request that nothing else
+//! matched, just like `resolve`. Unlike `resolve`, it allocates a `Vec` and tries every route.
+//!
 //! For more advanced usage and examples, please refer to individual module documentation.
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
 pub use front_line_derive::*;
 pub use front_line_router::*;
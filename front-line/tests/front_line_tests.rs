@@ -1,7 +1,11 @@
-use front_line::{Error, FrontLine, HttpVersion, Router, RouterResult};
+use front_line::route_table::{match_table, Segment};
+use front_line::{
+    Error, FromRoute, FromRouteRest, FrontLine, HttpVersion, Latin1, Method, MethodSet, ParseError,
+    RouteStatus, RouteTable, Router, RouterResult, TargetForm, ToPath,
+};
 use rstest::rstest;
 
-#[derive(PartialEq, Debug, FrontLine)]
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
 enum MarketingRoutes {
     #[get("/")]
     RenderIndex,
@@ -17,7 +21,7 @@ enum MarketingRoutes {
     RenderPortal,
 }
 
-#[derive(PartialEq, Debug, FrontLine)]
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
 #[prefix("/api")]
 enum ApiRoutes<'a> {
     #[get("/users")]
@@ -32,12 +36,761 @@ enum ApiRoutes<'a> {
     UpdateUserRole { id: u32, role: &'a str },
 }
 
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+#[prefix("/assets")]
+enum AssetRoutes<'a> {
+    #[get("/versions/{version}/{*path}")]
+    GetVersionedAsset { version: u32, path: &'a str },
+    #[get("/{*path}")]
+    GetAsset { path: &'a str },
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+#[prefix("/templates")]
+enum TemplateRoutes<'a> {
+    #[get("/{{name}}")]
+    NamePlaceholder,
+    #[get("/{{name}}/{id}")]
+    NamePlaceholderById { id: u32 },
+    #[get("/{{{key}}}")]
+    BracedKey { key: &'a str },
+    #[get("/{key}")]
+    Key { key: &'a str },
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+enum AliasRoutes {
+    #[get("/users/{id}")]
+    #[get("/u/{id}")]
+    GetUser { id: u32 },
+    #[get("/u/{name}/posts")]
+    #[get("/users/{name}/posts")]
+    GetPosts(String),
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+#[prefix("/latin")]
+enum Latin1Routes<'a> {
+    #[get("/raw/{name}")]
+    Raw { name: &'a [u8] },
+    #[get("/names/{name}")]
+    Name { name: Latin1<'a> },
+    #[get("/strings/{name}")]
+    Text { name: &'a str },
+}
+
+/// A path to a file that can't escape its root directory.
+#[derive(PartialEq, Debug)]
+struct FilePath<'a>(&'a str);
+
+impl<'de> FromRouteRest<'de> for FilePath<'de> {
+    fn parse_path_rest(rest: &'de str) -> Option<Self> {
+        let escapes = rest.split('/').any(|segment| segment == "..");
+        (!rest.is_empty() && !escapes).then_some(FilePath(rest))
+    }
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+enum RestRoutes<'a> {
+    #[get("/sums/{**values}")]
+    Sum { values: Vec<u32> },
+    #[get("/files/{**path}")]
+    #[get("/raw/{**path}")]
+    GetFile(FilePath<'a>),
+    #[get("/tags/{**tags}")]
+    GetTags {
+        #[decode]
+        tags: Vec<String>,
+    },
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+enum RestListRoutes<T> {
+    #[get("/items/{**items}")]
+    GetItems { items: Vec<T> },
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+#[prefix("/strict")]
+#[trailing_slash(strict)]
+enum StrictRoutes {
+    #[get("/users")]
+    GetAllUsers,
+    #[get("/users/{id}")]
+    GetUser { id: u32 },
+    #[post("/users")]
+    CreateUser,
+    #[get("/admins/")]
+    GetAllAdmins,
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+#[prefix("/legacy")]
+#[case_insensitive]
+enum LegacyRoutes<'a> {
+    #[get("/users")]
+    GetAllUsers,
+    #[get("/users/{name}/roles")]
+    GetUserRoles { name: &'a str },
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+#[prefix("/people")]
+enum PeopleRoutes<'a> {
+    #[get("/{name}/roles/{role}")]
+    GetPersonRole {
+        #[decode]
+        name: String,
+        role: &'a str,
+    },
+    #[decode]
+    #[get("/{first}/{last}")]
+    GetPerson { first: String, last: String },
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+enum EncodedRoutes {
+    #[decode]
+    #[get("/notes/{title}/versions/{version}")]
+    GetNote { title: String, version: u32 },
+    #[decode]
+    #[get("/files/{*path}")]
+    GetFile { path: String },
+    #[decode]
+    #[get("/search?{q}&{page}")]
+    Search { q: String, page: u32 },
+    #[decode]
+    #[get("/split/{a}.{b}")]
+    Split { a: String, b: String },
+    #[decode]
+    #[get("/list?{q}&{page}")]
+    List { q: String, page: Option<u32> },
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+enum SearchRoutes<'a> {
+    #[get("/search?{q}&{page}")]
+    Search { q: &'a str, page: u32 },
+    #[get("/users/{id}/posts?{tag}")]
+    GetUserPosts { id: u32, tag: &'a str },
+    #[get("/items/{id}")]
+    GetItem { id: Option<u32> },
+    #[get("/posts?{page}")]
+    ListPosts { page: Option<u32> },
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+#[prefix("/tenants/{tenant}")]
+enum TenantRoutes<'a> {
+    #[get("/users")]
+    GetUsers { tenant: &'a str },
+    #[get("/users/{id}")]
+    GetUser { tenant: &'a str, id: u32 },
+    #[options("*")]
+    Server,
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+#[prefix("/api", "/api/v1")]
+enum PrefixedRootRoutes {
+    #[get("/")]
+    GetRoot,
+    #[get("/users")]
+    GetUsers,
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+#[prefix("/admin")]
+enum BarePrefixRoutes {
+    #[get("")]
+    GetDashboard,
+    #[get("/users")]
+    GetUsers,
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+#[prefix("/api")]
+#[trailing_slash(strict)]
+enum StrictPrefixedRootRoutes {
+    #[get("/")]
+    GetRoot,
+    #[post("")]
+    PostRoot,
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+#[prefix("/docs")]
+#[head_implies_get]
+enum DocumentRoutes<'a> {
+    #[get("/{name}")]
+    GetDocument { name: &'a str },
+    #[head("/latest")]
+    HeadLatest,
+    #[post("/{name}")]
+    CreateDocument { name: &'a str },
+    #[fallback]
+    #[get]
+    NotFound,
+}
+
+#[derive(PartialEq, Debug)]
+struct Quantity(u32);
+
+impl<'de> FromRoute<'de> for Quantity {
+    fn parse_path_variable(slice: &'de str) -> Option<Self> {
+        Self::try_parse_path_variable(slice).ok()
+    }
+
+    fn try_parse_path_variable(slice: &'de str) -> Result<Self, ParseError> {
+        match slice.parse() {
+            Ok(quantity) if quantity > 0 => Ok(Quantity(quantity)),
+            _ => Err(ParseError::new("a positive integer")),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+enum CheckedRoutes {
+    #[get("/orders/{id}")]
+    GetOrder { id: Result<u32, ParseError> },
+    #[put("/orders/{id}/quantity/{quantity}")]
+    SetQuantity {
+        id: u32,
+        quantity: Result<Quantity, ParseError>,
+    },
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+enum GeometryRoutes {
+    #[get("/points/{coords}")]
+    GetPoint { coords: [f64; 3] },
+    #[get("/ranges/{range}")]
+    GetRange { range: (u32, u32) },
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+#[prefix("/drafts")]
+enum DraftRoutes {
+    #[get("/{title}")]
+    GetDraft { title: String },
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+enum PublishingRoutes<'a> {
+    #[flatten]
+    Drafts(DraftRoutes),
+    #[flatten]
+    Things(ThingRoute<'a>),
+}
+
+#[derive(PartialEq, Debug)]
+struct Date {
+    year: u16,
+    month: u8,
+    day: u8,
+}
+
+fn parse_date(slice: &str) -> Option<Date> {
+    let mut parts = slice.splitn(3, '-');
+    Some(Date {
+        year: parts.next()?.parse().ok()?,
+        month: parts.next()?.parse().ok()?,
+        day: parts.next()?.parse().ok()?,
+    })
+}
+
+fn parse_tag(slice: &str) -> Option<String> {
+    Some(slice.to_lowercase())
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+enum CalendarRoutes {
+    #[get("/days/{date}")]
+    GetDay {
+        #[parse_with(parse_date)]
+        date: Date,
+    },
+    #[get("/days/{date}/events/{id}")]
+    GetEvent(#[parse_with(crate::parse_date)] Date, u32),
+    #[post("/tags/{tag}")]
+    FollowTag {
+        #[decode]
+        #[parse_with(parse_tag)]
+        tag: String,
+    },
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+#[prefix("/accounts/{id}")]
+enum AccountRoutes<'a> {
+    #[get("")]
+    GetAccount {
+        #[from("id")]
+        account_id: u32,
+    },
+    #[get("/members/{member}?{type}")]
+    GetMember {
+        #[from("id")]
+        account_id: u32,
+        #[from("member")]
+        member_id: u32,
+        #[from("type")]
+        kind: &'a str,
+    },
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+enum ArchiveRoutes<'a> {
+    #[get("/posts/{year}/{month?}/{day?}")]
+    Posts {
+        year: u32,
+        month: Option<u32>,
+        day: Option<u32>,
+    },
+    #[get(r"/pages/{page?:\d+}?{sort}")]
+    Pages(Option<u32>, &'a str),
+    #[get("/{slug?}")]
+    Page { slug: Option<&'a str> },
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+enum AdminRoutes<'a> {
+    #[get("/")]
+    Dashboard,
+    #[get("/users/{name}")]
+    GetUser { name: &'a str },
+}
+
+#[derive(PartialEq, Debug)]
+struct MetricsRoute<'a>(&'a str);
+
+impl<'de: 'a, 'a> Router<'de> for MetricsRoute<'a> {
+    fn handle_parsed(method: Method, remaining_path: &'de str) -> Option<Self> {
+        match method {
+            Method::Get => Some(MetricsRoute(remaining_path)),
+            _ => None,
+        }
+    }
+}
+
+impl ToPath for MetricsRoute<'_> {
+    fn to_path(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[cfg(feature = "openapi")]
+impl front_line::OpenApi for MetricsRoute<'_> {
+    fn openapi_routes() -> Vec<front_line::OpenApiRoute> {
+        Vec::new()
+    }
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+#[prefix("/console")]
+enum ConsoleRoutes<'a> {
+    #[get("/")]
+    Home,
+    #[mount("/admin")]
+    Admin(AdminRoutes<'a>),
+    #[mount("/metrics")]
+    Metrics(MetricsRoute<'a>),
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+#[prefix("/items")]
+enum ItemRoutes<Id: Copy = u32> {
+    #[get("/{id}")]
+    GetItem { id: Id },
+}
+
+fn parse_fixed<const N: usize>(slice: &str) -> Option<[u8; N]> {
+    slice.as_bytes().try_into().ok()
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+enum GenericRoutes<'a, T, const N: usize>
+where
+    T: PartialEq,
+{
+    #[get("/names/{name}")]
+    GetName { name: &'a str },
+    #[get("/values/{value}")]
+    GetValue { value: T },
+    #[get("/optional/{value}")]
+    GetOptional(Option<T>),
+    #[get("/sized/{value}")]
+    GetSized {
+        #[parse_with(parse_fixed)]
+        value: [u8; N],
+    },
+    #[flatten]
+    Items(ItemRoutes),
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+#[prefix("/compat", "/compat/v1")]
+enum CompatRoutes<'a> {
+    #[get("/users")]
+    GetAllUsers,
+    #[get("/{version}/status")]
+    GetStatus { version: &'a str },
+    #[flatten]
+    Api(ApiRoutes<'a>),
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+#[prefix("/files")]
+enum FileRoutes<'a> {
+    #[get(r"/{id:\d+}")]
+    GetFile { id: u64 },
+    #[get("/latest")]
+    GetLatestFile,
+    #[get("/{name:[a-zA-Z0-9]+}")]
+    GetNamedFile { name: &'a str },
+    #[delete("/{id:\\d+}/versions/{version:[0-9]+}")]
+    DeleteFileVersion { id: u64, version: u32 },
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+#[prefix("/bank/v1")]
+enum BankRoutes<'a> {
+    #[get("/status")]
+    GetStatus,
+    #[prefix("/accounts")]
+    #[get("")]
+    ListAccounts,
+    #[prefix("/accounts")]
+    #[get("/{id}")]
+    #[delete("/{id}")]
+    Account { id: u32 },
+    #[prefix("/accounts/{id}")]
+    #[get("/members/{member}")]
+    GetMember { id: u32, member: &'a str },
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+#[prefix("/api")]
+enum GroupedRoutes<'a> {
+    #[get("/status")]
+    Status,
+    #[group("/v1")]
+    #[get("/users/{id}")]
+    UserV1 { id: u32 },
+    #[get("/teams")]
+    TeamsV1,
+    #[group("/v2")]
+    #[get("/users/{id}")]
+    UserV2 { id: u32 },
+    #[prefix("/orgs/{org}")]
+    #[get("/members")]
+    MembersV2 { org: &'a str },
+    #[mount("/admin")]
+    AdminV2(AdminRoutes<'a>),
+    #[group("")]
+    #[get("/health")]
+    Health,
+}
+
+#[derive(PartialEq, Debug, FrontLine, RouteTable)]
+#[prefix("/api", "/v1")]
+enum TableRoutes {
+    #[get("/")]
+    Index,
+    #[get("/users")]
+    #[post("/users")]
+    Users,
+    #[get("/users/{id}/posts/{post?}")]
+    UserPosts { id: u32, post: Option<u32> },
+    #[get("/files/{*path}")]
+    File { path: String },
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+#[prefix("api/")]
+enum RelativeRoutes<'a> {
+    #[get("")]
+    Index,
+    #[get("users/{id}")]
+    User { id: u32 },
+    #[get("/teams")]
+    Teams,
+    #[prefix("accounts/")]
+    #[get("{id}")]
+    Account { id: u32 },
+    #[mount("admin/")]
+    Admin(AdminRoutes<'a>),
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+#[host("api.example.com")]
+enum ApiSiteRoutes<'a> {
+    #[get("/status")]
+    GetStatus,
+    #[get("/users/{id}")]
+    GetUser { id: &'a str },
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+#[host("www.example.com", "example.com")]
+enum WwwSiteRoutes {
+    #[get("/")]
+    GetIndex,
+    #[get("/status")]
+    GetStatus,
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+enum SiteRoutes<'a> {
+    #[flatten]
+    Api(ApiSiteRoutes<'a>),
+    #[flatten]
+    Www(WwwSiteRoutes),
+    #[get("/health")]
+    Health,
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+enum SpecificityRoutes<'a> {
+    #[get("/users/{*rest}")]
+    UserPage { rest: &'a str },
+    #[get("/users/{id}")]
+    User { id: &'a str },
+    #[get(r"/users/{id:\d+}")]
+    NumberedUser { id: u32 },
+    #[get("/users/{id}.json")]
+    UserJson { id: &'a str },
+    #[get("/users/me")]
+    CurrentUser,
+    #[get("/users/{id}/posts")]
+    UserPosts { id: &'a str },
+    #[get("/users/me/posts")]
+    CurrentUserPosts,
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+#[prefix("/tuples")]
+enum TupleRoutes<'a> {
+    #[get("/users/{id}")]
+    GetUser(u32),
+    #[get("/users/{id}/posts/{slug}")]
+    GetUserPost(u32, &'a str),
+    #[delete("/users/{id}")]
+    DeleteUser { id: u32 },
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+#[prefix("/shared")]
+enum SharedPrefixRoutes<'a> {
+    #[get("/api/v1/users/{id}")]
+    GetUser { id: u32 },
+    #[get("/api/{*rest}")]
+    GetApiFallback { rest: &'a str },
+    #[get("/api/v1/teams")]
+    GetTeams,
+    #[get("/api/v1/teams/{id}")]
+    GetTeam { id: u32 },
+    #[post("/api/v1/teams")]
+    CreateTeam,
+    #[get("/api")]
+    GetApiIndex,
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+#[prefix("/pages")]
+enum PageRoutes<'a> {
+    #[get("/about")]
+    GetAbout,
+    #[fallback]
+    NotFound { path: &'a str },
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+enum ReadOnlyRoutes {
+    #[get("/")]
+    Index,
+    #[fallback]
+    #[get]
+    #[head]
+    NotFound,
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+#[prefix("/server")]
+enum ServerRoutes {
+    #[options("*")]
+    ServerOptions,
+    #[options("/status")]
+    StatusOptions,
+    #[get("/status")]
+    GetStatus,
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+#[get("/health")]
+struct HealthRoute;
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+#[prefix("/things")]
+#[get("/{name}")]
+#[put("/{name}")]
+struct ThingRoute<'a> {
+    name: &'a str,
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+#[get(r"/widgets/{id:\d+}")]
+struct WidgetRoute(u32);
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+enum StructRoutes<'a> {
+    #[flatten]
+    Health(HealthRoute),
+    #[flatten]
+    Thing(ThingRoute<'a>),
+    #[flatten]
+    Widget(WidgetRoute),
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+enum MixedSegmentRoutes<'a> {
+    #[get("/files/{name}.json")]
+    GetJsonFile { name: &'a str },
+    #[get("/files/{name}.tar.gz")]
+    GetArchive { name: &'a str },
+    #[put("/files/{name}.tar.gz")]
+    PutArchive { name: &'a str },
+    #[get("/v{version}/users")]
+    GetUsers { version: u32 },
+    #[get("/reports/{year}-{month}/summary")]
+    GetSummary { year: u16, month: u8 },
+    #[get("/items/{id};{rev}")]
+    GetItemRevision { id: u32, rev: &'a str },
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+#[case_insensitive]
+enum CaseInsensitiveMixedRoutes<'a> {
+    #[get("/files/{name}.json")]
+    GetJsonFile { name: &'a str },
+    #[get("/prices/{amount}€")]
+    GetPrice { amount: u32 },
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+enum ResourceRoutes {
+    #[get("/resource")]
+    #[head("/resource")]
+    Read,
+    #[methods(put, patch, "/resource")]
+    Write,
+    #[methods(delete, "/resource")]
+    #[delete("/resource")]
+    Delete,
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+enum HealthRoutes<'a> {
+    #[any("/health")]
+    Health,
+    #[any("/proxy/{*path}")]
+    Proxy { path: &'a str },
+    #[get("/status")]
+    Status,
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+#[prefix("/echo")]
+enum MethodFieldRoutes<'a> {
+    #[any("/")]
+    Root {
+        #[method]
+        method: Method,
+    },
+    #[any("/proxy/{*path}")]
+    Proxy {
+        #[method]
+        method: Method,
+        path: &'a str,
+    },
+    #[methods(get, post, "/items/{id}")]
+    Item(#[method] Method, u32),
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+enum NonEmptyFieldRoutes<'a> {
+    #[get("/users/{name}/roles/{role}")]
+    GetUserRole {
+        #[non_empty]
+        name: &'a str,
+        role: &'a str,
+    },
+    #[get("/search?{q}")]
+    #[non_empty]
+    Search { q: &'a str },
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+#[non_empty]
+enum NonEmptyRoutes<'a> {
+    #[get("/users/{name}/roles/{role}")]
+    GetUserRole { name: &'a str, role: &'a str },
+    #[get("/files/{*path}")]
+    GetFile { path: &'a str },
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+#[prefix("/api")]
+#[merge_slashes]
+enum MergedSlashRoutes<'a> {
+    #[get("/")]
+    GetIndex,
+    #[get("/users")]
+    GetAllUsers,
+    #[post("/users")]
+    CreateUser,
+    #[get("/users/{id}")]
+    GetUser { id: u32 },
+    #[get("/users/{id}/roles/{role}")]
+    GetUserRole { id: u32, role: &'a str },
+    #[get("/files/{*path}")]
+    GetFile { path: &'a str },
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+#[merge_slashes]
+#[trailing_slash(strict)]
+#[case_insensitive]
+enum StrictMergedSlashRoutes {
+    #[get("/users/{id}")]
+    GetUser { id: u32 },
+    #[get("/admins/")]
+    GetAllAdmins,
+}
+
+#[derive(PartialEq, Debug, FrontLine, ToPath)]
+#[prefix("/v1")]
+enum VersionedRoutes<'a> {
+    #[flatten]
+    Marketing(MarketingRoutes),
+    #[flatten]
+    Api(ApiRoutes<'a>),
+    #[flatten]
+    Assets(AssetRoutes<'a>),
+    #[flatten]
+    People(PeopleRoutes<'a>),
+    #[get("/search?{q}&{page}")]
+    Search { q: &'a str, page: u32 },
+}
+
 #[derive(PartialEq, Debug, FrontLine)]
 enum AllRoutes<'a> {
     #[flatten]
     Marketing(MarketingRoutes),
     #[flatten]
     Api(ApiRoutes<'a>),
+    #[flatten]
+    Search(SearchRoutes<'a>),
 }
 
 #[rstest]
@@ -45,81 +798,144 @@ enum AllRoutes<'a> {
     b"GET / HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(MarketingRoutes::RenderIndex),
+        pattern: Some("/"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"GET /?key=value HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(MarketingRoutes::RenderIndex),
+        pattern: Some("/"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/",
         query: "key=value",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"GET / HTTP/1.1\r\n\r\nheader-section",
         Ok(RouterResult {
         route: Some(MarketingRoutes::RenderIndex),
+        pattern: Some("/"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"header-section",
+        raw_headers: b"",
+        raw_body: b"header-section",
+        lenient: false,
     })
 )]
 #[case(
     b"GET /?key=value HTTP/1.1\r\n\r\nheader-section",
     Ok(RouterResult {
         route: Some(MarketingRoutes::RenderIndex),
+        pattern: Some("/"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/",
         query: "key=value",
         version: HttpVersion::OneOne,
-        head_and_body: b"header-section",
+        raw_headers: b"",
+        raw_body: b"header-section",
+        lenient: false,
     })
 )]
 #[case(
     b"GET /sign-up HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(MarketingRoutes::RenderSignUp),
+        pattern: Some("/sign-up"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/sign-up",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"POST /sign-up HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(MarketingRoutes::ProcessSignUp),
+        pattern: Some("/sign-up"),
+        method: Method::Post,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/sign-up",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"GET /log-in HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(MarketingRoutes::RenderLogIn),
+        pattern: Some("/log-in"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/log-in",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"POST /log-in HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(MarketingRoutes::ProcessLogIn),
+        pattern: Some("/log-in"),
+        method: Method::Post,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/log-in",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"GET /portal HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(MarketingRoutes::RenderPortal),
+        pattern: Some("/portal"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/portal",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 fn test_non_prefixed_routes(
@@ -135,72 +951,128 @@ fn test_non_prefixed_routes(
     b"GET /api/users HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(ApiRoutes::GetAllUsers),
+        pattern: Some("/api/users"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/api/users",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"POST /api/users HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(ApiRoutes::CreateUser),
+        pattern: Some("/api/users"),
+        method: Method::Post,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/api/users",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"GET /api/users/42 HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(ApiRoutes::GetUser { id: 42 }),
+        pattern: Some("/api/users/{id}"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/api/users/42",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"GET /api/users/42/roles/admin HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(ApiRoutes::GetUserRole { id: 42, role: "admin" }),
+        pattern: Some("/api/users/{id}/roles/{role}"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/api/users/42/roles/admin",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"PUT /api/users/42/roles/admin HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(ApiRoutes::UpdateUserRole { id: 42, role: "admin" }),
+        pattern: Some("/api/users/{id}/roles/{role}"),
+        method: Method::Put,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/api/users/42/roles/admin",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"PUT /api/users/42/roles/admin?key=value HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(ApiRoutes::UpdateUserRole { id: 42, role: "admin" }),
+        pattern: Some("/api/users/{id}/roles/{role}"),
+        method: Method::Put,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/api/users/42/roles/admin",
         query: "key=value",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"PUT /api/users/42/roles/admin HTTP/1.1\r\n\r\nheader-section",
     Ok(RouterResult {
         route: Some(ApiRoutes::UpdateUserRole { id: 42, role: "admin" }),
+        pattern: Some("/api/users/{id}/roles/{role}"),
+        method: Method::Put,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/api/users/42/roles/admin",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"header-section",
+        raw_headers: b"",
+        raw_body: b"header-section",
+        lenient: false,
     })
 )]
 #[case(
     b"PUT /api/users/42/roles/admin?key=value HTTP/1.1\r\n\r\nheader-section",
     Ok(RouterResult {
         route: Some(ApiRoutes::UpdateUserRole { id: 42, role: "admin" }),
+        pattern: Some("/api/users/{id}/roles/{role}"),
+        method: Method::Put,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/api/users/42/roles/admin",
         query: "key=value",
         version: HttpVersion::OneOne,
-        head_and_body: b"header-section",
+        raw_headers: b"",
+        raw_body: b"header-section",
+        lenient: false,
     })
 )]
 fn test_prefixed_routes(
@@ -216,153 +1088,288 @@ fn test_prefixed_routes(
     b"GET / HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(AllRoutes::Marketing(MarketingRoutes::RenderIndex)),
+        pattern: Some("/"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"GET /?key=value HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(AllRoutes::Marketing(MarketingRoutes::RenderIndex)),
+        pattern: Some("/"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/",
         query: "key=value",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"GET / HTTP/1.1\r\n\r\nheader-section",
         Ok(RouterResult {
         route: Some(AllRoutes::Marketing(MarketingRoutes::RenderIndex)),
+        pattern: Some("/"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"header-section",
+        raw_headers: b"",
+        raw_body: b"header-section",
+        lenient: false,
     })
 )]
 #[case(
     b"GET /?key=value HTTP/1.1\r\n\r\nheader-section",
     Ok(RouterResult {
         route: Some(AllRoutes::Marketing(MarketingRoutes::RenderIndex)),
+        pattern: Some("/"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/",
         query: "key=value",
         version: HttpVersion::OneOne,
-        head_and_body: b"header-section",
+        raw_headers: b"",
+        raw_body: b"header-section",
+        lenient: false,
     })
 )]
 #[case(
     b"GET /sign-up HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(AllRoutes::Marketing(MarketingRoutes::RenderSignUp)),
+        pattern: Some("/sign-up"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/sign-up",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"POST /sign-up HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(AllRoutes::Marketing(MarketingRoutes::ProcessSignUp)),
+        pattern: Some("/sign-up"),
+        method: Method::Post,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/sign-up",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"GET /log-in HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(AllRoutes::Marketing(MarketingRoutes::RenderLogIn)),
+        pattern: Some("/log-in"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/log-in",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"POST /log-in HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(AllRoutes::Marketing(MarketingRoutes::ProcessLogIn)),
+        pattern: Some("/log-in"),
+        method: Method::Post,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/log-in",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"GET /portal HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(AllRoutes::Marketing(MarketingRoutes::RenderPortal)),
+        pattern: Some("/portal"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/portal",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"GET /api/users HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(AllRoutes::Api(ApiRoutes::GetAllUsers)),
+        pattern: Some("/api/users"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/api/users",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"POST /api/users HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(AllRoutes::Api(ApiRoutes::CreateUser)),
+        pattern: Some("/api/users"),
+        method: Method::Post,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/api/users",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"GET /api/users/42 HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(AllRoutes::Api(ApiRoutes::GetUser { id: 42 })),
+        pattern: Some("/api/users/{id}"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/api/users/42",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"GET /api/users/42/roles/admin HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(AllRoutes::Api(ApiRoutes::GetUserRole { id: 42, role: "admin" })),
+        pattern: Some("/api/users/{id}/roles/{role}"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/api/users/42/roles/admin",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"PUT /api/users/42/roles/admin HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(AllRoutes::Api(ApiRoutes::UpdateUserRole { id: 42, role: "admin" })),
+        pattern: Some("/api/users/{id}/roles/{role}"),
+        method: Method::Put,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/api/users/42/roles/admin",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"PUT /api/users/42/roles/admin?key=value HTTP/1.1\r\n\r\n",
     Ok(RouterResult {
         route: Some(AllRoutes::Api(ApiRoutes::UpdateUserRole { id: 42, role: "admin" })),
+        pattern: Some("/api/users/{id}/roles/{role}"),
+        method: Method::Put,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/api/users/42/roles/admin",
         query: "key=value",
         version: HttpVersion::OneOne,
-        head_and_body: b"",
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 #[case(
     b"PUT /api/users/42/roles/admin HTTP/1.1\r\n\r\nheader-section",
     Ok(RouterResult {
         route: Some(AllRoutes::Api(ApiRoutes::UpdateUserRole { id: 42, role: "admin" })),
+        pattern: Some("/api/users/{id}/roles/{role}"),
+        method: Method::Put,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/api/users/42/roles/admin",
         query: "",
         version: HttpVersion::OneOne,
-        head_and_body: b"header-section",
+        raw_headers: b"",
+        raw_body: b"header-section",
+        lenient: false,
     })
 )]
 #[case(
     b"PUT /api/users/42/roles/admin?key=value HTTP/1.1\r\n\r\nheader-section",
     Ok(RouterResult {
         route: Some(AllRoutes::Api(ApiRoutes::UpdateUserRole { id: 42, role: "admin" })),
+        pattern: Some("/api/users/{id}/roles/{role}"),
+        method: Method::Put,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/api/users/42/roles/admin",
         query: "key=value",
         version: HttpVersion::OneOne,
-        head_and_body: b"header-section",
+        raw_headers: b"",
+        raw_body: b"header-section",
+        lenient: false,
+    })
+)]
+#[case(
+    b"GET /search?q=foo&page=2 HTTP/1.1\r\n\r\n",
+    Ok(RouterResult {
+        route: Some(AllRoutes::Search(SearchRoutes::Search { q: "foo", page: 2 })),
+        pattern: Some("/search?{q}&{page}"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/search",
+        query: "q=foo&page=2",
+        version: HttpVersion::OneOne,
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
     })
 )]
 fn test_merged_routes(
@@ -372,3 +1379,2232 @@ fn test_merged_routes(
     let result = AllRoutes::resolve(input);
     assert_eq!(result, expected_result);
 }
+
+#[rstest]
+#[case(
+    b"GET /assets/css/app/main.css HTTP/1.1\r\n\r\n",
+    Ok(RouterResult {
+        route: Some(AssetRoutes::GetAsset { path: "css/app/main.css" }),
+        pattern: Some("/assets/{*path}"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/assets/css/app/main.css",
+        query: "",
+        version: HttpVersion::OneOne,
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
+    })
+)]
+#[case(
+    b"GET /assets/css/app/main.css?v=1 HTTP/1.1\r\n\r\n",
+    Ok(RouterResult {
+        route: Some(AssetRoutes::GetAsset { path: "css/app/main.css" }),
+        pattern: Some("/assets/{*path}"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/assets/css/app/main.css",
+        query: "v=1",
+        version: HttpVersion::OneOne,
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
+    })
+)]
+#[case(
+    b"GET /assets/ HTTP/1.1\r\n\r\n",
+    Ok(RouterResult {
+        route: Some(AssetRoutes::GetAsset { path: "" }),
+        pattern: Some("/assets/{*path}"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/assets/",
+        query: "",
+        version: HttpVersion::OneOne,
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
+    })
+)]
+#[case(
+    b"GET /assets/versions/3/js/app.js HTTP/1.1\r\n\r\n",
+    Ok(RouterResult {
+        route: Some(AssetRoutes::GetVersionedAsset { version: 3, path: "js/app.js" }),
+        pattern: Some("/assets/versions/{version}/{*path}"),
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/assets/versions/3/js/app.js",
+        query: "",
+        version: HttpVersion::OneOne,
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
+    })
+)]
+#[case(
+    b"GET /assets HTTP/1.1\r\n\r\n",
+    Ok(RouterResult {
+        route: None,
+        pattern: None,
+        method: Method::Get,
+        authority: None,
+        target_form: TargetForm::Origin,
+        raw_path: b"/assets",
+        query: "",
+        version: HttpVersion::OneOne,
+        raw_headers: b"",
+        raw_body: b"",
+        lenient: false,
+    })
+)]
+fn test_catch_all_routes(
+    #[case] input: &[u8],
+    #[case] expected_result: Result<RouterResult<'_, AssetRoutes>, Error>,
+) {
+    let result = AssetRoutes::resolve(input);
+    assert_eq!(result, expected_result);
+}
+
+#[rstest]
+#[case(b"GET /sums/1/2/3 HTTP/1.1\r\n\r\n", Some(RestRoutes::Sum { values: vec![1, 2, 3] }))]
+#[case(b"GET /sums/4 HTTP/1.1\r\n\r\n", Some(RestRoutes::Sum { values: vec![4] }))]
+#[case(b"GET /sums/ HTTP/1.1\r\n\r\n", Some(RestRoutes::Sum { values: vec![] }))]
+#[case(b"GET /sums/1/x/3 HTTP/1.1\r\n\r\n", None)]
+#[case(
+    b"GET /files/css/app.css?v=2 HTTP/1.1\r\n\r\n",
+    Some(RestRoutes::GetFile(FilePath("css/app.css")))
+)]
+#[case(
+    b"GET /raw/a/b HTTP/1.1\r\n\r\n",
+    Some(RestRoutes::GetFile(FilePath("a/b")))
+)]
+#[case(b"GET /files/../secret HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /files/ HTTP/1.1\r\n\r\n", None)]
+#[case(
+    b"GET /tags/rust/web%20dev HTTP/1.1\r\n\r\n",
+    Some(RestRoutes::GetTags { tags: vec!["rust".to_string(), "web dev".to_string()] })
+)]
+fn test_rest_captures(#[case] input: &[u8], #[case] expected: Option<RestRoutes>) {
+    let result = RestRoutes::resolve(input).unwrap();
+    assert_eq!(result.route, expected);
+}
+
+#[rstest]
+#[case(
+    b"GET /templates/{name} HTTP/1.1\r\n\r\n",
+    Some(TemplateRoutes::NamePlaceholder)
+)]
+#[case(
+    b"GET /templates/{name}/7 HTTP/1.1\r\n\r\n",
+    Some(TemplateRoutes::NamePlaceholderById { id: 7 })
+)]
+#[case(
+    b"GET /templates/{user} HTTP/1.1\r\n\r\n",
+    Some(TemplateRoutes::BracedKey { key: "user" })
+)]
+#[case(
+    b"GET /templates/name HTTP/1.1\r\n\r\n",
+    Some(TemplateRoutes::Key { key: "name" })
+)]
+#[case(b"GET /templates/{name}/x HTTP/1.1\r\n\r\n", None)]
+fn test_escaped_braces(#[case] input: &[u8], #[case] expected: Option<TemplateRoutes>) {
+    let result = TemplateRoutes::resolve(input).unwrap();
+    assert_eq!(result.route, expected);
+}
+
+#[rstest]
+#[case(TemplateRoutes::NamePlaceholder, "/templates/{name}")]
+#[case(TemplateRoutes::NamePlaceholderById { id: 7 }, "/templates/{name}/7")]
+#[case(TemplateRoutes::BracedKey { key: "user" }, "/templates/{user}")]
+fn test_escaped_braces_to_path(#[case] route: TemplateRoutes, #[case] expected: &str) {
+    assert_eq!(route.to_path(), expected);
+}
+
+#[rstest]
+#[case(
+    b"GET /users/7 HTTP/1.1\r\n\r\n",
+    Some((AliasRoutes::GetUser { id: 7 }, "/users/{id}"))
+)]
+#[case(
+    b"GET /u/7 HTTP/1.1\r\n\r\n",
+    Some((AliasRoutes::GetUser { id: 7 }, "/u/{id}"))
+)]
+#[case(
+    b"GET /u/ann/posts HTTP/1.1\r\n\r\n",
+    Some((AliasRoutes::GetPosts(String::from("ann")), "/u/{name}/posts"))
+)]
+#[case(
+    b"GET /users/ann/posts HTTP/1.1\r\n\r\n",
+    Some((AliasRoutes::GetPosts(String::from("ann")), "/users/{name}/posts"))
+)]
+#[case(b"GET /u/ann HTTP/1.1\r\n\r\n", None)]
+fn test_path_aliases(#[case] input: &[u8], #[case] expected: Option<(AliasRoutes, &str)>) {
+    let result = AliasRoutes::resolve(input).unwrap();
+    assert_eq!(result.route.zip(result.pattern), expected);
+}
+
+#[rstest]
+#[case(AliasRoutes::GetUser { id: 7 }, "/users/7")]
+#[case(AliasRoutes::GetPosts(String::from("ann")), "/u/ann/posts")]
+fn test_path_aliases_to_path(#[case] route: AliasRoutes, #[case] expected: &str) {
+    assert_eq!(route.to_path(), expected);
+}
+
+#[rstest]
+#[case(
+    b"GET /latin/raw/caf\xe9 HTTP/1.1\r\n\r\n",
+    Ok(Some(Latin1Routes::Raw { name: b"caf\xe9" }))
+)]
+#[case(
+    b"GET /latin/names/caf\xe9 HTTP/1.1\r\n\r\n",
+    Ok(Some(Latin1Routes::Name { name: Latin1(String::from("café").into()) }))
+)]
+#[case(
+    b"GET /latin/names/caf\xc3\xa9 HTTP/1.1\r\n\r\n",
+    Ok(Some(Latin1Routes::Name { name: Latin1("café".into()) }))
+)]
+#[case(
+    b"GET /latin/strings/caf\xc3\xa9 HTTP/1.1\r\n\r\n",
+    Ok(Some(Latin1Routes::Text { name: "café" }))
+)]
+#[case(
+    b"GET /latin/strings/caf\xe9 HTTP/1.1\r\n\r\n",
+    Err(Error::NonUtf8Path)
+)]
+#[case(b"GET /latin/caf\xe9 HTTP/1.1\r\n\r\n", Err(Error::NonUtf8Path))]
+fn test_latin1_captures(
+    #[case] input: &[u8],
+    #[case] expected: Result<Option<Latin1Routes>, Error>,
+) {
+    let result = Latin1Routes::resolve(input).map(|result| result.route);
+    assert_eq!(result, expected);
+    let lenient = Latin1Routes::resolve_non_utf8(input).map(|result| result.route);
+    assert_eq!(lenient, Ok(expected.unwrap_or(None)));
+}
+
+#[test]
+fn test_generic_rest_capture() {
+    let result = RestListRoutes::<u8>::resolve(b"GET /items/1/2 HTTP/1.1\r\n\r\n").unwrap();
+    assert_eq!(
+        result.route,
+        Some(RestListRoutes::GetItems { items: vec![1, 2] })
+    );
+    assert_eq!(result.pattern, Some("/items/{**items}"));
+    let result = RestListRoutes::<u8>::resolve(b"GET /items/1/300 HTTP/1.1\r\n\r\n").unwrap();
+    assert_eq!(result.route, None);
+}
+
+#[rstest]
+#[case(b"GET /strict/users HTTP/1.1\r\n\r\n", Some(StrictRoutes::GetAllUsers))]
+#[case(b"GET /strict/users/ HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /strict/users/42 HTTP/1.1\r\n\r\n", Some(StrictRoutes::GetUser { id: 42 }))]
+#[case(b"GET /strict/users/42/ HTTP/1.1\r\n\r\n", None)]
+#[case(
+    b"GET /strict/admins/ HTTP/1.1\r\n\r\n",
+    Some(StrictRoutes::GetAllAdmins)
+)]
+#[case(b"GET /strict/admins HTTP/1.1\r\n\r\n", None)]
+#[case(b"POST /strict/users HTTP/1.1\r\n\r\n", Some(StrictRoutes::CreateUser))]
+#[case(b"POST /strict/users/ HTTP/1.1\r\n\r\n", None)]
+fn test_strict_trailing_slash_routes(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<StrictRoutes>,
+) {
+    let result = StrictRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(b"GET /api/users HTTP/1.1\r\n\r\n", Some(ApiRoutes::GetAllUsers))]
+#[case(b"GET /api/users/ HTTP/1.1\r\n\r\n", Some(ApiRoutes::GetAllUsers))]
+#[case(b"GET /api/users/42 HTTP/1.1\r\n\r\n", Some(ApiRoutes::GetUser { id: 42 }))]
+#[case(b"GET /api/users/42/ HTTP/1.1\r\n\r\n", Some(ApiRoutes::GetUser { id: 42 }))]
+fn test_lenient_trailing_slash_routes(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<ApiRoutes>,
+) {
+    let result = ApiRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(b"GET /legacy/users HTTP/1.1\r\n\r\n", Some(LegacyRoutes::GetAllUsers))]
+#[case(b"GET /LEGACY/Users HTTP/1.1\r\n\r\n", Some(LegacyRoutes::GetAllUsers))]
+#[case(
+    b"GET /Legacy/USERS/JohnDoe/Roles HTTP/1.1\r\n\r\n",
+    Some(LegacyRoutes::GetUserRoles { name: "JohnDoe" })
+)]
+#[case(b"GET /legacy/usersx HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /legacy/us\xc3\xa9rs HTTP/1.1\r\n\r\n", None)]
+fn test_case_insensitive_routes(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<LegacyRoutes>,
+) {
+    let result = LegacyRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(b"GET /API/users HTTP/1.1\r\n\r\n")]
+#[case(b"GET /api/Users HTTP/1.1\r\n\r\n")]
+fn test_case_sensitive_routes(#[case] input: &[u8]) {
+    let result = ApiRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(None));
+}
+
+#[rstest]
+#[case(
+    b"GET /people/john%20doe/roles/ad%6Din HTTP/1.1\r\n\r\n",
+    Some(PeopleRoutes::GetPersonRole { name: "john doe".to_owned(), role: "ad%6Din" })
+)]
+#[case(
+    b"GET /people/john/roles/admin HTTP/1.1\r\n\r\n",
+    Some(PeopleRoutes::GetPersonRole { name: "john".to_owned(), role: "admin" })
+)]
+#[case(
+    b"GET /people/J%C3%BCrgen/M%C3%BCller HTTP/1.1\r\n\r\n",
+    Some(PeopleRoutes::GetPerson { first: "Jürgen".to_owned(), last: "Müller".to_owned() })
+)]
+#[case(b"GET /people/john%2/roles/admin HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /people/john/doe%zz HTTP/1.1\r\n\r\n", None)]
+fn test_decoded_routes(#[case] input: &[u8], #[case] expected_route: Option<PeopleRoutes>) {
+    let result = PeopleRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case("two words")]
+#[case("a/b")]
+#[case("why?")]
+#[case("this&that")]
+#[case("x=1")]
+#[case("#1")]
+#[case("100% a+b")]
+#[case("/?&=# ")]
+#[case("x.y")]
+fn test_to_path_round_trips(#[case] value: &str) {
+    let routes = [
+        EncodedRoutes::GetNote {
+            title: value.to_owned(),
+            version: 3,
+        },
+        EncodedRoutes::GetFile {
+            path: value.to_owned(),
+        },
+        EncodedRoutes::Search {
+            q: value.to_owned(),
+            page: 2,
+        },
+        EncodedRoutes::Split {
+            a: value.to_owned(),
+            b: value.to_owned(),
+        },
+        EncodedRoutes::List {
+            q: value.to_owned(),
+            page: None,
+        },
+        EncodedRoutes::List {
+            q: value.to_owned(),
+            page: Some(2),
+        },
+    ];
+    for route in routes {
+        let request = format!("GET {} HTTP/1.1\r\n\r\n", route.to_path());
+        let resolved = EncodedRoutes::resolve(request.as_bytes()).unwrap().route;
+        assert_eq!(resolved, Some(route));
+    }
+}
+
+#[rstest]
+#[case(
+    EncodedRoutes::Split { a: "x.y".to_owned(), b: "z".to_owned() },
+    "/split/x%2Ey.z"
+)]
+#[case(
+    EncodedRoutes::List { q: "a".to_owned(), page: None },
+    "/list?q=a"
+)]
+#[case(
+    EncodedRoutes::List { q: "a".to_owned(), page: Some(2) },
+    "/list?q=a&page=2"
+)]
+fn test_to_path_encoded(#[case] route: EncodedRoutes, #[case] expected: &str) {
+    assert_eq!(route.to_path(), expected);
+}
+
+#[rstest]
+#[case(
+    b"GET /search?q=foo&page=2 HTTP/1.1\r\n\r\n",
+    Some(SearchRoutes::Search { q: "foo", page: 2 })
+)]
+#[case(
+    b"GET /search?page=2&q=foo HTTP/1.1\r\n\r\n",
+    Some(SearchRoutes::Search { q: "foo", page: 2 })
+)]
+#[case(
+    b"GET /search?other=1&q=foo&page=2&q=bar HTTP/1.1\r\n\r\n",
+    Some(SearchRoutes::Search { q: "foo", page: 2 })
+)]
+#[case(
+    b"GET /search?q&page=2 HTTP/1.1\r\n\r\n",
+    Some(SearchRoutes::Search { q: "", page: 2 })
+)]
+#[case(b"GET /search?q=foo HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /search?q=foo&page=two HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /search HTTP/1.1\r\n\r\n", None)]
+#[case(
+    b"GET /users/42/posts?tag=rust HTTP/1.1\r\n\r\n",
+    Some(SearchRoutes::GetUserPosts { id: 42, tag: "rust" })
+)]
+#[case(
+    b"GET /posts HTTP/1.1\r\n\r\n",
+    Some(SearchRoutes::ListPosts { page: None })
+)]
+#[case(
+    b"GET /posts?page=3 HTTP/1.1\r\n\r\n",
+    Some(SearchRoutes::ListPosts { page: Some(3) })
+)]
+#[case(
+    b"GET /posts?page=three HTTP/1.1\r\n\r\n",
+    Some(SearchRoutes::ListPosts { page: None })
+)]
+fn test_query_routes(#[case] input: &[u8], #[case] expected_route: Option<SearchRoutes>) {
+    let result = SearchRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(
+    Method::Get,
+    "/search?page=2&q=rust",
+    Some(SearchRoutes::Search { q: "rust", page: 2 })
+)]
+#[case(
+    Method::Get,
+    "/users/42/posts?tag=rust",
+    Some(SearchRoutes::GetUserPosts { id: 42, tag: "rust" })
+)]
+#[case(Method::Get, "/items/7", Some(SearchRoutes::GetItem { id: Some(7) }))]
+#[case(Method::Get, "/search?q=rust", None)]
+#[case(Method::Post, "/items/7", None)]
+fn test_resolve_parts(
+    #[case] method: Method,
+    #[case] path: &str,
+    #[case] expected_route: Option<SearchRoutes>,
+) {
+    assert_eq!(SearchRoutes::resolve_parts(method, path), expected_route);
+}
+
+#[rstest]
+#[case(b"GET /files/42 HTTP/1.1\r\n\r\n", Some(FileRoutes::GetFile { id: 42 }))]
+#[case(b"GET /files/latest HTTP/1.1\r\n\r\n", Some(FileRoutes::GetLatestFile))]
+#[case(
+    b"GET /files/abc123 HTTP/1.1\r\n\r\n",
+    Some(FileRoutes::GetNamedFile { name: "abc123" })
+)]
+#[case(
+    b"GET /files/99999999999999999999 HTTP/1.1\r\n\r\n",
+    Some(FileRoutes::GetNamedFile { name: "99999999999999999999" })
+)]
+#[case(b"GET /files/abc-123 HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /files/ HTTP/1.1\r\n\r\n", None)]
+#[case(
+    b"DELETE /files/42/versions/3 HTTP/1.1\r\n\r\n",
+    Some(FileRoutes::DeleteFileVersion { id: 42, version: 3 })
+)]
+#[case(b"DELETE /files/42/versions/v3 HTTP/1.1\r\n\r\n", None)]
+#[case(b"DELETE /files/-42/versions/3 HTTP/1.1\r\n\r\n", None)]
+fn test_constrained_routes(#[case] input: &[u8], #[case] expected_route: Option<FileRoutes>) {
+    let result = FileRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(
+    b"GET /status HTTP/1.1\r\nHost: api.example.com\r\n\r\n",
+    Some(SiteRoutes::Api(ApiSiteRoutes::GetStatus))
+)]
+#[case(
+    b"GET /users/42 HTTP/1.1\r\nhost: API.example.com:8443\r\n\r\n",
+    Some(SiteRoutes::Api(ApiSiteRoutes::GetUser { id: "42" }))
+)]
+#[case(
+    b"GET /status HTTP/1.1\r\nHost: www.example.com\r\n\r\n",
+    Some(SiteRoutes::Www(WwwSiteRoutes::GetStatus))
+)]
+#[case(
+    b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n",
+    Some(SiteRoutes::Www(WwwSiteRoutes::GetIndex))
+)]
+#[case(
+    b"GET http://api.example.com/status HTTP/1.1\r\nHost: www.example.com\r\n\r\n",
+    Some(SiteRoutes::Api(ApiSiteRoutes::GetStatus))
+)]
+#[case(
+    b"GET /health HTTP/1.1\r\nHost: other.example.com\r\n\r\n",
+    Some(SiteRoutes::Health)
+)]
+#[case(b"GET /health HTTP/1.0\r\n\r\n", Some(SiteRoutes::Health))]
+#[case(b"GET /users/42 HTTP/1.1\r\nHost: www.example.com\r\n\r\n", None)]
+#[case(b"GET /status HTTP/1.1\r\nHost: other.example.com\r\n\r\n", None)]
+#[case(b"GET /status HTTP/1.0\r\n\r\n", None)]
+fn test_host_routes(#[case] input: &[u8], #[case] expected_route: Option<SiteRoutes>) {
+    let result = SiteRoutes::resolve_with_host(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(b"GET / HTTP/1.1\r\nX-Note: hi\r\n Host: api.example.com\r\n\r\n")]
+#[case(b"GET / HTTP/1.1\r\nHost : api.example.com\r\n\r\n")]
+#[case(b"GET / HTTP/1.1\r\nHost: api.example.com\r\nHost: www.example.com\r\n\r\n")]
+#[case(b"GET / HTTP/1.1\r\nHost: api.example.com\r\nhost: api.example.com\r\n\r\n")]
+#[case(b"GET / HTTP/1.1\r\nHost: api.example.com www.example.com\r\n\r\n")]
+fn test_host_routes_invalid_host(#[case] input: &[u8]) {
+    let result = SiteRoutes::resolve_with_host(input).map(|result| result.route);
+    assert_eq!(result, Err(Error::InvalidHost));
+}
+
+#[rstest]
+#[case(b"GET /status HTTP/1.1\r\nHost: api.example.com\r\n\r\n", None)]
+#[case(
+    b"GET /health HTTP/1.1\r\nHost: api.example.com\r\n\r\n",
+    Some(SiteRoutes::Health)
+)]
+fn test_host_routes_without_host(#[case] input: &[u8], #[case] expected_route: Option<SiteRoutes>) {
+    let result = SiteRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[test]
+fn test_declared_hosts() {
+    assert_eq!(ApiSiteRoutes::HOSTS, ["api.example.com"]);
+    assert_eq!(WwwSiteRoutes::HOSTS, ["www.example.com", "example.com"]);
+    assert!(SiteRoutes::HOSTS.is_empty());
+}
+
+#[rstest]
+#[case(
+    b"GET /users/me HTTP/1.1\r\n\r\n",
+    Some(SpecificityRoutes::CurrentUser)
+)]
+#[case(
+    b"GET /users/42 HTTP/1.1\r\n\r\n",
+    Some(SpecificityRoutes::NumberedUser { id: 42 })
+)]
+#[case(
+    b"GET /users/alice HTTP/1.1\r\n\r\n",
+    Some(SpecificityRoutes::User { id: "alice" })
+)]
+#[case(
+    b"GET /users/alice.json HTTP/1.1\r\n\r\n",
+    Some(SpecificityRoutes::UserJson { id: "alice" })
+)]
+#[case(
+    b"GET /users/me/posts HTTP/1.1\r\n\r\n",
+    Some(SpecificityRoutes::CurrentUserPosts)
+)]
+#[case(
+    b"GET /users/alice/posts HTTP/1.1\r\n\r\n",
+    Some(SpecificityRoutes::UserPosts { id: "alice" })
+)]
+#[case(
+    b"GET /users/alice/friends HTTP/1.1\r\n\r\n",
+    Some(SpecificityRoutes::UserPage { rest: "alice/friends" })
+)]
+#[case(b"GET /user HTTP/1.1\r\n\r\n", None)]
+fn test_specificity_routes(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<SpecificityRoutes>,
+) {
+    let result = SpecificityRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(
+    b"GET /shared/api/v1/users/42 HTTP/1.1\r\n\r\n",
+    Some(SharedPrefixRoutes::GetUser { id: 42 })
+)]
+#[case(
+    b"GET /shared/api/v1/users/me HTTP/1.1\r\n\r\n",
+    Some(SharedPrefixRoutes::GetApiFallback { rest: "v1/users/me" })
+)]
+#[case(
+    b"GET /shared/api/v1/teams HTTP/1.1\r\n\r\n",
+    Some(SharedPrefixRoutes::GetTeams)
+)]
+#[case(
+    b"GET /shared/api/v2/teams HTTP/1.1\r\n\r\n",
+    Some(SharedPrefixRoutes::GetApiFallback { rest: "v2/teams" })
+)]
+#[case(
+    b"POST /shared/api/v1/teams HTTP/1.1\r\n\r\n",
+    Some(SharedPrefixRoutes::CreateTeam)
+)]
+#[case(
+    b"GET /shared/api HTTP/1.1\r\n\r\n",
+    Some(SharedPrefixRoutes::GetApiIndex)
+)]
+#[case(
+    b"GET /shared/api/v1/teams/ HTTP/1.1\r\n\r\n",
+    Some(SharedPrefixRoutes::GetTeams)
+)]
+#[case(
+    b"GET /shared/api/ HTTP/1.1\r\n\r\n",
+    Some(SharedPrefixRoutes::GetApiIndex)
+)]
+#[case(
+    b"GET /shared/api/v1/teams/7 HTTP/1.1\r\n\r\n",
+    Some(SharedPrefixRoutes::GetTeam { id: 7 })
+)]
+#[case(b"POST /shared/api/v1/users/42 HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /shared/apis/v1/teams HTTP/1.1\r\n\r\n", None)]
+fn test_shared_prefix_routes(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<SharedPrefixRoutes>,
+) {
+    let result = SharedPrefixRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(
+    b"GET /health HTTP/1.1\r\n\r\n",
+    Some(StructRoutes::Health(HealthRoute)),
+    Some("/health")
+)]
+#[case(
+    b"PUT /things/lamp HTTP/1.1\r\n\r\n",
+    Some(StructRoutes::Thing(ThingRoute { name: "lamp" })),
+    Some("/things/{name}")
+)]
+#[case(
+    b"GET /widgets/7 HTTP/1.1\r\n\r\n",
+    Some(StructRoutes::Widget(WidgetRoute(7))),
+    Some(r"/widgets/{id:\d+}")
+)]
+#[case(b"POST /health HTTP/1.1\r\n\r\n", None, None)]
+#[case(b"GET /widgets/seven HTTP/1.1\r\n\r\n", None, None)]
+fn test_struct_routes(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<StructRoutes>,
+    #[case] expected_pattern: Option<&str>,
+) {
+    let result = StructRoutes::resolve(input).unwrap();
+    assert_eq!(result.route, expected_route);
+    assert_eq!(result.pattern, expected_pattern);
+}
+
+#[test]
+fn test_struct_handle_parsed() {
+    assert_eq!(
+        ThingRoute::handle_parsed(Method::Get, "/things/lamp"),
+        Some(ThingRoute { name: "lamp" })
+    );
+    assert_eq!(HealthRoute::handle_parsed(Method::Get, "/nope"), None);
+}
+
+#[rstest]
+#[case(StructRoutes::Health(HealthRoute), "/health")]
+#[case(StructRoutes::Thing(ThingRoute { name: "lamp" }), "/things/lamp")]
+#[case(StructRoutes::Widget(WidgetRoute(7)), "/widgets/7")]
+fn test_struct_to_path(#[case] route: StructRoutes, #[case] expected: &str) {
+    assert_eq!(route.to_path(), expected);
+}
+
+#[rstest]
+#[case(
+    Method::Get,
+    "/v1/api/users/42",
+    (Some(VersionedRoutes::Api(ApiRoutes::GetUser { id: 42 })), "")
+)]
+#[case(Method::Get, "/v1/api/users/42/nothing", (None, "/nothing"))]
+#[case(Method::Get, "/v1/api/users/42/roles", (None, "/roles"))]
+#[case(Method::Delete, "/v1/api/users/42", (None, "/users/42"))]
+#[case(Method::Get, "/v1/unknown", (None, "unknown"))]
+#[case(Method::Get, "/v2/api/users", (None, "/v2/api/users"))]
+fn test_partial_routes(
+    #[case] method: Method,
+    #[case] path: &str,
+    #[case] expected: (Option<VersionedRoutes>, &str),
+) {
+    assert_eq!(
+        VersionedRoutes::handle_parsed_partial(method, path, ""),
+        expected
+    );
+}
+
+#[rstest]
+#[case(
+    b"OPTIONS * HTTP/1.1\r\n\r\n",
+    Ok((Some(ServerRoutes::ServerOptions), Some("*")))
+)]
+#[case(
+    b"OPTIONS /server/status HTTP/1.1\r\n\r\n",
+    Ok((Some(ServerRoutes::StatusOptions), Some("/server/status")))
+)]
+#[case(b"OPTIONS /server/* HTTP/1.1\r\n\r\n", Ok((None, None)))]
+#[case(b"GET * HTTP/1.1\r\n\r\n", Err(Error::MalformedRequestTarget))]
+fn test_asterisk_routes(
+    #[case] input: &[u8],
+    #[case] expected: Result<(Option<ServerRoutes>, Option<&str>), Error>,
+) {
+    let result = ServerRoutes::resolve(input).map(|result| (result.route, result.pattern));
+    assert_eq!(result, expected);
+}
+
+#[rstest]
+#[case(
+    b"GET /server/status HTTP/1.1\r\n\r\n",
+    TargetForm::Origin,
+    Some(ServerRoutes::GetStatus)
+)]
+#[case(
+    b"GET http://example.com/server/status HTTP/1.1\r\n\r\n",
+    TargetForm::Absolute,
+    Some(ServerRoutes::GetStatus)
+)]
+#[case(
+    b"OPTIONS * HTTP/1.1\r\n\r\n",
+    TargetForm::Asterisk,
+    Some(ServerRoutes::ServerOptions)
+)]
+#[case(
+    b"CONNECT example.com:443 HTTP/1.1\r\n\r\n",
+    TargetForm::Authority,
+    None
+)]
+#[case(b"GET example.com:443 HTTP/1.1\r\n\r\n", TargetForm::Authority, None)]
+fn test_target_form(
+    #[case] input: &[u8],
+    #[case] expected_form: TargetForm,
+    #[case] expected_route: Option<ServerRoutes>,
+) {
+    let result = ServerRoutes::resolve(input).unwrap();
+    assert_eq!(result.target_form, expected_form);
+    assert_eq!(result.route, expected_route);
+    assert_eq!(result.into_owned().target_form, expected_form);
+}
+
+#[rstest]
+#[case(ServerRoutes::ServerOptions, "*")]
+#[case(ServerRoutes::StatusOptions, "/server/status")]
+fn test_asterisk_to_path(#[case] route: ServerRoutes, #[case] expected: &str) {
+    assert_eq!(route.to_path(), expected);
+}
+
+#[rstest]
+#[case(
+    b"GET /pages/about HTTP/1.1\r\n\r\n",
+    PageRoutes::GetAbout,
+    Some("/pages/about")
+)]
+#[case(
+    b"GET /pages/missing HTTP/1.1\r\n\r\n",
+    PageRoutes::NotFound { path: "/pages/missing" },
+    None
+)]
+#[case(
+    b"POST /pages/about HTTP/1.1\r\n\r\n",
+    PageRoutes::NotFound { path: "/pages/about" },
+    None
+)]
+#[case(
+    b"GET /other?key=value HTTP/1.1\r\n\r\n",
+    PageRoutes::NotFound { path: "/other" },
+    None
+)]
+fn test_fallback_routes(
+    #[case] input: &[u8],
+    #[case] expected_route: PageRoutes,
+    #[case] expected_pattern: Option<&str>,
+) {
+    let result = PageRoutes::resolve(input).unwrap();
+    assert_eq!(result.route, Some(expected_route));
+    assert_eq!(result.pattern, expected_pattern);
+}
+
+#[rstest]
+#[case(b"GET  HTTP/1.1\r\n\r\n", TargetForm::Empty)]
+#[case(b"GET ?page=2 HTTP/1.0\r\n\r\n", TargetForm::Empty)]
+#[case(b"CONNECT example.com:443 HTTP/1.1\r\n\r\n", TargetForm::Authority)]
+fn test_fallback_unroutable_targets(#[case] input: &[u8], #[case] expected_form: TargetForm) {
+    let result = PageRoutes::resolve(input).unwrap();
+    assert_eq!(result.route, None);
+    assert_eq!(result.target_form, expected_form);
+}
+
+#[rstest]
+#[case(b"GET / HTTP/1.1\r\n\r\n", Some(ReadOnlyRoutes::Index))]
+#[case(b"GET /missing HTTP/1.1\r\n\r\n", Some(ReadOnlyRoutes::NotFound))]
+#[case(b"HEAD / HTTP/1.1\r\n\r\n", Some(ReadOnlyRoutes::NotFound))]
+#[case(b"POST / HTTP/1.1\r\n\r\n", None)]
+#[case(b"DELETE /missing HTTP/1.1\r\n\r\n", None)]
+fn test_fallback_method_routes(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<ReadOnlyRoutes>,
+) {
+    let result = ReadOnlyRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[test]
+fn test_fallback_non_utf8_path() {
+    let result = PageRoutes::resolve(b"GET /pages/\xff HTTP/1.1\r\n\r\n");
+    assert_eq!(result.map(|result| result.route), Err(Error::NonUtf8Path));
+}
+
+#[rstest]
+#[case(PageRoutes::GetAbout, "/pages/about")]
+#[case(PageRoutes::NotFound { path: "/other" }, "/other")]
+fn test_fallback_to_path(#[case] route: PageRoutes, #[case] expected: &str) {
+    assert_eq!(route.to_path(), expected);
+}
+
+#[rstest]
+#[case(b"GET /assets/css/\xffmain.css HTTP/1.1\r\n\r\n")]
+#[case(b"GET /assets/versions/\xff/main.css HTTP/1.1\r\n\r\n")]
+#[case(b"GET /shared/api/v1/users/\xff HTTP/1.1\r\n\r\n")]
+#[case(b"GET /\xff HTTP/1.1\r\n\r\n")]
+fn test_non_utf8_captures(#[case] input: &[u8]) {
+    let assets = AssetRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(assets, Err(Error::NonUtf8Path));
+    let shared = SharedPrefixRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(shared, Err(Error::NonUtf8Path));
+}
+
+#[rstest]
+#[case(
+    b"GET /tuples/users/42 HTTP/1.1\r\n\r\n",
+    Some(TupleRoutes::GetUser(42))
+)]
+#[case(
+    b"GET /tuples/users/42/posts/hello HTTP/1.1\r\n\r\n",
+    Some(TupleRoutes::GetUserPost(42, "hello"))
+)]
+#[case(b"DELETE /tuples/users/7 HTTP/1.1\r\n\r\n", Some(TupleRoutes::DeleteUser { id: 7 }))]
+#[case(b"GET /tuples/users/abc HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /tuples/users/abc/posts/hello HTTP/1.1\r\n\r\n", None)]
+fn test_positional_capture_routes(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<TupleRoutes>,
+) {
+    let result = TupleRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(TupleRoutes::GetUser(42), "/tuples/users/42")]
+#[case(TupleRoutes::GetUserPost(42, "hello"), "/tuples/users/42/posts/hello")]
+fn test_positional_to_path(#[case] route: TupleRoutes, #[case] expected: &str) {
+    assert_eq!(route.to_path(), expected);
+}
+
+#[rstest]
+#[case(b"GET /items/42 HTTP/1.1\r\n\r\n", Some(SearchRoutes::GetItem { id: Some(42) }))]
+#[case(b"GET /items/ HTTP/1.1\r\n\r\n", Some(SearchRoutes::GetItem { id: None }))]
+#[case(b"GET /items/abc HTTP/1.1\r\n\r\n", Some(SearchRoutes::GetItem { id: None }))]
+fn test_optional_capture_routes(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<SearchRoutes>,
+) {
+    let result = SearchRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(b"GET /resource HTTP/1.1\r\n\r\n", Some(ResourceRoutes::Read))]
+#[case(b"HEAD /resource HTTP/1.1\r\n\r\n", Some(ResourceRoutes::Read))]
+#[case(b"PUT /resource HTTP/1.1\r\n\r\n", Some(ResourceRoutes::Write))]
+#[case(b"PATCH /resource HTTP/1.1\r\n\r\n", Some(ResourceRoutes::Write))]
+#[case(b"DELETE /resource HTTP/1.1\r\n\r\n", Some(ResourceRoutes::Delete))]
+#[case(b"POST /resource HTTP/1.1\r\n\r\n", None)]
+fn test_multiple_method_routes(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<ResourceRoutes>,
+) {
+    let result = ResourceRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(b"GET /health HTTP/1.1\r\n\r\n", Some(HealthRoutes::Health))]
+#[case(b"POST /health HTTP/1.1\r\n\r\n", Some(HealthRoutes::Health))]
+#[case(b"DELETE /health HTTP/1.1\r\n\r\n", Some(HealthRoutes::Health))]
+#[case(b"PATCH /health HTTP/1.1\r\n\r\n", Some(HealthRoutes::Health))]
+#[case(
+    b"PUT /proxy/a/b HTTP/1.1\r\n\r\n",
+    Some(HealthRoutes::Proxy { path: "a/b" })
+)]
+#[case(b"GET /status HTTP/1.1\r\n\r\n", Some(HealthRoutes::Status))]
+#[case(b"POST /status HTTP/1.1\r\n\r\n", None)]
+fn test_any_method_routes(#[case] input: &[u8], #[case] expected_route: Option<HealthRoutes>) {
+    let result = HealthRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[test]
+fn test_any_method_allowed_methods() {
+    assert_eq!(
+        HealthRoutes::allowed_method_set("/health", ""),
+        MethodSet::ALL
+    );
+    assert_eq!(
+        HealthRoutes::allowed_methods("/proxy/a", "").len(),
+        Method::ALL.len()
+    );
+    assert_eq!(HealthRoutes::METHODS, MethodSet::ALL);
+    assert_eq!(HealthRoutes::Proxy { path: "a/b" }.to_path(), "/proxy/a/b");
+}
+
+#[rstest]
+#[case(
+    b"GET /echo HTTP/1.1\r\n\r\n",
+    Some(MethodFieldRoutes::Root { method: Method::Get })
+)]
+#[case(
+    b"TRACE /echo/ HTTP/1.1\r\n\r\n",
+    Some(MethodFieldRoutes::Root { method: Method::Trace })
+)]
+#[case(
+    b"DELETE /echo/proxy/a/b HTTP/1.1\r\n\r\n",
+    Some(MethodFieldRoutes::Proxy { method: Method::Delete, path: "a/b" })
+)]
+#[case(
+    b"POST /echo/items/7 HTTP/1.1\r\n\r\n",
+    Some(MethodFieldRoutes::Item(Method::Post, 7))
+)]
+#[case(b"PUT /echo/items/7 HTTP/1.1\r\n\r\n", None)]
+fn test_method_fields(#[case] input: &[u8], #[case] expected_route: Option<MethodFieldRoutes>) {
+    let result = MethodFieldRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(
+    b"GET /users/john/roles/ HTTP/1.1\r\n\r\n",
+    Some(NonEmptyFieldRoutes::GetUserRole { name: "john", role: "" })
+)]
+#[case(b"GET /users//roles/admin HTTP/1.1\r\n\r\n", None)]
+#[case(
+    b"GET /search?q=rust HTTP/1.1\r\n\r\n",
+    Some(NonEmptyFieldRoutes::Search { q: "rust" })
+)]
+#[case(b"GET /search?q= HTTP/1.1\r\n\r\n", None)]
+fn test_non_empty_fields(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<NonEmptyFieldRoutes>,
+) {
+    let result = NonEmptyFieldRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(
+    b"GET /users/john/roles/admin HTTP/1.1\r\n\r\n",
+    Some(NonEmptyRoutes::GetUserRole { name: "john", role: "admin" })
+)]
+#[case(b"GET /users//roles/admin HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /users/john/roles/ HTTP/1.1\r\n\r\n", None)]
+#[case(
+    b"GET /files/a/b HTTP/1.1\r\n\r\n",
+    Some(NonEmptyRoutes::GetFile { path: "a/b" })
+)]
+#[case(b"GET /files/ HTTP/1.1\r\n\r\n", None)]
+fn test_non_empty_routes(#[case] input: &[u8], #[case] expected_route: Option<NonEmptyRoutes>) {
+    let result = NonEmptyRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(b"GET /api/users/42 HTTP/1.1\r\n\r\n", Some(MergedSlashRoutes::GetUser { id: 42 }))]
+#[case(b"GET /api//users/42 HTTP/1.1\r\n\r\n", Some(MergedSlashRoutes::GetUser { id: 42 }))]
+#[case(b"GET //api///users//42 HTTP/1.1\r\n\r\n", Some(MergedSlashRoutes::GetUser { id: 42 }))]
+#[case(b"GET /api/users/42// HTTP/1.1\r\n\r\n", Some(MergedSlashRoutes::GetUser { id: 42 }))]
+#[case(
+    b"GET /api//users HTTP/1.1\r\n\r\n",
+    Some(MergedSlashRoutes::GetAllUsers)
+)]
+#[case(b"GET /api// HTTP/1.1\r\n\r\n", Some(MergedSlashRoutes::GetIndex))]
+#[case(
+    b"POST //api//users/ HTTP/1.1\r\n\r\n",
+    Some(MergedSlashRoutes::CreateUser)
+)]
+#[case(
+    b"GET /api/users//42//roles/admin HTTP/1.1\r\n\r\n",
+    Some(MergedSlashRoutes::GetUserRole { id: 42, role: "admin" })
+)]
+#[case(
+    b"GET /api//files//a//b HTTP/1.1\r\n\r\n",
+    Some(MergedSlashRoutes::GetFile { path: "a//b" })
+)]
+#[case(b"GET /api/users/42//x HTTP/1.1\r\n\r\n", None)]
+fn test_merged_slashes(#[case] input: &[u8], #[case] expected_route: Option<MergedSlashRoutes>) {
+    let result = MergedSlashRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(b"GET //USERS//42 HTTP/1.1\r\n\r\n", Some(StrictMergedSlashRoutes::GetUser { id: 42 }))]
+#[case(b"GET /users/42/ HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /users/42// HTTP/1.1\r\n\r\n", None)]
+#[case(
+    b"GET /admins// HTTP/1.1\r\n\r\n",
+    Some(StrictMergedSlashRoutes::GetAllAdmins)
+)]
+#[case(b"GET /admins HTTP/1.1\r\n\r\n", None)]
+fn test_strict_merged_slashes(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<StrictMergedSlashRoutes>,
+) {
+    let result = StrictMergedSlashRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(b"GET /api//users/42 HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /api/users//42 HTTP/1.1\r\n\r\n", None)]
+fn test_unmerged_slashes(#[case] input: &[u8], #[case] expected_route: Option<ApiRoutes>) {
+    let result = ApiRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[test]
+fn test_method_fields_to_path() {
+    let proxy = MethodFieldRoutes::Proxy {
+        method: Method::Post,
+        path: "a/b",
+    };
+    assert_eq!(proxy.to_path(), "/echo/proxy/a/b");
+    assert_eq!(
+        MethodFieldRoutes::Item(Method::Get, 7).to_path(),
+        "/echo/items/7"
+    );
+    assert_eq!(
+        MethodFieldRoutes::Root {
+            method: Method::Get
+        }
+        .to_path(),
+        "/echo/"
+    );
+}
+
+#[rstest]
+#[case(
+    VersionedRoutes::Marketing(MarketingRoutes::RenderSignUp),
+    "/v1/sign-up"
+)]
+#[case(VersionedRoutes::Api(ApiRoutes::GetAllUsers), "/v1/api/users")]
+#[case(VersionedRoutes::Api(ApiRoutes::GetUser { id: 42 }), "/v1/api/users/42")]
+#[case(
+    VersionedRoutes::Api(ApiRoutes::GetUserRole { id: 42, role: "admin" }),
+    "/v1/api/users/42/roles/admin"
+)]
+#[case(
+    VersionedRoutes::Assets(AssetRoutes::GetAsset { path: "css/app/main.css" }),
+    "/v1/assets/css/app/main.css"
+)]
+#[case(
+    VersionedRoutes::People(PeopleRoutes::GetPersonRole {
+        name: "john doe".to_owned(),
+        role: "admin",
+    }),
+    "/v1/people/john%20doe/roles/admin"
+)]
+#[case(VersionedRoutes::Search { q: "foo", page: 2 }, "/v1/search?q=foo&page=2")]
+fn test_to_path(#[case] route: VersionedRoutes, #[case] expected_path: &str) {
+    assert_eq!(route.to_path(), expected_path);
+}
+
+#[rstest]
+#[case("/v1/")]
+#[case("/v1/portal")]
+#[case("/v1/api/users/42/roles/admin")]
+#[case("/v1/assets/versions/3/js/app.js")]
+#[case("/v1/people/J%C3%BCrgen/M%C3%BCller")]
+#[case("/v1/search?q=foo&page=2")]
+fn test_to_path_round_trip(#[case] path: &str) {
+    let request = format!("GET {path} HTTP/1.1\r\n\r\n");
+    let result = VersionedRoutes::resolve(request.as_bytes()).unwrap();
+    assert_eq!(result.route.unwrap().to_path(), path);
+}
+
+#[rstest]
+#[case(b"GET /api/users/42 HTTP/1.1\r\n\r\n", RouteStatus::Matched)]
+#[case(
+    b"DELETE /api/users HTTP/1.1\r\n\r\n",
+    RouteStatus::MethodNotAllowed(vec![Method::Get, Method::Post])
+)]
+#[case(
+    b"POST /api/users/42/roles/admin HTTP/1.1\r\n\r\n",
+    RouteStatus::MethodNotAllowed(vec![Method::Get, Method::Put])
+)]
+#[case(b"POST /api/users/abc HTTP/1.1\r\n\r\n", RouteStatus::NotFound)]
+#[case(b"GET /nowhere HTTP/1.1\r\n\r\n", RouteStatus::NotFound)]
+fn test_route_status(#[case] input: &[u8], #[case] expected_status: RouteStatus) {
+    let (_, status) = AllRoutes::resolve_with_status(input).unwrap();
+    assert_eq!(status, expected_status);
+}
+
+#[rstest]
+#[case("/api/users", MethodSet::from_methods(&[Method::Get, Method::Post]))]
+#[case("/api/users/42/roles/admin", MethodSet::from_methods(&[Method::Get, Method::Put]))]
+#[case("/log-in", MethodSet::from_methods(&[Method::Get, Method::Post]))]
+#[case("/nowhere", MethodSet::EMPTY)]
+fn test_allowed_method_set(#[case] path: &str, #[case] expected: MethodSet) {
+    assert_eq!(AllRoutes::allowed_method_set(path, ""), expected);
+}
+
+fn resolved_methods<'de, R: Router<'de>>(path: &'de str) -> MethodSet {
+    Method::ALL
+        .into_iter()
+        .filter(|method| R::resolve_parts(*method, path).is_some())
+        .collect()
+}
+
+#[rstest]
+#[case("/api/users")]
+#[case("/api/users/42/roles/admin")]
+#[case("/docs/latest")]
+#[case("/docs/readme")]
+#[case("/docs")]
+#[case("/console")]
+#[case("/console/admin/users")]
+#[case("/console/metrics")]
+#[case("/pages/about")]
+#[case("/server/status")]
+#[case("*")]
+#[case("/")]
+#[case("/nowhere")]
+fn test_allowed_method_set_matches_resolve(#[case] path: &'static str) {
+    assert_eq!(
+        AllRoutes::allowed_method_set(path, ""),
+        resolved_methods::<AllRoutes>(path)
+    );
+    assert_eq!(
+        DocumentRoutes::allowed_method_set(path, ""),
+        resolved_methods::<DocumentRoutes>(path)
+    );
+    assert_eq!(
+        ConsoleRoutes::allowed_method_set(path, ""),
+        resolved_methods::<ConsoleRoutes>(path)
+    );
+    assert_eq!(
+        PageRoutes::allowed_method_set(path, ""),
+        resolved_methods::<PageRoutes>(path)
+    );
+    assert_eq!(
+        ReadOnlyRoutes::allowed_method_set(path, ""),
+        resolved_methods::<ReadOnlyRoutes>(path)
+    );
+    assert_eq!(
+        ServerRoutes::allowed_method_set(path, ""),
+        resolved_methods::<ServerRoutes>(path)
+    );
+}
+
+#[rstest]
+#[case(AllRoutes::METHODS, MethodSet::from_methods(&[Method::Get, Method::Post, Method::Put]))]
+#[case(ServerRoutes::METHODS, MethodSet::from_methods(&[Method::Get, Method::Options]))]
+#[case(
+    DocumentRoutes::METHODS,
+    MethodSet::from_methods(&[Method::Get, Method::Post, Method::Head])
+)]
+#[case(ReadOnlyRoutes::METHODS, MethodSet::from_methods(&[Method::Get, Method::Head]))]
+#[case(PageRoutes::METHODS, MethodSet::ALL)]
+#[case(<MetricsRoute as Router>::METHODS, MethodSet::ALL)]
+fn test_declared_methods(#[case] methods: MethodSet, #[case] expected: MethodSet) {
+    assert_eq!(methods, expected);
+}
+
+#[rstest]
+#[case(b"GET /v1/ HTTP/1.1\r\n\r\n", Some("/v1/"))]
+#[case(b"POST /v1/sign-up HTTP/1.1\r\n\r\n", Some("/v1/sign-up"))]
+#[case(b"GET /v1/api/users/42 HTTP/1.1\r\n\r\n", Some("/v1/api/users/{id}"))]
+#[case(
+    b"PUT /v1/api/users/42/roles/admin HTTP/1.1\r\n\r\n",
+    Some("/v1/api/users/{id}/roles/{role}")
+)]
+#[case(
+    b"GET /v1/assets/css/app.css HTTP/1.1\r\n\r\n",
+    Some("/v1/assets/{*path}")
+)]
+#[case(
+    b"GET /v1/people/alice/roles/admin HTTP/1.1\r\n\r\n",
+    Some("/v1/people/{name}/roles/{role}")
+)]
+#[case(
+    b"GET /v1/search?q=foo&page=2 HTTP/1.1\r\n\r\n",
+    Some("/v1/search?{q}&{page}")
+)]
+#[case(b"GET /v1/nowhere HTTP/1.1\r\n\r\n", None)]
+#[case(
+    b"GET http://example.com/v1/api/users/42 HTTP/1.1\r\n\r\n",
+    Some("/v1/api/users/{id}")
+)]
+fn test_route_patterns(#[case] input: &[u8], #[case] expected_pattern: Option<&str>) {
+    let result = VersionedRoutes::resolve(input).map(|result| result.pattern);
+    assert_eq!(result, Ok(expected_pattern));
+}
+
+#[test]
+fn test_declared_patterns() {
+    assert_eq!(
+        ApiRoutes::PATTERNS,
+        [
+            "/api/users",
+            "/api/users/{id}",
+            "/api/users/{id}/roles/{role}"
+        ]
+    );
+    assert_eq!(ResourceRoutes::PATTERNS, ["/resource"]);
+}
+
+#[rstest]
+#[case(ApiRoutes::PREFIX, "/api")]
+#[case(PrefixedRootRoutes::PREFIX, "/api")]
+#[case(TenantRoutes::PREFIX, "/tenants/{tenant}")]
+#[case(MarketingRoutes::PREFIX, "")]
+#[case(HealthRoute::PREFIX, "")]
+#[case(<MetricsRoute as Router>::PREFIX, "")]
+fn test_declared_prefix(#[case] prefix: &str, #[case] expected: &str) {
+    assert_eq!(prefix, expected);
+}
+
+#[test]
+fn test_declared_routes() {
+    assert_eq!(
+        ApiRoutes::ROUTES,
+        [
+            (Method::Get, "/api/users"),
+            (Method::Post, "/api/users"),
+            (Method::Get, "/api/users/{id}"),
+            (Method::Get, "/api/users/{id}/roles/{role}"),
+            (Method::Put, "/api/users/{id}/roles/{role}"),
+        ]
+    );
+    assert_eq!(
+        StructRoutes::ROUTES,
+        [
+            (Method::Get, "/health"),
+            (Method::Get, "/things/{name}"),
+            (Method::Put, "/things/{name}"),
+            (Method::Get, r"/widgets/{id:\d+}"),
+        ]
+    );
+    assert_eq!(
+        &CompatRoutes::ROUTES[..3],
+        [
+            (Method::Get, "/compat/v1/users"),
+            (Method::Get, "/compat/v1/{version}/status"),
+            (Method::Get, "/compat/v1/api/users"),
+        ]
+    );
+    assert_eq!(CompatRoutes::ROUTES.len(), 14);
+    assert_eq!(ServerRoutes::ROUTES[0], (Method::Options, "*"));
+}
+
+#[rstest]
+#[case(
+    b"GET /compat/users HTTP/1.1\r\n\r\n",
+    Some(CompatRoutes::GetAllUsers),
+    Some("/compat/users")
+)]
+#[case(
+    b"GET /compat/v1/users HTTP/1.1\r\n\r\n",
+    Some(CompatRoutes::GetAllUsers),
+    Some("/compat/v1/users")
+)]
+#[case(
+    b"GET /compat/v2/status HTTP/1.1\r\n\r\n",
+    Some(CompatRoutes::GetStatus { version: "v2" }),
+    Some("/compat/{version}/status")
+)]
+#[case(
+    b"GET /compat/v1/status HTTP/1.1\r\n\r\n",
+    Some(CompatRoutes::GetStatus { version: "v1" }),
+    Some("/compat/{version}/status")
+)]
+#[case(
+    b"GET /compat/v1/api/users/42 HTTP/1.1\r\n\r\n",
+    Some(CompatRoutes::Api(ApiRoutes::GetUser { id: 42 })),
+    Some("/compat/v1/api/users/{id}")
+)]
+#[case(
+    b"POST /compat/api/users HTTP/1.1\r\n\r\n",
+    Some(CompatRoutes::Api(ApiRoutes::CreateUser)),
+    Some("/compat/api/users")
+)]
+#[case(b"GET /users HTTP/1.1\r\n\r\n", None, None)]
+#[case(b"GET /compat/v1/nowhere HTTP/1.1\r\n\r\n", None, None)]
+fn test_multiple_prefix_routes(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<CompatRoutes>,
+    #[case] expected_pattern: Option<&str>,
+) {
+    let result = CompatRoutes::resolve(input).map(|result| (result.route, result.pattern));
+    assert_eq!(result, Ok((expected_route, expected_pattern)));
+}
+
+#[test]
+fn test_multiple_prefix_to_path() {
+    assert_eq!(CompatRoutes::GetAllUsers.to_path(), "/compat/users");
+    assert_eq!(
+        CompatRoutes::Api(ApiRoutes::GetUser { id: 42 }).to_path(),
+        "/compat/api/users/42"
+    );
+}
+
+#[rstest]
+#[case(
+    b"GET /files/a.json HTTP/1.1\r\n\r\n",
+    Some(MixedSegmentRoutes::GetJsonFile { name: "a" })
+)]
+#[case(
+    b"GET /files/backup.tar.gz HTTP/1.1\r\n\r\n",
+    Some(MixedSegmentRoutes::GetArchive { name: "backup" })
+)]
+#[case(
+    b"GET /v2/users HTTP/1.1\r\n\r\n",
+    Some(MixedSegmentRoutes::GetUsers { version: 2 })
+)]
+#[case(
+    b"GET /reports/2023-11/summary HTTP/1.1\r\n\r\n",
+    Some(MixedSegmentRoutes::GetSummary { year: 2023, month: 11 })
+)]
+#[case(
+    b"PUT /files/backup.tar.gz HTTP/1.1\r\n\r\n",
+    Some(MixedSegmentRoutes::PutArchive { name: "backup" })
+)]
+#[case(b"GET /files/a.txt HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /files/a.json.json HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /files/a/b.json HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /files/a.jsonx HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /vx/users HTTP/1.1\r\n\r\n", None)]
+#[case(
+    b"GET /items/42;r7 HTTP/1.1\r\n\r\n",
+    Some(MixedSegmentRoutes::GetItemRevision { id: 42, rev: "r7" })
+)]
+#[case(
+    b"GET /items/42;r7;r8 HTTP/1.1\r\n\r\n",
+    Some(MixedSegmentRoutes::GetItemRevision { id: 42, rev: "r7;r8" })
+)]
+#[case(b"GET /items/42 HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /items/42/;r7 HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /items/x;r7 HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /reports/2023/summary HTTP/1.1\r\n\r\n", None)]
+fn test_mixed_segment_routes(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<MixedSegmentRoutes>,
+) {
+    let result = MixedSegmentRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(
+    b"GET /FILES/a.JSON HTTP/1.1\r\n\r\n",
+    Some(CaseInsensitiveMixedRoutes::GetJsonFile { name: "a" })
+)]
+#[case(b"GET /files/a.txt HTTP/1.1\r\n\r\n", None)]
+fn test_case_insensitive_mixed_segment_routes(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<CaseInsensitiveMixedRoutes>,
+) {
+    let result = CaseInsensitiveMixedRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[test]
+fn test_mixed_segment_to_path() {
+    assert_eq!(
+        MixedSegmentRoutes::GetJsonFile { name: "a" }.to_path(),
+        "/files/a.json"
+    );
+    assert_eq!(
+        MixedSegmentRoutes::GetSummary {
+            year: 2023,
+            month: 11
+        }
+        .to_path(),
+        "/reports/2023-11/summary"
+    );
+}
+
+#[rstest]
+#[case(
+    b"GET /orders/42 HTTP/1.1\r\n\r\n",
+    Some(CheckedRoutes::GetOrder { id: Ok(42) })
+)]
+#[case(
+    b"GET /orders/abc HTTP/1.1\r\n\r\n",
+    Some(CheckedRoutes::GetOrder { id: Err(ParseError::new("u32")) })
+)]
+#[case(
+    b"PUT /orders/42/quantity/3 HTTP/1.1\r\n\r\n",
+    Some(CheckedRoutes::SetQuantity { id: 42, quantity: Ok(Quantity(3)) })
+)]
+#[case(
+    b"PUT /orders/42/quantity/0 HTTP/1.1\r\n\r\n",
+    Some(CheckedRoutes::SetQuantity {
+        id: 42,
+        quantity: Err(ParseError::new("a positive integer"))
+    })
+)]
+#[case(b"PUT /orders/abc/quantity/3 HTTP/1.1\r\n\r\n", None)]
+fn test_checked_routes(#[case] input: &[u8], #[case] expected_route: Option<CheckedRoutes>) {
+    let result = CheckedRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(
+    b"GET /api HTTP/1.1\r\n\r\n",
+    Some(PrefixedRootRoutes::GetRoot),
+    Some("/api/")
+)]
+#[case(
+    b"GET /api/ HTTP/1.1\r\n\r\n",
+    Some(PrefixedRootRoutes::GetRoot),
+    Some("/api/")
+)]
+#[case(
+    b"GET /api/v1 HTTP/1.1\r\n\r\n",
+    Some(PrefixedRootRoutes::GetRoot),
+    Some("/api/v1/")
+)]
+#[case(
+    b"GET /api/v1/ HTTP/1.1\r\n\r\n",
+    Some(PrefixedRootRoutes::GetRoot),
+    Some("/api/v1/")
+)]
+#[case(
+    b"GET /api/users HTTP/1.1\r\n\r\n",
+    Some(PrefixedRootRoutes::GetUsers),
+    Some("/api/users")
+)]
+#[case(b"GET /apis HTTP/1.1\r\n\r\n", None, None)]
+#[case(b"GET / HTTP/1.1\r\n\r\n", None, None)]
+fn test_prefixed_root_routes(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<PrefixedRootRoutes>,
+    #[case] expected_pattern: Option<&str>,
+) {
+    let result = PrefixedRootRoutes::resolve(input).unwrap();
+    assert_eq!(result.route, expected_route);
+    assert_eq!(result.pattern, expected_pattern);
+}
+
+#[rstest]
+#[case(b"GET /admin HTTP/1.1\r\n\r\n", Some(BarePrefixRoutes::GetDashboard))]
+#[case(b"GET /admin/ HTTP/1.1\r\n\r\n", Some(BarePrefixRoutes::GetDashboard))]
+#[case(b"GET /admin/users HTTP/1.1\r\n\r\n", Some(BarePrefixRoutes::GetUsers))]
+#[case(b"GET /admin/other HTTP/1.1\r\n\r\n", None)]
+fn test_bare_prefix_routes(#[case] input: &[u8], #[case] expected_route: Option<BarePrefixRoutes>) {
+    let result = BarePrefixRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[test]
+fn test_bare_prefix_to_path() {
+    assert_eq!(BarePrefixRoutes::GetDashboard.to_path(), "/admin");
+    assert_eq!(BarePrefixRoutes::PATTERNS, ["/admin", "/admin/users"]);
+}
+
+#[rstest]
+#[case(Method::Get, "/api/", Some(StrictPrefixedRootRoutes::GetRoot))]
+#[case(Method::Get, "/api", None)]
+#[case(Method::Post, "/api", Some(StrictPrefixedRootRoutes::PostRoot))]
+#[case(Method::Post, "/api/", None)]
+fn test_strict_prefixed_root_routes(
+    #[case] method: Method,
+    #[case] path: &str,
+    #[case] expected_route: Option<StrictPrefixedRootRoutes>,
+) {
+    assert_eq!(
+        StrictPrefixedRootRoutes::handle_parsed(method, path),
+        expected_route
+    );
+}
+
+#[rstest]
+#[case(b"GET /names/ada HTTP/1.1\r\n\r\n", Some(GenericRoutes::GetName { name: "ada" }))]
+#[case(b"GET /values/-7 HTTP/1.1\r\n\r\n", Some(GenericRoutes::GetValue { value: -7 }))]
+#[case(
+    b"GET /optional/x HTTP/1.1\r\n\r\n",
+    Some(GenericRoutes::GetOptional(None))
+)]
+#[case(
+    b"GET /items/42 HTTP/1.1\r\n\r\n",
+    Some(GenericRoutes::Items(ItemRoutes::GetItem { id: 42 }))
+)]
+#[case(
+    b"GET /sized/abcd HTTP/1.1\r\n\r\n",
+    Some(GenericRoutes::GetSized { value: *b"abcd" })
+)]
+#[case(b"GET /sized/abc HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /values/seven HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /items/-1 HTTP/1.1\r\n\r\n", None)]
+fn test_generic_routes(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<GenericRoutes<i64, 4>>,
+) {
+    let result = GenericRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[test]
+fn test_generic_to_path() {
+    assert_eq!(ItemRoutes::GetItem { id: 7u8 }.to_path(), "/items/7");
+}
+
+#[rstest]
+#[case(
+    b"GET /points/1,2.5,-3 HTTP/1.1\r\n\r\n",
+    Some(GeometryRoutes::GetPoint { coords: [1.0, 2.5, -3.0] })
+)]
+#[case(b"GET /points/1,2 HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /points/1,y,3 HTTP/1.1\r\n\r\n", None)]
+#[case(
+    b"GET /ranges/10,20 HTTP/1.1\r\n\r\n",
+    Some(GeometryRoutes::GetRange { range: (10, 20) })
+)]
+#[case(b"GET /ranges/10,20,30 HTTP/1.1\r\n\r\n", None)]
+fn test_delimited_routes(#[case] input: &[u8], #[case] expected_route: Option<GeometryRoutes>) {
+    let result = GeometryRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(
+    b"GET /drafts/hello HTTP/1.1\r\n\r\n",
+    Some(PublishingRoutes::Drafts(DraftRoutes::GetDraft { title: String::from("hello") }))
+)]
+#[case(
+    b"GET /things/42 HTTP/1.1\r\n\r\n",
+    Some(PublishingRoutes::Things(ThingRoute { name: "42" }))
+)]
+#[case(b"GET /drafts HTTP/1.1\r\n\r\n", None)]
+fn test_owned_and_borrowed_flatten_routes(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<PublishingRoutes>,
+) {
+    let result = PublishingRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[test]
+fn test_owned_flatten_outlives_request() {
+    let request = b"GET /drafts/hello HTTP/1.1\r\n\r\n".to_vec();
+    let route = match PublishingRoutes::resolve(&request).map(|result| result.route) {
+        Ok(Some(PublishingRoutes::Drafts(drafts))) => drafts,
+        other => panic!("expected a draft route, got {other:?}"),
+    };
+    drop(request);
+    assert_eq!(
+        route,
+        DraftRoutes::GetDraft {
+            title: String::from("hello")
+        }
+    );
+}
+
+#[test]
+fn test_owned_result_moves_to_thread() {
+    let request = b"POST /drafts/hello?v=2 HTTP/1.1\r\nContent-Length: 2\r\n\r\nhi".to_vec();
+    let owned = DraftRoutes::resolve(&request).unwrap().into_owned();
+    drop(request);
+    let handle = std::thread::spawn(move || {
+        let result = owned.as_router_result();
+        (
+            result.route.is_some(),
+            result.query_pairs().count(),
+            result.body().map(<[u8]>::to_vec),
+        )
+    });
+    assert_eq!(handle.join().unwrap(), (false, 1, Some(b"hi".to_vec())));
+
+    let request = b"GET /drafts/hello HTTP/1.1\r\n\r\n".to_vec();
+    let owned = PublishingRoutes::resolve(&request)
+        .unwrap()
+        .map_route(|route| match route {
+            PublishingRoutes::Drafts(drafts) => drafts,
+            other => panic!("expected a draft route, got {other:?}"),
+        })
+        .into_owned();
+    drop(request);
+    let expected = DraftRoutes::GetDraft {
+        title: String::from("hello"),
+    };
+    assert_eq!(owned.route, Some(expected));
+    assert_eq!(owned.pattern, Some("/drafts/{title}"));
+}
+
+#[test]
+fn test_resolve_in_shared_buffer() {
+    let buffer: std::sync::Arc<[u8]> = std::sync::Arc::from(
+        &b"GET /drafts/hello?v=2 HTTP/1.1\r\nContent-Length: 5\r\n\r\nworld"[..],
+    );
+    let result = front_line::resolve_in::<DraftRoutes, _>(buffer.clone()).unwrap();
+    let handle = std::thread::spawn(move || {
+        let body = result.buffer()[result.body_range()].to_vec();
+        let query = result.as_router_result().query.to_string();
+        (result.route, query, body)
+    });
+    let expected = DraftRoutes::GetDraft {
+        title: String::from("hello"),
+    };
+    assert_eq!(
+        handle.join().unwrap(),
+        (Some(expected), "v=2".to_string(), b"world".to_vec())
+    );
+}
+
+#[test]
+fn test_resolve_spans() {
+    let request = b"GET /drafts/hello%20world?v=2 HTTP/1.1\r\nHost: a\r\n\r\nbody";
+    let (result, spans) = DraftRoutes::resolve_spans(request).unwrap();
+    assert_eq!(result, DraftRoutes::resolve(request).unwrap());
+    assert_eq!(&request[spans.method], b"GET");
+    assert_eq!(&request[spans.path], b"/drafts/hello%20world");
+    assert_eq!(&request[spans.query], b"v=2");
+    assert_eq!(&request[spans.version], b"HTTP/1.1");
+    assert_eq!(&request[spans.headers], b"Host: a\r\n");
+    assert_eq!(&request[spans.body], b"body");
+}
+
+#[test]
+fn test_empty_request_target() {
+    let request = b"GET  HTTP/1.1\r\n\r\n";
+    assert_eq!(MarketingRoutes::resolve(request).unwrap().route, None);
+    assert_eq!(AllRoutes::resolve(request).unwrap().route, None);
+    assert_eq!(VersionedRoutes::resolve(request).unwrap().route, None);
+    assert_eq!(MethodFieldRoutes::resolve(request).unwrap().route, None);
+    assert_eq!(StrictRoutes::resolve(request).unwrap().route, None);
+    let (result, spans) = MarketingRoutes::resolve_spans(request).unwrap();
+    assert_eq!(result.version, HttpVersion::OneOne);
+    assert_eq!(spans.path, 4..4);
+    assert_eq!(&request[spans.version], b"HTTP/1.1");
+}
+
+#[test]
+fn test_resolve_reader() {
+    use std::io::{BufReader, Read};
+
+    let request = b"PUT /drafts/hello HTTP/1.1\r\nContent-Length: 5\r\n\r\nworld".as_slice();
+    let mut reader = BufReader::with_capacity(8, request);
+    let result = front_line::resolve_reader::<DraftRoutes, _>(&mut reader, 1024).unwrap();
+    assert_eq!(result.route, None);
+    assert_eq!(result.method, Method::Put);
+
+    let request = b"GET /drafts/hello?v=2 HTTP/1.1\r\nContent-Length: 5\r\n\r\nworld".as_slice();
+    let mut reader = BufReader::with_capacity(8, request);
+    let result = front_line::resolve_reader::<DraftRoutes, _>(&mut reader, 1024).unwrap();
+    let expected = DraftRoutes::GetDraft {
+        title: String::from("hello"),
+    };
+    assert_eq!(result.route, Some(expected));
+    assert_eq!(result.query, "v=2");
+    assert_eq!(result.as_router_result().headers().count(), 1);
+    let mut body = Vec::new();
+    reader.read_to_end(&mut body).unwrap();
+    assert_eq!(body, b"world");
+}
+
+#[rstest]
+#[case(
+    b"GET /days/2024-01-31 HTTP/1.1\r\n\r\n",
+    Some(CalendarRoutes::GetDay { date: Date { year: 2024, month: 1, day: 31 } })
+)]
+#[case(
+    b"GET /days/2024-01-31/events/7 HTTP/1.1\r\n\r\n",
+    Some(CalendarRoutes::GetEvent(Date { year: 2024, month: 1, day: 31 }, 7))
+)]
+#[case(
+    b"POST /tags/Rust%20Lang HTTP/1.1\r\n\r\n",
+    Some(CalendarRoutes::FollowTag { tag: "rust lang".to_string() })
+)]
+#[case(b"GET /days/tomorrow HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /days/2024-01 HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /days/2024-01-31/events/x HTTP/1.1\r\n\r\n", None)]
+fn test_parse_with_routes(#[case] input: &[u8], #[case] expected_route: Option<CalendarRoutes>) {
+    let result = CalendarRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(
+    b"GET /accounts/7 HTTP/1.1\r\n\r\n",
+    Some(AccountRoutes::GetAccount { account_id: 7 })
+)]
+#[case(
+    b"GET /accounts/7/members/42?type=admin HTTP/1.1\r\n\r\n",
+    Some(AccountRoutes::GetMember { account_id: 7, member_id: 42, kind: "admin" })
+)]
+#[case(b"GET /accounts/7/members/x?type=admin HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /accounts/7/members/42 HTTP/1.1\r\n\r\n", None)]
+fn test_from_routes(#[case] input: &[u8], #[case] expected_route: Option<AccountRoutes>) {
+    let result = AccountRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(b"GET /bank/v1/status HTTP/1.1\r\n\r\n", Some(BankRoutes::GetStatus))]
+#[case(
+    b"GET /bank/v1/accounts HTTP/1.1\r\n\r\n",
+    Some(BankRoutes::ListAccounts)
+)]
+#[case(
+    b"GET /bank/v1/accounts/7 HTTP/1.1\r\n\r\n",
+    Some(BankRoutes::Account { id: 7 })
+)]
+#[case(
+    b"DELETE /bank/v1/accounts/7 HTTP/1.1\r\n\r\n",
+    Some(BankRoutes::Account { id: 7 })
+)]
+#[case(
+    b"GET /bank/v1/accounts/7/members/ada HTTP/1.1\r\n\r\n",
+    Some(BankRoutes::GetMember { id: 7, member: "ada" })
+)]
+#[case(b"GET /bank/v1/7 HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /bank/v1/members/ada HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /accounts/7 HTTP/1.1\r\n\r\n", None)]
+fn test_variant_prefix_routes(#[case] input: &[u8], #[case] expected_route: Option<BankRoutes>) {
+    let result = BankRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[test]
+fn test_variant_prefix_to_path() {
+    assert_eq!(BankRoutes::ListAccounts.to_path(), "/bank/v1/accounts");
+    assert_eq!(
+        BankRoutes::Account { id: 7 }.to_path(),
+        "/bank/v1/accounts/7"
+    );
+    assert_eq!(
+        BankRoutes::GetMember {
+            id: 7,
+            member: "ada"
+        }
+        .to_path(),
+        "/bank/v1/accounts/7/members/ada"
+    );
+    assert_eq!(
+        BankRoutes::PATTERNS,
+        [
+            "/bank/v1/status",
+            "/bank/v1/accounts",
+            "/bank/v1/accounts/{id}",
+            "/bank/v1/accounts/{id}/members/{member}"
+        ]
+    );
+}
+
+#[rstest]
+#[case(b"GET /api HTTP/1.1\r\n\r\n", Some(RelativeRoutes::Index))]
+#[case(b"GET /api/users/7 HTTP/1.1\r\n\r\n", Some(RelativeRoutes::User { id: 7 }))]
+#[case(b"GET /api/teams HTTP/1.1\r\n\r\n", Some(RelativeRoutes::Teams))]
+#[case(
+    b"GET /api/accounts/7 HTTP/1.1\r\n\r\n",
+    Some(RelativeRoutes::Account { id: 7 })
+)]
+#[case(
+    b"GET /api/admin/users/ada HTTP/1.1\r\n\r\n",
+    Some(RelativeRoutes::Admin(AdminRoutes::GetUser { name: "ada" }))
+)]
+#[case(b"GET /api//users/7 HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /apiusers/7 HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /api/accounts7 HTTP/1.1\r\n\r\n", None)]
+fn test_relative_paths(#[case] input: &[u8], #[case] expected_route: Option<RelativeRoutes>) {
+    let result = RelativeRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[test]
+fn test_relative_paths_are_joined() {
+    assert_eq!(RelativeRoutes::PREFIX, "/api");
+    assert_eq!(RelativeRoutes::User { id: 7 }.to_path(), "/api/users/7");
+    assert_eq!(
+        RelativeRoutes::Account { id: 7 }.to_path(),
+        "/api/accounts/7"
+    );
+    assert_eq!(
+        RelativeRoutes::Admin(AdminRoutes::Dashboard).to_path(),
+        "/api/admin/"
+    );
+    assert_eq!(
+        RelativeRoutes::PATTERNS,
+        [
+            "/api",
+            "/api/users/{id}",
+            "/api/teams",
+            "/api/accounts/{id}",
+            "/api/admin/",
+            "/api/admin/users/{name}"
+        ]
+    );
+}
+
+#[rstest]
+#[case(b"GET /api/status HTTP/1.1\r\n\r\n", Some(GroupedRoutes::Status))]
+#[case(
+    b"GET /api/v1/users/7 HTTP/1.1\r\n\r\n",
+    Some(GroupedRoutes::UserV1 { id: 7 })
+)]
+#[case(b"GET /api/v1/teams HTTP/1.1\r\n\r\n", Some(GroupedRoutes::TeamsV1))]
+#[case(
+    b"GET /api/v2/users/7 HTTP/1.1\r\n\r\n",
+    Some(GroupedRoutes::UserV2 { id: 7 })
+)]
+#[case(
+    b"GET /api/v2/orgs/acme/members HTTP/1.1\r\n\r\n",
+    Some(GroupedRoutes::MembersV2 { org: "acme" })
+)]
+#[case(
+    b"GET /api/v2/admin/users/ada HTTP/1.1\r\n\r\n",
+    Some(GroupedRoutes::AdminV2(AdminRoutes::GetUser { name: "ada" }))
+)]
+#[case(b"GET /api/health HTTP/1.1\r\n\r\n", Some(GroupedRoutes::Health))]
+#[case(b"GET /api/v2/teams HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /api/v2/health HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /api/v1/status HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /api/users/7 HTTP/1.1\r\n\r\n", None)]
+fn test_group_routes(#[case] input: &[u8], #[case] expected_route: Option<GroupedRoutes>) {
+    let result = GroupedRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[test]
+fn test_group_patterns_and_paths() {
+    assert_eq!(
+        GroupedRoutes::PATTERNS,
+        [
+            "/api/status",
+            "/api/v1/users/{id}",
+            "/api/v1/teams",
+            "/api/v2/users/{id}",
+            "/api/v2/orgs/{org}/members",
+            "/api/health",
+            "/api/v2/admin/",
+            "/api/v2/admin/users/{name}"
+        ]
+    );
+    assert_eq!(GroupedRoutes::TeamsV1.to_path(), "/api/v1/teams");
+    assert_eq!(
+        GroupedRoutes::MembersV2 { org: "acme" }.to_path(),
+        "/api/v2/orgs/acme/members"
+    );
+    assert_eq!(
+        GroupedRoutes::AdminV2(AdminRoutes::Dashboard).to_path(),
+        "/api/v2/admin/"
+    );
+}
+
+#[test]
+fn test_route_table() {
+    use Segment::{CatchAll, Literal, Variable};
+    assert_eq!(TableRoutes::ROUTE_TABLE.len(), 12);
+    assert_eq!(
+        TableRoutes::ROUTE_TABLE[0],
+        (Method::Get, &[Literal("api"), Literal("")][..])
+    );
+    assert_eq!(
+        TableRoutes::ROUTE_TABLE[3],
+        (
+            Method::Get,
+            &[
+                Literal("api"),
+                Literal("users"),
+                Variable("id"),
+                Literal("posts"),
+                Variable("post")
+            ][..]
+        )
+    );
+    assert_eq!(
+        TableRoutes::ROUTE_TABLE[11],
+        (
+            Method::Get,
+            &[Literal("v1"), Literal("files"), CatchAll("path")][..]
+        )
+    );
+    assert_eq!(TableRoutes::ROUTE_TABLE_VARIANTS.len(), 12);
+    assert_eq!(TableRoutes::ROUTE_TABLE_VARIANTS[3], "UserPosts");
+}
+
+#[rstest]
+#[case(Method::Get, "/api/", Some(("Index", vec![])))]
+#[case(Method::Post, "/v1/users", Some(("Users", vec![])))]
+#[case(
+    Method::Get,
+    "/api/users/7/posts/3",
+    Some(("UserPosts", vec![("id", "7"), ("post", "3")]))
+)]
+#[case(
+    Method::Get,
+    "/v1/users/7/posts",
+    Some(("UserPosts", vec![("id", "7")]))
+)]
+#[case(
+    Method::Get,
+    "/api/files/a/b.txt",
+    Some(("File", vec![("path", "a/b.txt")]))
+)]
+#[case(Method::Delete, "/api/users", None)]
+#[case(Method::Get, "/users", None)]
+fn test_match_route_table(
+    #[case] method: Method,
+    #[case] path: &str,
+    #[case] expected: Option<(&str, Vec<(&str, &str)>)>,
+) {
+    let matched = match_table(TableRoutes::ROUTE_TABLE, method, path).map(|matched| {
+        let variant = TableRoutes::ROUTE_TABLE_VARIANTS[matched.index];
+        (variant, matched.captures().collect::<Vec<_>>())
+    });
+    assert_eq!(matched, expected);
+}
+
+#[test]
+fn test_from_to_path() {
+    assert_eq!(
+        AccountRoutes::GetMember {
+            account_id: 7,
+            member_id: 42,
+            kind: "admin"
+        }
+        .to_path(),
+        "/accounts/7/members/42?type=admin"
+    );
+    assert_eq!(
+        AccountRoutes::PATTERNS,
+        &["/accounts/{id}", "/accounts/{id}/members/{member}?{type}"]
+    );
+}
+
+#[rstest]
+#[case(
+    b"GET /posts/2024 HTTP/1.1\r\n\r\n",
+    Some(ArchiveRoutes::Posts { year: 2024, month: None, day: None })
+)]
+#[case(
+    b"GET /posts/2024/ HTTP/1.1\r\n\r\n",
+    Some(ArchiveRoutes::Posts { year: 2024, month: None, day: None })
+)]
+#[case(
+    b"GET /posts/2024/5 HTTP/1.1\r\n\r\n",
+    Some(ArchiveRoutes::Posts { year: 2024, month: Some(5), day: None })
+)]
+#[case(
+    b"GET /posts/2024/5/17 HTTP/1.1\r\n\r\n",
+    Some(ArchiveRoutes::Posts { year: 2024, month: Some(5), day: Some(17) })
+)]
+#[case(
+    b"GET /posts/2024/5/17/ HTTP/1.1\r\n\r\n",
+    Some(ArchiveRoutes::Posts { year: 2024, month: Some(5), day: Some(17) })
+)]
+#[case(b"GET /posts/2024/may HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /posts/2024/5/17/18 HTTP/1.1\r\n\r\n", None)]
+#[case(
+    b"GET /pages?sort=new HTTP/1.1\r\n\r\n",
+    Some(ArchiveRoutes::Pages(None, "new"))
+)]
+#[case(
+    b"GET /pages/3?sort=new HTTP/1.1\r\n\r\n",
+    Some(ArchiveRoutes::Pages(Some(3), "new"))
+)]
+#[case(b"GET /pages/x?sort=new HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET / HTTP/1.1\r\n\r\n", Some(ArchiveRoutes::Page { slug: None }))]
+#[case(b"GET /about HTTP/1.1\r\n\r\n", Some(ArchiveRoutes::Page { slug: Some("about") }))]
+fn test_optional_segment_routes(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<ArchiveRoutes>,
+) {
+    let result = ArchiveRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result, Ok(expected_route));
+}
+
+#[rstest]
+#[case(ArchiveRoutes::Posts { year: 2024, month: None, day: None }, "/posts/2024")]
+#[case(ArchiveRoutes::Posts { year: 2024, month: Some(5), day: None }, "/posts/2024/5")]
+#[case(ArchiveRoutes::Posts { year: 2024, month: Some(5), day: Some(17) }, "/posts/2024/5/17")]
+#[case(ArchiveRoutes::Posts { year: 2024, month: None, day: Some(17) }, "/posts/2024")]
+#[case(ArchiveRoutes::Pages(None, "new"), "/pages?sort=new")]
+#[case(ArchiveRoutes::Pages(Some(3), "new"), "/pages/3?sort=new")]
+#[case(ArchiveRoutes::Page { slug: None }, "/")]
+#[case(ArchiveRoutes::Page { slug: Some("about") }, "/about")]
+fn test_optional_segment_to_path(#[case] route: ArchiveRoutes, #[case] expected: &str) {
+    assert_eq!(route.to_path(), expected);
+}
+
+#[test]
+fn test_optional_segment_patterns() {
+    assert_eq!(
+        ArchiveRoutes::PATTERNS,
+        &[
+            "/posts/{year}/{month?}/{day?}",
+            r"/pages/{page?:\d+}?{sort}",
+            "/{slug?}"
+        ]
+    );
+    let result = ArchiveRoutes::resolve(b"GET /posts/2024/5 HTTP/1.1\r\n\r\n");
+    assert_eq!(
+        result.map(|result| result.pattern),
+        Ok(Some("/posts/{year}/{month?}/{day?}"))
+    );
+}
+
+#[rstest]
+#[case(
+    b"GET /console/ HTTP/1.1\r\n\r\n",
+    Some(ConsoleRoutes::Home),
+    Some("/console/")
+)]
+#[case(
+    b"GET /console/admin HTTP/1.1\r\n\r\n",
+    Some(ConsoleRoutes::Admin(AdminRoutes::Dashboard)),
+    Some("/console/admin/")
+)]
+#[case(
+    b"GET /console/admin/ HTTP/1.1\r\n\r\n",
+    Some(ConsoleRoutes::Admin(AdminRoutes::Dashboard)),
+    Some("/console/admin/")
+)]
+#[case(
+    b"GET /console/admin/users/ada HTTP/1.1\r\n\r\n",
+    Some(ConsoleRoutes::Admin(AdminRoutes::GetUser { name: "ada" })),
+    Some("/console/admin/users/{name}")
+)]
+#[case(
+    b"GET /console/metrics/cpu HTTP/1.1\r\n\r\n",
+    Some(ConsoleRoutes::Metrics(MetricsRoute("/cpu"))),
+    None
+)]
+#[case(
+    b"GET /console/metrics HTTP/1.1\r\n\r\n",
+    Some(ConsoleRoutes::Metrics(MetricsRoute("/"))),
+    None
+)]
+#[case(b"GET /console/adminx HTTP/1.1\r\n\r\n", None, None)]
+#[case(b"GET /console/admin/other HTTP/1.1\r\n\r\n", None, None)]
+#[case(b"GET /admin/users/ada HTTP/1.1\r\n\r\n", None, None)]
+#[case(b"POST /console/metrics/cpu HTTP/1.1\r\n\r\n", None, None)]
+fn test_mounted_routes(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<ConsoleRoutes>,
+    #[case] expected_pattern: Option<&str>,
+) {
+    let result = ConsoleRoutes::resolve(input).map(|result| (result.route, result.pattern));
+    assert_eq!(result, Ok((expected_route, expected_pattern)));
+}
+
+#[test]
+fn test_mounted_to_path() {
+    assert_eq!(
+        ConsoleRoutes::Admin(AdminRoutes::GetUser { name: "ada" }).to_path(),
+        "/console/admin/users/ada"
+    );
+    assert_eq!(
+        ConsoleRoutes::Metrics(MetricsRoute("/cpu")).to_path(),
+        "/console/metrics/cpu"
+    );
+    assert_eq!(
+        ConsoleRoutes::PATTERNS,
+        &[
+            "/console/",
+            "/console/admin/",
+            "/console/admin/users/{name}"
+        ]
+    );
+}
+
+#[rstest]
+#[case(
+    b"GET /tenants/acme/users HTTP/1.1\r\n\r\n",
+    Some(TenantRoutes::GetUsers { tenant: "acme" }),
+    Some("/tenants/{tenant}/users")
+)]
+#[case(
+    b"GET /tenants/acme/users/42 HTTP/1.1\r\n\r\n",
+    Some(TenantRoutes::GetUser { tenant: "acme", id: 42 }),
+    Some("/tenants/{tenant}/users/{id}")
+)]
+#[case(b"OPTIONS * HTTP/1.1\r\n\r\n", Some(TenantRoutes::Server), Some("*"))]
+#[case(b"GET /tenants/acme HTTP/1.1\r\n\r\n", None, None)]
+#[case(b"GET /tenants/users HTTP/1.1\r\n\r\n", None, None)]
+#[case(b"GET /users/42 HTTP/1.1\r\n\r\n", None, None)]
+fn test_prefix_variable_routes(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<TenantRoutes>,
+    #[case] expected_pattern: Option<&str>,
+) {
+    let result = TenantRoutes::resolve(input).map(|result| (result.route, result.pattern));
+    assert_eq!(result, Ok((expected_route, expected_pattern)));
+}
+
+#[test]
+fn test_prefix_variable_to_path() {
+    assert_eq!(
+        TenantRoutes::GetUser {
+            tenant: "acme",
+            id: 42
+        }
+        .to_path(),
+        "/tenants/acme/users/42"
+    );
+    assert_eq!(
+        TenantRoutes::ROUTES,
+        &[
+            (Method::Get, "/tenants/{tenant}/users"),
+            (Method::Get, "/tenants/{tenant}/users/{id}"),
+            (Method::Options, "*"),
+        ]
+    );
+}
+
+#[rstest]
+#[case(
+    b"GET /docs/intro HTTP/1.1\r\n\r\n",
+    Some(DocumentRoutes::GetDocument { name: "intro" }),
+    Method::Get
+)]
+#[case(
+    b"HEAD /docs/intro HTTP/1.1\r\n\r\n",
+    Some(DocumentRoutes::GetDocument { name: "intro" }),
+    Method::Head
+)]
+#[case(
+    b"HEAD /docs/latest HTTP/1.1\r\n\r\n",
+    Some(DocumentRoutes::HeadLatest),
+    Method::Head
+)]
+#[case(
+    b"GET /docs/latest HTTP/1.1\r\n\r\n",
+    Some(DocumentRoutes::GetDocument { name: "latest" }),
+    Method::Get
+)]
+#[case(
+    b"HEAD /other HTTP/1.1\r\n\r\n",
+    Some(DocumentRoutes::NotFound),
+    Method::Head
+)]
+#[case(b"PUT /other HTTP/1.1\r\n\r\n", None, Method::Put)]
+fn test_head_implies_get(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<DocumentRoutes>,
+    #[case] expected_method: Method,
+) {
+    let result = DocumentRoutes::resolve(input).map(|result| (result.route, result.method));
+    assert_eq!(result, Ok((expected_route, expected_method)));
+}
+
+#[test]
+fn test_head_without_head_implies_get() {
+    let result = MixedSegmentRoutes::resolve(b"HEAD /files/a.json HTTP/1.1\r\n\r\n");
+    assert_eq!(result.map(|result| result.route), Ok(None));
+}
+
+#[rstest]
+#[case(
+    "GET /files/é.json HTTP/1.1\r\n\r\n".as_bytes(),
+    Some(MixedSegmentRoutes::GetJsonFile { name: "é" })
+)]
+#[case(
+    "GET /items/42;é HTTP/1.1\r\n\r\n".as_bytes(),
+    Some(MixedSegmentRoutes::GetItemRevision { id: 42, rev: "é" })
+)]
+#[case(
+    "GET /files/.json HTTP/1.1\r\n\r\n".as_bytes(),
+    Some(MixedSegmentRoutes::GetJsonFile { name: "" })
+)]
+#[case(b"GET /files/\xC3.json HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /files/\xC3\xA9\xC3 HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /items/\xE2\x82;1 HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /v HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /items/; HTTP/1.1\r\n\r\n", None)]
+fn test_multibyte_capture_boundaries(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<MixedSegmentRoutes>,
+) {
+    let result = MixedSegmentRoutes::resolve(input).map(|result| result.route);
+    assert!(matches!(result, Ok(_) | Err(Error::NonUtf8Path)));
+    assert_eq!(result.ok().flatten(), expected_route);
+}
+
+#[rstest]
+#[case(
+    "GET /prices/12€ HTTP/1.1\r\n\r\n".as_bytes(),
+    Some(CaseInsensitiveMixedRoutes::GetPrice { amount: 12 })
+)]
+#[case(b"GET /prices/12\xE2\x82 HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /prices/\xE2\x82\xAC\xE2 HTTP/1.1\r\n\r\n", None)]
+#[case(b"GET /prices/\xE2 HTTP/1.1\r\n\r\n", None)]
+fn test_case_insensitive_multibyte_suffix(
+    #[case] input: &[u8],
+    #[case] expected_route: Option<CaseInsensitiveMixedRoutes>,
+) {
+    let result = CaseInsensitiveMixedRoutes::resolve(input).map(|result| result.route);
+    assert_eq!(result.ok().flatten(), expected_route);
+}
+
+/// Resolves `path` with every router whose generated matchers slice captures, mixed segments,
+/// prefixes and mount paths, which must never panic.
+fn resolve_everywhere(path: &[u8]) {
+    let mut request = b"GET ".to_vec();
+    request.extend_from_slice(path);
+    request.extend_from_slice(b" HTTP/1.1\r\n\r\n");
+    let _ = AllRoutes::resolve(&request);
+    let _ = VersionedRoutes::resolve(&request);
+    let _ = MixedSegmentRoutes::resolve(&request);
+    let _ = CaseInsensitiveMixedRoutes::resolve(&request);
+    let _ = SpecificityRoutes::resolve(&request);
+    let _ = ArchiveRoutes::resolve(&request);
+    let _ = ConsoleRoutes::resolve(&request);
+    let _ = FileRoutes::resolve(&request);
+    let _ = GeometryRoutes::resolve(&request);
+    let _ = TenantRoutes::resolve(&request);
+    let _ = LegacyRoutes::resolve(&request);
+    let _ = SharedPrefixRoutes::resolve(&request);
+}
+
+#[test]
+fn test_adversarial_paths_never_panic() {
+    let paths: [&[u8]; 12] = [
+        b"/files/a.json",
+        b"/files/backup.tar.gz",
+        b"/items/42;r7",
+        b"/v2/users",
+        b"/reports/2023-11/summary",
+        "/prices/12€".as_bytes(),
+        b"/users/me/posts",
+        b"/console/admin/metrics/cpu",
+        b"/posts/2024/03/01",
+        b"/tenants/acme/users/42",
+        b"/legacy/Users/42",
+        b"/v1/api/users/42/roles/admin",
+    ];
+    let fragments: [&[u8]; 8] = [
+        b"",
+        b"\xC3",
+        b"\xC3\xA9",
+        b"\xE2\x82",
+        b"\xF0\x9F\x98\x80",
+        b"/",
+        b".",
+        b";",
+    ];
+    for path in paths {
+        for end in 0..=path.len() {
+            resolve_everywhere(&path[..end]);
+            for fragment in fragments {
+                let mut mangled = path[..end].to_vec();
+                mangled.extend_from_slice(fragment);
+                mangled.extend_from_slice(&path[end..]);
+                resolve_everywhere(&mangled);
+            }
+        }
+    }
+}
@@ -0,0 +1,142 @@
+#![cfg(feature = "openapi")]
+
+use front_line::{
+    FrontLine, Method, OpenApi, OpenApiParameter, OpenApiRoute, ParameterLocation, ParseError,
+    Router, SchemaType,
+};
+use serde_json::json;
+
+#[derive(PartialEq, Debug, FrontLine)]
+#[prefix("/api")]
+enum ApiRoutes<'a> {
+    #[get("/users")]
+    GetAllUsers,
+    #[post("/users")]
+    CreateUser,
+    #[get("/users/{id}")]
+    GetUser { id: u32 },
+    #[get("/users/{id}/roles/{role}")]
+    GetUserRole { id: u32, role: &'a str },
+    #[put("/users/{id}/roles/{role}")]
+    UpdateUserRole { id: u32, role: &'a str },
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+enum SearchRoutes<'a> {
+    #[get("/search?{q}&{page}&{exact}")]
+    Search {
+        q: &'a str,
+        page: Option<u32>,
+        exact: Option<bool>,
+    },
+    #[get(r"/files/{version:\d+}/{*path}")]
+    GetFile(f64, String),
+    #[get("/orders/{id}")]
+    GetOrder { id: Result<u32, ParseError> },
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+#[prefix("/v1", "/v2")]
+enum AllRoutes<'a> {
+    #[options("*")]
+    Server,
+    #[flatten]
+    Api(ApiRoutes<'a>),
+}
+
+fn path_parameter(name: &'static str, schema: SchemaType) -> OpenApiParameter {
+    OpenApiParameter {
+        name,
+        location: ParameterLocation::Path,
+        schema,
+        required: true,
+    }
+}
+
+#[test]
+fn test_openapi_paths() {
+    let id =
+        json!({ "name": "id", "in": "path", "required": true, "schema": { "type": "integer" } });
+    let role =
+        json!({ "name": "role", "in": "path", "required": true, "schema": { "type": "string" } });
+    assert_eq!(
+        ApiRoutes::openapi_paths(),
+        json!({
+            "/api/users": {
+                "get": { "parameters": [] },
+                "post": { "parameters": [] },
+            },
+            "/api/users/{id}": {
+                "get": { "parameters": [id] },
+            },
+            "/api/users/{id}/roles/{role}": {
+                "get": { "parameters": [id, role] },
+                "put": { "parameters": [id, role] },
+            },
+        })
+    );
+}
+
+#[test]
+fn test_openapi_parameters() {
+    let query_parameter = |name, schema, required| OpenApiParameter {
+        name,
+        location: ParameterLocation::Query,
+        schema,
+        required,
+    };
+    assert_eq!(
+        SearchRoutes::openapi_routes(),
+        vec![
+            OpenApiRoute {
+                method: Method::Get,
+                path: String::from("/search"),
+                parameters: vec![
+                    query_parameter("q", SchemaType::String, true),
+                    query_parameter("page", SchemaType::Integer, false),
+                    query_parameter("exact", SchemaType::Boolean, false),
+                ],
+            },
+            OpenApiRoute {
+                method: Method::Get,
+                path: String::from("/files/{version}/{path}"),
+                parameters: vec![
+                    path_parameter("version", SchemaType::Number),
+                    path_parameter("path", SchemaType::String),
+                ],
+            },
+            OpenApiRoute {
+                method: Method::Get,
+                path: String::from("/orders/{id}"),
+                parameters: vec![path_parameter("id", SchemaType::Integer)],
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_openapi_matches_declared_routes() {
+    let routes = AllRoutes::openapi_routes();
+    let declared: Vec<_> = routes
+        .iter()
+        .map(|route| (route.method, route.path.as_str()))
+        .collect();
+    assert_eq!(
+        declared,
+        vec![
+            (Method::Options, "*"),
+            (Method::Get, "/v1/api/users"),
+            (Method::Post, "/v1/api/users"),
+            (Method::Get, "/v1/api/users/{id}"),
+            (Method::Get, "/v1/api/users/{id}/roles/{role}"),
+            (Method::Put, "/v1/api/users/{id}/roles/{role}"),
+            (Method::Options, "*"),
+            (Method::Get, "/v2/api/users"),
+            (Method::Post, "/v2/api/users"),
+            (Method::Get, "/v2/api/users/{id}"),
+            (Method::Get, "/v2/api/users/{id}/roles/{role}"),
+            (Method::Put, "/v2/api/users/{id}/roles/{role}"),
+        ]
+    );
+    assert_eq!(routes.len(), AllRoutes::ROUTES.len());
+}
@@ -0,0 +1,118 @@
+#![cfg(feature = "resolve-all")]
+
+use front_line::{Error, FrontLine, Router};
+use rstest::rstest;
+
+#[derive(PartialEq, Debug, FrontLine)]
+#[prefix("/api")]
+enum OverlappingRoutes<'a> {
+    #[get("/users/me")]
+    GetMe,
+    #[get("/users/{name}")]
+    GetUserByName { name: &'a str },
+    #[get("/users/{*rest}")]
+    GetUserPath { rest: &'a str },
+    #[fallback]
+    NotFound,
+}
+
+#[rstest]
+#[case(
+    b"GET /api/users/me HTTP/1.1\r\n\r\n",
+    vec![
+        OverlappingRoutes::GetMe,
+        OverlappingRoutes::GetUserByName { name: "me" },
+        OverlappingRoutes::GetUserPath { rest: "me" },
+    ]
+)]
+#[case(
+    b"GET /api/users/42 HTTP/1.1\r\n\r\n",
+    vec![
+        OverlappingRoutes::GetUserByName { name: "42" },
+        OverlappingRoutes::GetUserPath { rest: "42" },
+    ]
+)]
+#[case(
+    b"GET /api/users/42/roles HTTP/1.1\r\n\r\n",
+    vec![OverlappingRoutes::GetUserPath { rest: "42/roles" }]
+)]
+#[case(b"HEAD /api/users/me HTTP/1.1\r\n\r\n", vec![OverlappingRoutes::NotFound])]
+#[case(b"GET /api/other HTTP/1.1\r\n\r\n", vec![OverlappingRoutes::NotFound])]
+#[case(b"GET /other HTTP/1.1\r\n\r\n", vec![OverlappingRoutes::NotFound])]
+fn test_overlapping_routes(
+    #[case] request: &'static [u8],
+    #[case] expected: Vec<OverlappingRoutes<'static>>,
+) {
+    assert_eq!(OverlappingRoutes::resolve_all(request).unwrap(), expected);
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+#[prefix("/v1", "/v2")]
+#[head_implies_get]
+enum VersionedRoutes<'a> {
+    #[get("/items/{id}")]
+    GetItem { id: u32 },
+    #[get("/items/{*path}")]
+    GetItemPath { path: &'a str },
+}
+
+#[rstest]
+#[case(
+    b"GET /v2/items/7 HTTP/1.1\r\n\r\n",
+    vec![VersionedRoutes::GetItem { id: 7 }, VersionedRoutes::GetItemPath { path: "7" }]
+)]
+#[case(
+    b"HEAD /v1/items/7 HTTP/1.1\r\n\r\n",
+    vec![VersionedRoutes::GetItem { id: 7 }, VersionedRoutes::GetItemPath { path: "7" }]
+)]
+#[case(
+    b"GET /v1/items/abc HTTP/1.1\r\n\r\n",
+    vec![VersionedRoutes::GetItemPath { path: "abc" }]
+)]
+#[case(b"GET /v3/items/7 HTTP/1.1\r\n\r\n", vec![])]
+fn test_prefixed_routes(
+    #[case] request: &'static [u8],
+    #[case] expected: Vec<VersionedRoutes<'static>>,
+) {
+    assert_eq!(VersionedRoutes::resolve_all(request).unwrap(), expected);
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+enum AppRoutes<'a> {
+    #[flatten]
+    Api(OverlappingRoutes<'a>),
+    #[get("/api/users/{name}")]
+    GetProfile { name: &'a str },
+}
+
+#[test]
+fn test_flattened_routes() {
+    let routes = AppRoutes::resolve_all(b"GET /api/users/me HTTP/1.1\r\n\r\n").unwrap();
+    assert_eq!(
+        routes,
+        vec![
+            AppRoutes::GetProfile { name: "me" },
+            AppRoutes::Api(OverlappingRoutes::GetMe),
+            AppRoutes::Api(OverlappingRoutes::GetUserByName { name: "me" }),
+            AppRoutes::Api(OverlappingRoutes::GetUserPath { rest: "me" }),
+        ]
+    );
+}
+
+#[test]
+fn test_resolve_matches_first() {
+    let request = b"GET /api/users/42 HTTP/1.1\r\n\r\n";
+    let first = OverlappingRoutes::resolve(request).unwrap().route;
+    assert_eq!(
+        first.as_ref(),
+        OverlappingRoutes::resolve_all(request).unwrap().first()
+    );
+}
+
+#[test]
+fn test_invalid_request() {
+    assert_eq!(
+        OverlappingRoutes::resolve_all(b"GET /api/users/me"),
+        Err(Error::MissingHeaderTerminator)
+    );
+}
@@ -0,0 +1,84 @@
+//! Feeds arbitrary bytes to `Router::resolve` and its variants, checking that they never panic
+//! and that whatever they return points back into the request.
+
+#![no_main]
+
+use front_line::{FrontLine, Router, RouterResult};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(PartialEq, Debug, FrontLine)]
+enum UserRoutes<'a> {
+    #[get("/")]
+    Index,
+    #[get("/users/{id}")]
+    GetUser { id: u32 },
+    #[get("/users/{name}/roles/{role}")]
+    GetUserRole { name: &'a str, role: &'a str },
+    #[get("/people/{name}")]
+    GetPerson {
+        #[decode]
+        name: String,
+    },
+    #[get("/files/{name}.json")]
+    GetJson { name: &'a str },
+    #[get("/items/{id};{rev}")]
+    GetItem { id: u32, rev: &'a str },
+    #[get("/archive/{year}/{month?}")]
+    GetArchive { year: u16, month: Option<u8> },
+    #[get("/search?{q}&{page}")]
+    Search { q: &'a str, page: u32 },
+    #[get("/raw/{bytes}")]
+    GetRaw { bytes: &'a [u8] },
+}
+
+#[derive(PartialEq, Debug, FrontLine)]
+#[prefix("/v1")]
+#[case_insensitive]
+enum VersionedRoutes<'a> {
+    #[flatten]
+    Users(UserRoutes<'a>),
+    #[any("/assets/{*path}")]
+    GetAsset { path: &'a str },
+    #[options("*")]
+    Options,
+}
+
+/// Whether `slice` lies within `request`, which every slice of a `RouterResult` must.
+fn is_within(request: &[u8], slice: &[u8]) -> bool {
+    let start = request.as_ptr() as usize;
+    let slice_start = slice.as_ptr() as usize;
+    slice.is_empty() || (slice_start >= start && slice_start + slice.len() <= start + request.len())
+}
+
+fn check<T>(request: &[u8], result: &RouterResult<'_, T>) {
+    assert!(is_within(request, result.raw_headers));
+    assert!(is_within(request, result.raw_body));
+    assert!(is_within(request, result.query.as_bytes()));
+}
+
+fuzz_target!(|request: &[u8]| {
+    if let Ok(result) = UserRoutes::resolve(request) {
+        check(request, &result);
+        let (spanned, spans) = UserRoutes::resolve_spans(request).expect("resolve accepted it");
+        assert_eq!(spanned, result);
+        assert_eq!(&request[spans.headers], result.raw_headers);
+        assert_eq!(&request[spans.body], result.raw_body);
+        assert_eq!(&request[spans.query], result.query.as_bytes());
+        let _ = &request[spans.method];
+        let _ = &request[spans.path];
+        let _ = &request[spans.version];
+    }
+    if let Ok(result) = VersionedRoutes::resolve(request) {
+        check(request, &result);
+    }
+    if let Ok(result) = VersionedRoutes::resolve_non_utf8(request) {
+        check(request, &result);
+    }
+    if let Ok(result) = VersionedRoutes::resolve_lenient(request) {
+        check(request, &result);
+    }
+    if let Ok(result) = VersionedRoutes::resolve_with_host(request) {
+        check(request, &result);
+    }
+    let _ = VersionedRoutes::resolve_partial(request);
+});